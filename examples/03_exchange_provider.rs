@@ -3,6 +3,7 @@ use alloy::signers::local::PrivateKeySigner;
 use ferrofluid::constants::TIF_GTC;
 use ferrofluid::types::requests::OrderRequest;
 use ferrofluid::{signers::AlloySigner, ExchangeProvider};
+use rust_decimal_macros::dec;
 use uuid::Uuid;
 
 #[tokio::main]
@@ -25,10 +26,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Example 1: Create an order with client order ID
     let cloid = Uuid::new_v4();
     let _order = OrderRequest::limit(
-        0,         // BTC-USD asset ID
-        true,      // buy
-        "45000.0", // price
-        "0.01",    // size
+        0,             // BTC-USD asset ID
+        true,          // buy
+        dec!(45000.0), // price
+        dec!(0.01),    // size
         TIF_GTC,
     );
 
@@ -50,17 +51,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\nOrderBuilder created successfully!");
 
     // Example 3: Create bulk orders with mixed tracking
-    let orders_with_ids = vec![
+    let orders_with_ids = [
         (
-            OrderRequest::limit(0, true, "44900.0", "0.01", TIF_GTC),
+            OrderRequest::limit(0, true, dec!(44900.0), dec!(0.01), TIF_GTC),
             Some(Uuid::new_v4()),
         ),
         (
-            OrderRequest::limit(0, true, "44800.0", "0.01", TIF_GTC),
+            OrderRequest::limit(0, true, dec!(44800.0), dec!(0.01), TIF_GTC),
             None,
         ),
         (
-            OrderRequest::limit(0, true, "44700.0", "0.01", TIF_GTC),
+            OrderRequest::limit(0, true, dec!(44700.0), dec!(0.01), TIF_GTC),
             Some(Uuid::new_v4()),
         ),
     ];
@@ -1,23 +1,67 @@
 // ==================== Network Configuration ====================
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Network {
     Mainnet,
     Testnet,
+    /// Arbitrary endpoints, e.g. a local mock server or a private proxy.
+    /// Plain `http://` and `ws://` URLs are accepted.
+    Custom {
+        /// Base API URL, without the `/info` or `/exchange` suffix
+        api_url: String,
+        /// Full WebSocket URL, including the `/ws` path
+        ws_url: String,
+        /// Whether signatures should target mainnet
+        is_mainnet: bool,
+    },
 }
 
 impl Network {
-    pub fn api_url(&self) -> &'static str {
+    /// Create a custom network from arbitrary endpoints
+    pub fn custom(
+        api_url: impl Into<String>,
+        ws_url: impl Into<String>,
+        is_mainnet: bool,
+    ) -> Self {
+        Network::Custom {
+            api_url: api_url.into().trim_end_matches('/').to_string(),
+            ws_url: ws_url.into(),
+            is_mainnet,
+        }
+    }
+
+    pub fn api_url(&self) -> &str {
         match self {
             Network::Mainnet => "https://api.hyperliquid.xyz",
             Network::Testnet => "https://api.hyperliquid-testnet.xyz",
+            Network::Custom { api_url, .. } => api_url,
         }
     }
 
-    pub fn ws_url(&self) -> &'static str {
+    pub fn ws_url(&self) -> &str {
         match self {
             Network::Mainnet => "wss://api.hyperliquid.xyz/ws",
             Network::Testnet => "wss://api.hyperliquid-testnet.xyz/ws",
+            Network::Custom { ws_url, .. } => ws_url,
+        }
+    }
+
+    /// URL of the `/info` endpoint
+    pub fn info_url(&self) -> String {
+        format!("{}/info", self.api_url())
+    }
+
+    /// URL of the `/exchange` endpoint
+    pub fn exchange_url(&self) -> String {
+        format!("{}/exchange", self.api_url())
+    }
+
+    /// Whether actions on this network are signed for mainnet
+    pub fn is_mainnet(&self) -> bool {
+        match self {
+            Network::Mainnet => true,
+            Network::Testnet => false,
+            Network::Custom { is_mainnet, .. } => *is_mainnet,
         }
     }
 }
@@ -53,6 +97,7 @@ pub const WEIGHT_CANDLES: u32 = 2;
 pub const WEIGHT_FUNDING_HISTORY: u32 = 2;
 pub const WEIGHT_TOKEN_BALANCES: u32 = 1;
 pub const WEIGHT_REFERRAL: u32 = 1;
+pub const WEIGHT_INFO_DEFAULT: u32 = 2;
 
 // Exchange endpoints (these have higher weights)
 pub const WEIGHT_PLACE_ORDER: u32 = 3;
@@ -60,11 +105,13 @@ pub const WEIGHT_CANCEL_ORDER: u32 = 2;
 pub const WEIGHT_MODIFY_ORDER: u32 = 3;
 pub const WEIGHT_BULK_ORDER: u32 = 10;
 pub const WEIGHT_BULK_CANCEL: u32 = 8;
+pub const WEIGHT_EXCHANGE_DEFAULT: u32 = 1;
 
 // ==================== Rate Limit Configuration ====================
 
 pub const RATE_LIMIT_MAX_TOKENS: u32 = 1200;
-pub const RATE_LIMIT_REFILL_RATE: u32 = 600; // per minute
+pub const RATE_LIMIT_REFILL_RATE: u32 = 1200; // per minute
+pub const RATE_LIMIT_QUEUE_TIMEOUT_MS: u64 = 30_000;
 
 // ==================== Time Constants ====================
 
@@ -78,3 +125,36 @@ pub const TIF_ALO: &str = "Alo";
 
 pub const TPSL_TP: &str = "tp";
 pub const TPSL_SL: &str = "sl";
+
+// Cancels per action sent by `cancel_all`
+pub const MAX_CANCELS_PER_ACTION: usize = 100;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_urls() {
+        assert_eq!(
+            Network::Testnet.exchange_url(),
+            "https://api.hyperliquid-testnet.xyz/exchange"
+        );
+        assert_eq!(
+            Network::Mainnet.info_url(),
+            "https://api.hyperliquid.xyz/info"
+        );
+        assert!(Network::Mainnet.is_mainnet());
+        assert!(!Network::Testnet.is_mainnet());
+    }
+
+    #[test]
+    fn test_custom_network() {
+        let network =
+            Network::custom("http://127.0.0.1:3001/", "ws://127.0.0.1:3001/ws", false);
+
+        assert_eq!(network.info_url(), "http://127.0.0.1:3001/info");
+        assert_eq!(network.exchange_url(), "http://127.0.0.1:3001/exchange");
+        assert_eq!(network.ws_url(), "ws://127.0.0.1:3001/ws");
+        assert!(!network.is_mainnet());
+    }
+}
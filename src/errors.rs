@@ -43,4 +43,7 @@ pub enum HyperliquidError {
 
     #[error("invalid request: {0}")]
     InvalidRequest(String),
+
+    #[error("storage error: {0}")]
+    Storage(String),
 }
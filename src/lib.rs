@@ -2,6 +2,8 @@ pub mod constants;
 pub mod errors;
 pub mod providers;
 pub mod signers;
+#[cfg(feature = "testkit")]
+pub mod testkit;
 pub mod types;
 pub mod utils;
 
@@ -0,0 +1,214 @@
+//! Local tracking of Hyperliquid's address-based request budget
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use alloy::primitives::Address;
+use parking_lot::Mutex;
+
+use crate::types::info_types::UserRateLimit;
+
+/// What to do when the remaining budget falls below the threshold
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LowBudgetPolicy {
+    /// Send the request and log a warning
+    Warn,
+    /// Fail with `HyperliquidError::RateLimited` instead of sending
+    Block,
+}
+
+/// Configuration for [`AddressBudget`]
+#[derive(Clone, Debug)]
+pub struct AddressBudgetConfig {
+    /// How often to resync the counters from `userRateLimit`
+    pub sync_interval: Duration,
+    /// Remaining requests below which the budget counts as low
+    pub threshold: u64,
+    pub policy: LowBudgetPolicy,
+    /// Weight to buy with `reserveRequestWeight` when the budget runs low,
+    /// before falling back to `policy` (None to never spend). Raised to at
+    /// least `threshold`, so one purchase lifts the budget out of the low zone.
+    pub reserve_weight: Option<u64>,
+}
+
+impl Default for AddressBudgetConfig {
+    fn default() -> Self {
+        Self {
+            sync_interval: Duration::from_secs(60),
+            threshold: 100,
+            policy: LowBudgetPolicy::Warn,
+            reserve_weight: None,
+        }
+    }
+}
+
+/// Requests used and allowed for one address under the address-based limit
+///
+/// Hyperliquid allows each address a number of exchange requests that grows
+/// with its traded volume. Requests are counted locally as they are sent and
+/// the counters are replaced by the exchange's own whenever they are synced.
+#[derive(Debug)]
+pub struct AddressBudget {
+    user: Address,
+    config: AddressBudgetConfig,
+    used: AtomicU64,
+    cap: AtomicU64,
+    synced: AtomicBool,
+    syncing: AtomicBool,
+    last_sync: Mutex<Option<Instant>>,
+}
+
+impl AddressBudget {
+    /// Create a budget for `user`, unknown until first synced
+    pub fn new(user: Address, mut config: AddressBudgetConfig) -> Self {
+        config.reserve_weight = config
+            .reserve_weight
+            .map(|weight| weight.max(config.threshold));
+        Self {
+            user,
+            config,
+            used: AtomicU64::new(0),
+            cap: AtomicU64::new(u64::MAX),
+            synced: AtomicBool::new(false),
+            syncing: AtomicBool::new(false),
+            last_sync: Mutex::new(None),
+        }
+    }
+
+    /// Address whose budget is tracked
+    pub fn user(&self) -> Address {
+        self.user
+    }
+
+    pub fn config(&self) -> &AddressBudgetConfig {
+        &self.config
+    }
+
+    /// Requests used so far
+    pub fn used(&self) -> u64 {
+        self.used.load(Ordering::SeqCst)
+    }
+
+    /// Requests allowed, `u64::MAX` until first synced
+    pub fn cap(&self) -> u64 {
+        self.cap.load(Ordering::SeqCst)
+    }
+
+    /// Requests left before the address is rate limited
+    pub fn remaining(&self) -> u64 {
+        self.cap().saturating_sub(self.used())
+    }
+
+    /// Whether the remaining budget is below the threshold
+    pub fn is_low(&self) -> bool {
+        self.synced.load(Ordering::SeqCst) && self.remaining() < self.config.threshold
+    }
+
+    /// Whether the last sync attempt is older than the sync interval
+    pub fn needs_sync(&self) -> bool {
+        match *self.last_sync.lock() {
+            Some(last) => last.elapsed() >= self.config.sync_interval,
+            None => true,
+        }
+    }
+
+    /// Claim the next sync if one is due and none is in flight
+    ///
+    /// The caller must finish with [`Self::sync`] or [`Self::sync_failed`].
+    pub fn try_begin_sync(&self) -> bool {
+        self.needs_sync()
+            && self
+                .syncing
+                .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+    }
+
+    /// Replace the local counters with the exchange's
+    pub fn sync(&self, limit: &UserRateLimit) {
+        self.used.store(limit.n_requests_used, Ordering::SeqCst);
+        self.cap.store(limit.n_requests_cap, Ordering::SeqCst);
+        self.synced.store(true, Ordering::SeqCst);
+        self.finish_sync();
+    }
+
+    /// Record a failed sync, so the next attempt waits a full interval
+    pub fn sync_failed(&self) {
+        self.finish_sync();
+    }
+
+    fn finish_sync(&self) {
+        *self.last_sync.lock() = Some(Instant::now());
+        self.syncing.store(false, Ordering::SeqCst);
+    }
+
+    /// Count `requests` sent
+    pub fn record(&self, requests: u64) {
+        self.used.fetch_add(requests, Ordering::SeqCst);
+    }
+
+    /// Count `weight` bought with `reserveRequestWeight`
+    pub fn add_capacity(&self, weight: u64) {
+        let _ = self
+            .cap
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |cap| {
+                Some(cap.saturating_add(weight))
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    fn limit(used: u64, cap: u64) -> UserRateLimit {
+        UserRateLimit {
+            cum_vlm: Decimal::ZERO,
+            n_requests_used: used,
+            n_requests_cap: cap,
+        }
+    }
+
+    #[test]
+    fn test_counts_locally_between_syncs() {
+        let budget = AddressBudget::new(Address::ZERO, AddressBudgetConfig::default());
+        assert!(budget.needs_sync());
+        assert!(!budget.is_low());
+
+        budget.sync(&limit(9_850, 10_000));
+        assert!(!budget.needs_sync());
+        assert_eq!(budget.remaining(), 150);
+        assert!(!budget.is_low());
+
+        budget.record(60);
+        assert_eq!(budget.used(), 9_910);
+        assert!(budget.is_low());
+
+        budget.add_capacity(1_000);
+        assert_eq!(budget.remaining(), 1_090);
+        assert!(!budget.is_low());
+
+        // The exchange's counters win on the next sync
+        budget.sync(&limit(9_000, 10_000));
+        assert_eq!(budget.remaining(), 1_000);
+    }
+
+    #[test]
+    fn test_one_sync_at_a_time() {
+        let config = AddressBudgetConfig {
+            reserve_weight: Some(10),
+            ..AddressBudgetConfig::default()
+        };
+        let budget = AddressBudget::new(Address::ZERO, config);
+        assert_eq!(budget.config().reserve_weight, Some(100));
+
+        assert!(budget.try_begin_sync());
+        assert!(!budget.try_begin_sync());
+
+        // A failed attempt also waits out the interval
+        budget.sync_failed();
+        assert!(!budget.needs_sync());
+        assert!(!budget.try_begin_sync());
+    }
+}
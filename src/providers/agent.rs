@@ -5,10 +5,16 @@ use std::time::{Duration, Instant};
 
 use alloy::primitives::Address;
 use alloy::signers::local::PrivateKeySigner;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
 use crate::{
-    errors::HyperliquidError, providers::nonce::NonceManager, signers::HyperliquidSigner,
+    errors::HyperliquidError,
+    providers::{
+        address_budget::AddressBudget, agent_store::AgentStore, info::RateLimiter,
+        nonce::NonceManager, retry::RetryPolicy, transport::Transport,
+    },
+    signers::HyperliquidSigner,
     Network,
 };
 
@@ -27,7 +33,7 @@ pub struct AgentWallet {
     pub status: AgentStatus,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum AgentStatus {
     /// Agent is active and healthy
     Active,
@@ -94,6 +100,16 @@ pub struct AgentManager<S: HyperliquidSigner> {
     config: AgentConfig,
     /// Network for agent operations
     network: Network,
+    /// Persists agents across restarts
+    store: Option<Arc<dyn AgentStore>>,
+    /// Transport for agent approvals, the default when None
+    transport: Option<Arc<dyn Transport>>,
+    /// Rate limiter agent approvals draw weight from, a private one when None
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// Retry policy for agent approvals, the default when None
+    retry_policy: Option<RetryPolicy>,
+    /// Address budget agent approvals count against
+    budget: Option<Arc<AddressBudget>>,
 }
 
 impl<S: HyperliquidSigner + Clone> AgentManager<S> {
@@ -104,6 +120,87 @@ impl<S: HyperliquidSigner + Clone> AgentManager<S> {
             agents: Arc::new(RwLock::new(std::collections::HashMap::new())),
             config,
             network,
+            store: None,
+            transport: None,
+            rate_limiter: None,
+            retry_policy: None,
+            budget: None,
+        }
+    }
+
+    /// Create an agent manager that persists agents in `store`
+    ///
+    /// Stored agents that have not expired are reloaded, so a restart keeps
+    /// using the agents approved before it. Expired agents are removed.
+    pub async fn with_store(
+        master_signer: S,
+        config: AgentConfig,
+        network: Network,
+        store: Arc<dyn AgentStore>,
+    ) -> Result<Self, HyperliquidError> {
+        let ttl = config.ttl;
+        let agents = run_blocking(store.clone(), move |store| {
+            let mut agents = std::collections::HashMap::new();
+            for (name, agent) in store.load()? {
+                if agent.should_rotate(ttl) {
+                    store.remove(&name)?;
+                } else {
+                    agents.insert(name, agent);
+                }
+            }
+            Ok(agents)
+        })
+        .await?;
+
+        Ok(Self {
+            master_signer,
+            agents: Arc::new(RwLock::new(agents)),
+            config,
+            network,
+            store: Some(store),
+            transport: None,
+            rate_limiter: None,
+            retry_policy: None,
+            budget: None,
+        })
+    }
+
+    /// Send agent approvals through `transport`
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Draw agent approval weight from `rate_limiter`, e.g. the one of the
+    /// provider trading with the agents
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Retry failed agent approvals according to `policy`
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Count agent approvals against `budget` when it tracks the master account
+    pub fn with_address_budget(mut self, budget: Arc<AddressBudget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    async fn persist(
+        &self,
+        name: &str,
+        agent: AgentWallet,
+    ) -> Result<(), HyperliquidError> {
+        match &self.store {
+            Some(store) => {
+                let name = name.to_string();
+                run_blocking(store.clone(), move |store| store.save(&name, &agent)).await
+            }
+            None => Ok(()),
         }
     }
 
@@ -131,9 +228,13 @@ impl<S: HyperliquidSigner + Clone> AgentManager<S> {
             agents.insert(name.to_string(), agent_mut);
         }
 
-        // Create new agent
+        // Create new agent; nothing is kept unless the exchange approved it
         let new_agent = self.create_new_agent(name).await?;
         agents.insert(name.to_string(), new_agent.clone());
+        drop(agents);
+
+        // Saving under the same name replaces the rotated agent in the store
+        self.persist(name, new_agent.clone()).await?;
 
         Ok(new_agent)
     }
@@ -164,15 +265,33 @@ impl<S: HyperliquidSigner + Clone> AgentManager<S> {
         use crate::providers::RawExchangeProvider;
 
         // Create a temporary raw provider just for agent approval
-        let raw_provider = match self.network {
-            Network::Mainnet => RawExchangeProvider::mainnet(self.master_signer.clone()),
-            Network::Testnet => RawExchangeProvider::testnet(self.master_signer.clone()),
-        };
-
-        // Approve the agent
-        raw_provider.approve_agent(agent_address, name).await?;
+        let mut raw_provider = RawExchangeProvider::for_network(
+            self.master_signer.clone(),
+            self.network.clone(),
+        );
+        if let Some(transport) = &self.transport {
+            raw_provider = raw_provider.with_transport(transport.clone());
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            raw_provider = raw_provider.with_rate_limiter(rate_limiter.clone());
+        }
+        if let Some(policy) = &self.retry_policy {
+            raw_provider = raw_provider.with_retry_policy(policy.clone());
+        }
 
-        Ok(())
+        // Approve the agent, failing if the exchange rejects it
+        let response = raw_provider.approve_agent(agent_address, name).await?;
+        if let Some(budget) = &self.budget {
+            if budget.user() == self.master_signer.address() {
+                budget.record(1);
+            }
+        }
+        match response.error() {
+            Some(error) => Err(HyperliquidError::Unauthorized(format!(
+                "Agent approval rejected: {error}"
+            ))),
+            None => Ok(()),
+        }
     }
 
     /// Get all active agents
@@ -186,23 +305,64 @@ impl<S: HyperliquidSigner + Clone> AgentManager<S> {
     }
 
     /// Mark an agent as deregistered
-    pub async fn mark_deregistered(&self, name: &str) {
-        let mut agents = self.agents.write().await;
-        if let Some(agent) = agents.get_mut(name) {
-            agent.status = AgentStatus::Deregistered;
+    pub async fn mark_deregistered(&self, name: &str) -> Result<(), HyperliquidError> {
+        let agent = {
+            let mut agents = self.agents.write().await;
+            agents.get_mut(name).map(|agent| {
+                agent.status = AgentStatus::Deregistered;
+                agent.clone()
+            })
+        };
+        match agent {
+            Some(agent) => self.persist(name, agent).await,
+            None => Ok(()),
         }
     }
 
     /// Clean up deregistered agents
-    pub async fn cleanup_deregistered(&self) {
-        let mut agents = self.agents.write().await;
-        agents.retain(|_, agent| agent.status != AgentStatus::Deregistered);
+    pub async fn cleanup_deregistered(&self) -> Result<(), HyperliquidError> {
+        let deregistered: Vec<String> = {
+            let mut agents = self.agents.write().await;
+            let names: Vec<String> = agents
+                .iter()
+                .filter(|(_, agent)| agent.status == AgentStatus::Deregistered)
+                .map(|(name, _)| name.clone())
+                .collect();
+            for name in &names {
+                agents.remove(name);
+            }
+            names
+        };
+        match &self.store {
+            Some(store) => {
+                run_blocking(store.clone(), move |store| {
+                    deregistered.iter().try_for_each(|name| store.remove(name))
+                })
+                .await
+            }
+            None => Ok(()),
+        }
     }
 }
 
+// Store I/O and keystore crypto block, so they run off the async runtime
+async fn run_blocking<T, F>(
+    store: Arc<dyn AgentStore>,
+    f: F,
+) -> Result<T, HyperliquidError>
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn AgentStore) -> Result<T, HyperliquidError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || f(store.as_ref()))
+        .await
+        .map_err(|e| HyperliquidError::Storage(e.to_string()))?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::agent_store::FileAgentStore;
 
     #[test]
     fn test_agent_rotation_check() {
@@ -226,4 +386,39 @@ mod tests {
 
         assert!(nonce2 > nonce1);
     }
+
+    #[tokio::test]
+    async fn test_reloads_unexpired_agents_from_store() {
+        let dir = std::env::temp_dir()
+            .join(format!("ferrofluid-agents-{}", uuid::Uuid::new_v4()));
+        let store = Arc::new(FileAgentStore::new(&dir, "passphrase").unwrap());
+
+        let live = AgentWallet::new(PrivateKeySigner::random());
+        let mut expired = AgentWallet::new(PrivateKeySigner::random());
+        expired.created_at -= Duration::from_secs(2 * 60 * 60);
+        store.save("live", &live).unwrap();
+        store.save("expired", &expired).unwrap();
+
+        let config = AgentConfig {
+            ttl: Duration::from_secs(60 * 60),
+            ..Default::default()
+        };
+        let manager = AgentManager::with_store(
+            PrivateKeySigner::random(),
+            config,
+            Network::Testnet,
+            store.clone(),
+        )
+        .await
+        .unwrap();
+
+        let active = manager.get_active_agents().await;
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].0, "live");
+        assert_eq!(active[0].1.address, live.address);
+        // Expired agents are dropped from the store too
+        assert_eq!(store.load().unwrap().len(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -0,0 +1,218 @@
+//! Persistent storage for agent wallets
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::HyperliquidError;
+use crate::providers::agent::{AgentStatus, AgentWallet};
+
+/// Storage for agent wallets, so approved agents survive restarts
+pub trait AgentStore: Send + Sync {
+    /// Load all stored agents by name
+    fn load(&self) -> Result<Vec<(String, AgentWallet)>, HyperliquidError>;
+
+    /// Insert or update the agent stored under `name`
+    fn save(&self, name: &str, agent: &AgentWallet) -> Result<(), HyperliquidError>;
+
+    /// Remove the agent stored under `name`
+    fn remove(&self, name: &str) -> Result<(), HyperliquidError>;
+}
+
+/// Agent metadata kept next to the encrypted keys
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredAgent {
+    address: Address,
+    /// Unix time in milliseconds
+    created_at: u64,
+    status: AgentStatus,
+}
+
+const INDEX_FILE: &str = "agents.json";
+
+/// Stores agents in a directory as Web3 Secret Storage keystores
+///
+/// Each key is encrypted with the passphrase into `{address}.json`. Names,
+/// creation times and statuses are kept unencrypted in `agents.json`.
+pub struct FileAgentStore {
+    dir: PathBuf,
+    passphrase: String,
+}
+
+impl FileAgentStore {
+    /// Use `dir` for storage, creating it if needed
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        passphrase: impl Into<String>,
+    ) -> Result<Self, HyperliquidError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(storage_error)?;
+        Ok(Self {
+            dir,
+            passphrase: passphrase.into(),
+        })
+    }
+
+    fn key_file(address: Address) -> String {
+        format!("{address:#x}.json")
+    }
+
+    fn read_index(&self) -> Result<HashMap<String, StoredAgent>, HyperliquidError> {
+        match fs::read(self.dir.join(INDEX_FILE)) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+
+    fn write_index(
+        &self,
+        index: &HashMap<String, StoredAgent>,
+    ) -> Result<(), HyperliquidError> {
+        // Write then rename so a crash never leaves a truncated index
+        let tmp = self.dir.join(format!("{INDEX_FILE}.tmp"));
+        fs::write(&tmp, serde_json::to_vec_pretty(index)?).map_err(storage_error)?;
+        fs::rename(tmp, self.dir.join(INDEX_FILE)).map_err(storage_error)
+    }
+}
+
+impl AgentStore for FileAgentStore {
+    fn load(&self) -> Result<Vec<(String, AgentWallet)>, HyperliquidError> {
+        let mut agents = Vec::new();
+        for (name, stored) in self.read_index()? {
+            let path = self.dir.join(Self::key_file(stored.address));
+            let signer = PrivateKeySigner::decrypt_keystore(path, &self.passphrase)
+                .map_err(|e| {
+                    HyperliquidError::Storage(format!(
+                        "Failed to decrypt agent {name}: {e}"
+                    ))
+                })?;
+
+            let mut agent = AgentWallet::new(signer);
+            agent.created_at = instant_from_unix_ms(stored.created_at);
+            agent.status = stored.status;
+            agents.push((name, agent));
+        }
+        Ok(agents)
+    }
+
+    fn save(&self, name: &str, agent: &AgentWallet) -> Result<(), HyperliquidError> {
+        let key_file = Self::key_file(agent.address);
+        if !self.dir.join(&key_file).exists() {
+            PrivateKeySigner::encrypt_keystore(
+                &self.dir,
+                &mut rand::thread_rng(),
+                agent.signer.to_bytes(),
+                &self.passphrase,
+                Some(&key_file),
+            )
+            .map_err(|e| HyperliquidError::Storage(e.to_string()))?;
+        }
+
+        let mut index = self.read_index()?;
+        let replaced = index.insert(
+            name.to_string(),
+            StoredAgent {
+                address: agent.address,
+                created_at: unix_ms_from_instant(agent.created_at),
+                status: agent.status.clone(),
+            },
+        );
+        self.write_index(&index)?;
+
+        // A rotated agent's key is no longer needed
+        if let Some(old) = replaced.filter(|old| old.address != agent.address) {
+            remove_key(&self.dir.join(Self::key_file(old.address)))?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, name: &str) -> Result<(), HyperliquidError> {
+        let mut index = self.read_index()?;
+        if let Some(stored) = index.remove(name) {
+            self.write_index(&index)?;
+            remove_key(&self.dir.join(Self::key_file(stored.address)))?;
+        }
+        Ok(())
+    }
+}
+
+fn remove_key(path: &std::path::Path) -> Result<(), HyperliquidError> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(storage_error(e)),
+        _ => Ok(()),
+    }
+}
+
+fn storage_error(e: std::io::Error) -> HyperliquidError {
+    HyperliquidError::Storage(e.to_string())
+}
+
+// `Instant` has no fixed epoch, so creation times are stored as wall clock time
+fn unix_ms_from_instant(instant: Instant) -> u64 {
+    let created = SystemTime::now() - instant.elapsed();
+    created
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn instant_from_unix_ms(ms: u64) -> Instant {
+    let created = UNIX_EPOCH + Duration::from_millis(ms);
+    let age = SystemTime::now()
+        .duration_since(created)
+        .unwrap_or_default();
+    Instant::now().checked_sub(age).unwrap_or_else(Instant::now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("ferrofluid-agents-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_agents_round_trip_encrypted() {
+        let dir = temp_dir();
+        let store = FileAgentStore::new(&dir, "hunter2").unwrap();
+
+        let mut agent = AgentWallet::new(PrivateKeySigner::random());
+        agent.status = AgentStatus::PendingRotation;
+        store.save("default", &agent).unwrap();
+
+        let key = fs::read_to_string(dir.join(FileAgentStore::key_file(agent.address)))
+            .unwrap();
+        assert!(key.contains("\"crypto\""));
+        assert!(!key.contains(&hex::encode(agent.signer.to_bytes())));
+
+        let loaded = FileAgentStore::new(&dir, "hunter2")
+            .unwrap()
+            .load()
+            .unwrap();
+        assert_eq!(loaded.len(), 1);
+        let (name, reloaded) = &loaded[0];
+        assert_eq!(name, "default");
+        assert_eq!(reloaded.address, agent.address);
+        assert_eq!(reloaded.status, AgentStatus::PendingRotation);
+        // Creation time survives with millisecond precision
+        let (a, b) = (reloaded.created_at, agent.created_at);
+        assert!(a.max(b) - a.min(b) < Duration::from_secs(1));
+
+        let wrong = FileAgentStore::new(&dir, "wrong").unwrap();
+        assert!(matches!(wrong.load(), Err(HyperliquidError::Storage(_))));
+
+        store.remove("default").unwrap();
+        assert!(store.load().unwrap().is_empty());
+        assert!(!dir.join(FileAgentStore::key_file(agent.address)).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
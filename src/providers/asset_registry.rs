@@ -0,0 +1,299 @@
+//! Symbol to asset index resolution backed by exchange metadata
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::RwLock;
+
+use crate::errors::HyperliquidError;
+use crate::providers::info::InfoProvider;
+use crate::types::info_types::{AssetMeta, Meta, SpotMeta, SpotPairMeta, TokenMeta};
+use crate::types::{AssetPrecision, Symbol};
+
+/// Offset added to a spot pair index to form its asset index
+pub const SPOT_ASSET_OFFSET: u32 = 10_000;
+
+/// An asset as referenced by exchange methods: either a raw index or a symbol
+/// resolved through the provider's [`AssetRegistry`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetRef {
+    Index(u32),
+    Symbol(Symbol),
+}
+
+impl From<u32> for AssetRef {
+    fn from(index: u32) -> Self {
+        AssetRef::Index(index)
+    }
+}
+
+impl From<Symbol> for AssetRef {
+    fn from(symbol: Symbol) -> Self {
+        AssetRef::Symbol(symbol)
+    }
+}
+
+impl From<&Symbol> for AssetRef {
+    fn from(symbol: &Symbol) -> Self {
+        AssetRef::Symbol(symbol.clone())
+    }
+}
+
+impl From<&'static str> for AssetRef {
+    fn from(symbol: &'static str) -> Self {
+        AssetRef::Symbol(Symbol::from_static(symbol))
+    }
+}
+
+impl From<String> for AssetRef {
+    fn from(symbol: String) -> Self {
+        AssetRef::Symbol(Symbol::from(symbol))
+    }
+}
+
+/// Metadata for a resolved asset
+#[derive(Debug, Clone)]
+pub enum AssetInfo {
+    Perp { index: u32, meta: AssetMeta },
+    Spot(Box<SpotAsset>),
+}
+
+/// A spot pair with its base and quote tokens
+#[derive(Debug, Clone)]
+pub struct SpotAsset {
+    /// Asset index used in orders (`10000 + pair index`)
+    pub index: u32,
+    pub pair: SpotPairMeta,
+    pub base: TokenMeta,
+    pub quote: TokenMeta,
+}
+
+impl AssetInfo {
+    /// Asset index used in orders, cancels and leverage updates
+    pub fn index(&self) -> u32 {
+        match self {
+            AssetInfo::Perp { index, .. } => *index,
+            AssetInfo::Spot(spot) => spot.index,
+        }
+    }
+
+    /// Coin name as used by the info endpoints and WebSocket channels
+    pub fn coin(&self) -> &str {
+        match self {
+            AssetInfo::Perp { meta, .. } => &meta.name,
+            AssetInfo::Spot(spot) => &spot.pair.name,
+        }
+    }
+
+    /// Number of decimals allowed in order sizes
+    pub fn sz_decimals(&self) -> u32 {
+        match self {
+            AssetInfo::Perp { meta, .. } => meta.sz_decimals,
+            AssetInfo::Spot(spot) => spot.base.sz_decimals,
+        }
+    }
+
+    pub fn is_spot(&self) -> bool {
+        matches!(self, AssetInfo::Spot(_))
+    }
+
+    /// Price and size rounding rules for this asset
+    pub fn precision(&self) -> AssetPrecision {
+        if self.is_spot() {
+            AssetPrecision::spot(self.sz_decimals())
+        } else {
+            AssetPrecision::perp(self.sz_decimals())
+        }
+    }
+}
+
+#[derive(Default)]
+struct Assets {
+    by_symbol: HashMap<String, AssetInfo>,
+    by_index: HashMap<u32, AssetInfo>,
+}
+
+/// Maps symbols to asset indices using `meta` and `spotMeta`
+///
+/// Perpetuals are keyed by name (e.g. `BTC`). Spot pairs are keyed both by
+/// their `@{index}` notation and their pair name (e.g. `PURR/USDC`).
+pub struct AssetRegistry {
+    assets: RwLock<Assets>,
+}
+
+impl AssetRegistry {
+    /// Build a registry from already fetched metadata
+    pub fn from_meta(meta: &Meta, spot_meta: &SpotMeta) -> Self {
+        Self {
+            assets: RwLock::new(Self::index(meta, spot_meta)),
+        }
+    }
+
+    /// Fetch metadata and build a registry
+    pub async fn load(info: &InfoProvider) -> Result<Self, HyperliquidError> {
+        let meta = info.meta().await?;
+        let spot_meta = info.spot_meta().await?;
+        Ok(Self::from_meta(&meta, &spot_meta))
+    }
+
+    /// Re-fetch metadata, picking up newly listed assets
+    pub async fn refresh(&self, info: &InfoProvider) -> Result<(), HyperliquidError> {
+        let meta = info.meta().await?;
+        let spot_meta = info.spot_meta().await?;
+        *self.assets.write() = Self::index(&meta, &spot_meta);
+        Ok(())
+    }
+
+    /// Refresh in the background every `interval`
+    ///
+    /// Failed refreshes keep the previous metadata. The task runs until the
+    /// returned handle is aborted.
+    pub fn spawn_refresh(
+        self: &Arc<Self>,
+        info: Arc<InfoProvider>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let registry = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = registry.refresh(&info).await {
+                    tracing::warn!("Failed to refresh asset registry: {e}");
+                }
+            }
+        })
+    }
+
+    /// Look up an asset by symbol
+    pub fn get(&self, symbol: impl Into<Symbol>) -> Option<AssetInfo> {
+        let symbol = symbol.into();
+        self.assets.read().by_symbol.get(symbol.as_str()).cloned()
+    }
+
+    /// Look up an asset by index
+    pub fn get_by_index(&self, index: u32) -> Option<AssetInfo> {
+        self.assets.read().by_index.get(&index).cloned()
+    }
+
+    /// Resolve an asset reference to its index
+    pub fn resolve(&self, asset: impl Into<AssetRef>) -> Result<u32, HyperliquidError> {
+        match asset.into() {
+            AssetRef::Index(index) => Ok(index),
+            AssetRef::Symbol(symbol) => self
+                .get(&symbol)
+                .map(|info| info.index())
+                .ok_or_else(|| HyperliquidError::AssetNotFound(symbol.to_string())),
+        }
+    }
+
+    /// Number of known assets
+    pub fn len(&self) -> usize {
+        self.assets.read().by_index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn index(meta: &Meta, spot_meta: &SpotMeta) -> Assets {
+        let mut assets = Assets::default();
+
+        for (index, asset) in meta.universe.iter().enumerate() {
+            let info = AssetInfo::Perp {
+                index: index as u32,
+                meta: asset.clone(),
+            };
+            assets.by_symbol.insert(asset.name.clone(), info.clone());
+            assets.by_index.insert(index as u32, info);
+        }
+
+        let tokens: HashMap<u32, &TokenMeta> = spot_meta
+            .tokens
+            .iter()
+            .map(|token| (token.index, token))
+            .collect();
+        for pair in &spot_meta.universe {
+            let (Some(base), Some(quote)) =
+                (tokens.get(&pair.tokens[0]), tokens.get(&pair.tokens[1]))
+            else {
+                continue;
+            };
+
+            let index = SPOT_ASSET_OFFSET + pair.index;
+            let info = AssetInfo::Spot(Box::new(SpotAsset {
+                index,
+                pair: pair.clone(),
+                base: (*base).clone(),
+                quote: (*quote).clone(),
+            }));
+            assets
+                .by_symbol
+                .insert(format!("@{}", pair.index), info.clone());
+            assets.by_symbol.insert(pair.name.clone(), info.clone());
+            assets.by_index.insert(index, info);
+        }
+
+        assets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> AssetRegistry {
+        let meta: Meta = serde_json::from_str(
+            r#"{"universe":[
+                {"name":"BTC","szDecimals":5,"maxLeverage":50},
+                {"name":"ETH","szDecimals":4,"maxLeverage":50}
+            ]}"#,
+        )
+        .unwrap();
+        let spot_meta: SpotMeta = serde_json::from_str(
+            r#"{
+                "universe":[{"name":"PURR/USDC","tokens":[1,0],"index":0,"isCanonical":true}],
+                "tokens":[
+                    {"name":"USDC","szDecimals":8,"weiDecimals":8,"index":0,"tokenId":"0x0","isCanonical":true},
+                    {"name":"PURR","szDecimals":0,"weiDecimals":5,"index":1,"tokenId":"0x1","isCanonical":true}
+                ]
+            }"#,
+        )
+        .unwrap();
+        AssetRegistry::from_meta(&meta, &spot_meta)
+    }
+
+    #[test]
+    fn test_resolve_perp_and_spot() {
+        let registry = registry();
+
+        assert_eq!(registry.resolve("BTC").unwrap(), 0);
+        assert_eq!(registry.resolve("ETH").unwrap(), 1);
+        assert_eq!(registry.resolve("@0").unwrap(), 10_000);
+        assert_eq!(registry.resolve("PURR/USDC").unwrap(), 10_000);
+        assert_eq!(registry.resolve(7u32).unwrap(), 7);
+        assert!(matches!(
+            registry.resolve("DOGE"),
+            Err(HyperliquidError::AssetNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_asset_metadata() {
+        let registry = registry();
+
+        let btc = registry.get("BTC").unwrap();
+        assert_eq!(btc.sz_decimals(), 5);
+        assert!(!btc.is_spot());
+
+        let purr = registry.get_by_index(10_000).unwrap();
+        assert!(purr.is_spot());
+        assert_eq!(purr.coin(), "PURR/USDC");
+        assert_eq!(purr.sz_decimals(), 0);
+        assert_eq!(purr.precision(), AssetPrecision::spot(0));
+        assert_eq!(registry.len(), 3);
+    }
+}
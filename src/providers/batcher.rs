@@ -1,40 +1,88 @@
 //! Order batching for high-frequency trading strategies
 
-use std::future::Future;
-use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use tokio::sync::{mpsc, Mutex};
-use tokio::time::interval;
+use async_trait::async_trait;
+use tokio::sync::{mpsc, Mutex, Notify};
+use tokio::time::{interval_at, sleep_until, Instant, MissedTickBehavior};
 use uuid::Uuid;
 
 use crate::errors::HyperliquidError;
-use crate::types::requests::{CancelRequest, OrderRequest};
-use crate::types::responses::ExchangeResponseStatus;
+use crate::types::requests::{
+    CancelRequest, CancelRequestCloid, ModifyRequest, OrderRequest,
+};
+use crate::types::responses::{
+    ExchangeDataStatuses, ExchangeResponse, ExchangeResponseStatus,
+};
 
-type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
+type BatchResult = Result<ExchangeResponseStatus, HyperliquidError>;
 
 /// Order with metadata for batching
 #[derive(Clone)]
 pub struct PendingOrder {
     pub order: OrderRequest,
-    pub nonce: u64,
     pub id: Uuid,
-    pub response_tx:
-        mpsc::UnboundedSender<Result<ExchangeResponseStatus, HyperliquidError>>,
+    pub queued_at: Instant,
+    pub response_tx: mpsc::UnboundedSender<BatchResult>,
 }
 
 /// Cancel with metadata for batching
 #[derive(Clone)]
 pub struct PendingCancel {
     pub cancel: CancelRequest,
-    pub nonce: u64,
     pub id: Uuid,
-    pub response_tx:
-        mpsc::UnboundedSender<Result<ExchangeResponseStatus, HyperliquidError>>,
+    pub queued_at: Instant,
+    pub response_tx: mpsc::UnboundedSender<BatchResult>,
 }
 
+/// Cancel by client order ID with metadata for batching
+#[derive(Clone)]
+pub struct PendingCancelCloid {
+    pub cancel: CancelRequestCloid,
+    pub id: Uuid,
+    pub queued_at: Instant,
+    pub response_tx: mpsc::UnboundedSender<BatchResult>,
+}
+
+/// Modify with metadata for batching
+#[derive(Clone)]
+pub struct PendingModify {
+    pub modify: ModifyRequest,
+    pub id: Uuid,
+    pub queued_at: Instant,
+    pub response_tx: mpsc::UnboundedSender<BatchResult>,
+}
+
+trait Queued {
+    fn queued_at(&self) -> Instant;
+    fn respond(&self, result: BatchResult);
+}
+
+macro_rules! impl_queued {
+    ($($pending:ty),*) => {
+        $(
+            impl Queued for $pending {
+                fn queued_at(&self) -> Instant {
+                    self.queued_at
+                }
+
+                fn respond(&self, result: BatchResult) {
+                    let _ = self.response_tx.send(result);
+                }
+            }
+        )*
+    };
+}
+
+impl_queued!(
+    PendingOrder,
+    PendingCancel,
+    PendingCancelCloid,
+    PendingModify
+);
+
 /// Order type classification for priority batching
 #[derive(Debug, Clone, PartialEq)]
 pub enum OrderPriority {
@@ -79,14 +127,48 @@ impl Default for BatchConfig {
     }
 }
 
+/// Sends batched requests to the exchange
+#[async_trait]
+pub trait BatchExecutor: Send + Sync {
+    async fn orders(&self, orders: Vec<OrderRequest>) -> BatchResult;
+    async fn cancels(&self, cancels: Vec<CancelRequest>) -> BatchResult;
+    async fn cancels_by_cloid(&self, cancels: Vec<CancelRequestCloid>) -> BatchResult;
+    async fn modifies(&self, modifies: Vec<ModifyRequest>) -> BatchResult;
+}
+
+#[derive(Default)]
+struct Queues {
+    orders: Vec<PendingOrder>,
+    cancels: Vec<PendingCancel>,
+    cancels_by_cloid: Vec<PendingCancelCloid>,
+    modifies: Vec<PendingModify>,
+}
+
+impl Queues {
+    fn oldest(&self) -> Option<Instant> {
+        let orders = self.orders.iter().map(Queued::queued_at);
+        let cancels = self.cancels.iter().map(Queued::queued_at);
+        let cloids = self.cancels_by_cloid.iter().map(Queued::queued_at);
+        let modifies = self.modifies.iter().map(Queued::queued_at);
+        orders.chain(cancels).chain(cloids).chain(modifies).min()
+    }
+
+    fn has_full_batch(&self, max_batch_size: usize) -> bool {
+        self.orders.len() >= max_batch_size
+            || self.cancels.len() >= max_batch_size
+            || self.cancels_by_cloid.len() >= max_batch_size
+            || self.modifies.len() >= max_batch_size
+    }
+}
+
 /// Batches orders for efficient submission
 pub struct OrderBatcher {
-    /// Pending orders queue
-    pending_orders: Arc<Mutex<Vec<PendingOrder>>>,
-    /// Pending cancels queue
-    pending_cancels: Arc<Mutex<Vec<PendingCancel>>>,
-    /// Configuration
-    _config: BatchConfig,
+    /// Pending requests
+    queues: Arc<Mutex<Queues>>,
+    /// Wakes the batching loop when a request is queued
+    notify: Arc<Notify>,
+    /// Set once shutdown starts; later requests are rejected
+    closed: Arc<AtomicBool>,
     /// Shutdown signal
     shutdown_tx: mpsc::Sender<()>,
 }
@@ -97,15 +179,16 @@ impl OrderBatcher {
         let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
 
         let batcher = Self {
-            pending_orders: Arc::new(Mutex::new(Vec::new())),
-            pending_cancels: Arc::new(Mutex::new(Vec::new())),
-            _config: config,
+            queues: Arc::new(Mutex::new(Queues::default())),
+            notify: Arc::new(Notify::new()),
+            closed: Arc::new(AtomicBool::new(false)),
             shutdown_tx,
         };
 
         let handle = BatcherHandle {
-            pending_orders: batcher.pending_orders.clone(),
-            pending_cancels: batcher.pending_cancels.clone(),
+            queues: batcher.queues.clone(),
+            notify: batcher.notify.clone(),
+            config,
             shutdown_rx,
         };
 
@@ -113,123 +196,292 @@ impl OrderBatcher {
     }
 
     /// Add an order to the batch queue
-    pub async fn add_order(&self, order: OrderRequest, nonce: u64) -> OrderHandle {
+    pub async fn add_order(&self, order: OrderRequest) -> OrderHandle {
         let id = Uuid::new_v4();
         let (tx, rx) = mpsc::unbounded_channel();
 
         let pending = PendingOrder {
             order,
-            nonce,
             id,
+            queued_at: Instant::now(),
             response_tx: tx,
         };
 
-        self.pending_orders.lock().await.push(pending);
+        let mut queues = self.queues.lock().await;
+        if self.closed.load(Ordering::Acquire) {
+            return Self::closed();
+        }
+        queues.orders.push(pending);
+        self.notify.notify_one();
 
         OrderHandle::Pending { id, rx }
     }
 
     /// Add a cancel to the batch queue
-    pub async fn add_cancel(&self, cancel: CancelRequest, nonce: u64) -> OrderHandle {
+    pub async fn add_cancel(&self, cancel: CancelRequest) -> OrderHandle {
         let id = Uuid::new_v4();
         let (tx, rx) = mpsc::unbounded_channel();
 
         let pending = PendingCancel {
             cancel,
-            nonce,
             id,
+            queued_at: Instant::now(),
             response_tx: tx,
         };
 
-        self.pending_cancels.lock().await.push(pending);
+        let mut queues = self.queues.lock().await;
+        if self.closed.load(Ordering::Acquire) {
+            return Self::closed();
+        }
+        queues.cancels.push(pending);
+        self.notify.notify_one();
+
+        OrderHandle::Pending { id, rx }
+    }
+
+    /// Add a cancel by client order ID to the batch queue
+    pub async fn add_cancel_by_cloid(&self, cancel: CancelRequestCloid) -> OrderHandle {
+        let id = Uuid::new_v4();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let pending = PendingCancelCloid {
+            cancel,
+            id,
+            queued_at: Instant::now(),
+            response_tx: tx,
+        };
+
+        let mut queues = self.queues.lock().await;
+        if self.closed.load(Ordering::Acquire) {
+            return Self::closed();
+        }
+        queues.cancels_by_cloid.push(pending);
+        self.notify.notify_one();
+
+        OrderHandle::Pending { id, rx }
+    }
+
+    /// Add a modify to the batch queue
+    pub async fn add_modify(&self, modify: ModifyRequest) -> OrderHandle {
+        let id = Uuid::new_v4();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let pending = PendingModify {
+            modify,
+            id,
+            queued_at: Instant::now(),
+            response_tx: tx,
+        };
+
+        let mut queues = self.queues.lock().await;
+        if self.closed.load(Ordering::Acquire) {
+            return Self::closed();
+        }
+        queues.modifies.push(pending);
+        self.notify.notify_one();
 
         OrderHandle::Pending { id, rx }
     }
 
     /// Shutdown the batcher
-    pub async fn shutdown(self) {
+    ///
+    /// Queued requests are still sent before [`BatcherHandle::run`] returns.
+    /// Requests added after this are rejected immediately.
+    pub async fn shutdown(&self) {
+        {
+            // Closing under the queue lock means every request that made it
+            // into a queue is there for the final flush
+            let _queues = self.queues.lock().await;
+            self.closed.store(true, Ordering::Release);
+        }
         let _ = self.shutdown_tx.send(()).await;
     }
+
+    fn closed() -> OrderHandle {
+        OrderHandle::Immediate(Err(HyperliquidError::InvalidRequest(
+            "order batcher is shut down".to_string(),
+        )))
+    }
 }
 
 /// Handle for the background batching task
 pub struct BatcherHandle {
-    pending_orders: Arc<Mutex<Vec<PendingOrder>>>,
-    pending_cancels: Arc<Mutex<Vec<PendingCancel>>>,
+    queues: Arc<Mutex<Queues>>,
+    notify: Arc<Notify>,
+    config: BatchConfig,
     shutdown_rx: mpsc::Receiver<()>,
 }
 
 impl BatcherHandle {
     /// Run the batching loop (should be spawned as a task)
-    pub async fn run<F, G>(mut self, mut order_executor: F, mut cancel_executor: G)
-    where
-        F: FnMut(
-                Vec<PendingOrder>,
-            )
-                -> BoxFuture<Vec<Result<ExchangeResponseStatus, HyperliquidError>>>
-            + Send,
-        G: FnMut(
-                Vec<PendingCancel>,
-            )
-                -> BoxFuture<Vec<Result<ExchangeResponseStatus, HyperliquidError>>>
-            + Send,
-    {
-        let mut interval = interval(Duration::from_millis(100)); // Fixed interval for now
+    ///
+    /// Queues are flushed every `interval`, as soon as one holds
+    /// `max_batch_size` requests, or when the oldest request has waited
+    /// `max_wait_time`. Returns after flushing on shutdown.
+    pub async fn run<E: BatchExecutor + ?Sized>(mut self, executor: Arc<E>) {
+        let start = Instant::now() + self.config.interval;
+        let mut interval = interval_at(start, self.config.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
         loop {
+            let deadline = self
+                .queues
+                .lock()
+                .await
+                .oldest()
+                .map(|queued_at| queued_at + self.config.max_wait_time);
+
             tokio::select! {
-                _ = interval.tick() => {
-                    // Process orders
-                    let orders = {
-                        let mut pending = self.pending_orders.lock().await;
-                        std::mem::take(&mut *pending)
-                    };
-
-                    if !orders.is_empty() {
-                        // Separate ALO from regular orders
-                        let (alo_orders, regular_orders): (Vec<_>, Vec<_>) =
-                            orders.into_iter().partition(|o| {
-                                o.order.is_alo()
-                            });
-
-                        // Process ALO orders first (priority)
-                        if !alo_orders.is_empty() {
-                            let results = order_executor(alo_orders.clone()).await;
-                            for (order, result) in alo_orders.into_iter().zip(results) {
-                                let _ = order.response_tx.send(result);
-                            }
-                        }
-
-                        // Process regular orders
-                        if !regular_orders.is_empty() {
-                            let results = order_executor(regular_orders.clone()).await;
-                            for (order, result) in regular_orders.into_iter().zip(results) {
-                                let _ = order.response_tx.send(result);
-                            }
-                        }
-                    }
+                _ = interval.tick() => self.flush(executor.as_ref()).await,
+
+                _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    self.flush(executor.as_ref()).await;
+                }
 
-                    // Process cancels
-                    let cancels = {
-                        let mut pending = self.pending_cancels.lock().await;
-                        std::mem::take(&mut *pending)
-                    };
-
-                    if !cancels.is_empty() {
-                        let results = cancel_executor(cancels.clone()).await;
-                        for (cancel, result) in cancels.into_iter().zip(results) {
-                            let _ = cancel.response_tx.send(result);
-                        }
+                _ = self.notify.notified() => {
+                    let full = self
+                        .queues
+                        .lock()
+                        .await
+                        .has_full_batch(self.config.max_batch_size);
+                    if full {
+                        self.flush(executor.as_ref()).await;
                     }
                 }
 
                 _ = self.shutdown_rx.recv() => {
                     // Graceful shutdown
+                    self.flush(executor.as_ref()).await;
                     break;
                 }
             }
         }
     }
+
+    async fn flush<E: BatchExecutor + ?Sized>(&self, executor: &E) {
+        let queues = std::mem::take(&mut *self.queues.lock().await);
+        let max = self.config.max_batch_size.max(1);
+
+        // ALO orders go first (priority)
+        let (alo_orders, regular_orders): (Vec<_>, Vec<_>) = if self.config.prioritize_alo
+        {
+            queues.orders.into_iter().partition(|o| o.order.is_alo())
+        } else {
+            (Vec::new(), queues.orders)
+        };
+        for orders in [alo_orders, regular_orders] {
+            for chunk in orders.chunks(max) {
+                let requests = chunk.iter().map(|o| o.order.clone()).collect();
+                respond(chunk, executor.orders(requests).await);
+            }
+        }
+
+        for chunk in queues.cancels.chunks(max) {
+            let requests = chunk.iter().map(|c| c.cancel.clone()).collect();
+            respond(chunk, executor.cancels(requests).await);
+        }
+
+        for chunk in queues.cancels_by_cloid.chunks(max) {
+            let requests = chunk.iter().map(|c| c.cancel.clone()).collect();
+            respond(chunk, executor.cancels_by_cloid(requests).await);
+        }
+
+        for chunk in queues.modifies.chunks(max) {
+            let requests = chunk.iter().map(|m| m.modify.clone()).collect();
+            respond(chunk, executor.modifies(requests).await);
+        }
+    }
+}
+
+// Deliver each request in a batch its own result
+fn respond<T: Queued>(batch: &[T], result: BatchResult) {
+    let results = match result {
+        Ok(response) => split_batch_response(response, batch.len()),
+        Err(e) => {
+            // Return same error for all requests in batch
+            let mut results: Vec<BatchResult> =
+                batch.iter().skip(1).map(|_| Err(duplicate(&e))).collect();
+            results.insert(0, Err(e));
+            results
+        }
+    };
+
+    for (pending, result) in batch.iter().zip(results) {
+        pending.respond(result);
+    }
+}
+
+// Copy of an executor error for another request in the same batch. Errors
+// wrapping a non-`Clone` source keep only its message.
+fn duplicate(e: &HyperliquidError) -> HyperliquidError {
+    use HyperliquidError::*;
+
+    match e {
+        RateLimited {
+            available,
+            required,
+        } => RateLimited {
+            available: *available,
+            required: *required,
+        },
+        Network(msg) => Network(msg.clone()),
+        InvalidResponse(msg) => InvalidResponse(msg.clone()),
+        AssetNotFound(msg) => AssetNotFound(msg.clone()),
+        Signer(e) => Signer(e.clone()),
+        InvalidUrl(e) => InvalidUrl(*e),
+        Http { status, body } => Http {
+            status: *status,
+            body: body.clone(),
+        },
+        WebSocket(msg) => WebSocket(msg.clone()),
+        Serialize(msg) => Serialize(msg.clone()),
+        Unauthorized(msg) => Unauthorized(msg.clone()),
+        InvalidRequest(msg) => InvalidRequest(msg.clone()),
+        Storage(msg) => Storage(msg.clone()),
+        HyperHttp(_) | Json(_) | SerdeJson(_) => InvalidResponse(e.to_string()),
+    }
+}
+
+/// Split a bulk response into one response per request, by index
+///
+/// Each request receives an `Ok` response carrying only its own status. A
+/// top-level `Err` rejects the whole batch, so every request receives it.
+pub fn split_batch_response(
+    response: ExchangeResponseStatus,
+    count: usize,
+) -> Vec<Result<ExchangeResponseStatus, HyperliquidError>> {
+    match response {
+        ExchangeResponseStatus::Ok(ExchangeResponse {
+            response_type,
+            data: Some(data),
+        }) if data.statuses.len() == count => data
+            .statuses
+            .into_iter()
+            .map(|status| {
+                Ok(ExchangeResponseStatus::Ok(ExchangeResponse {
+                    response_type: response_type.clone(),
+                    data: Some(ExchangeDataStatuses {
+                        statuses: vec![status],
+                        status: None,
+                        address: None,
+                    }),
+                }))
+            })
+            .collect(),
+        ExchangeResponseStatus::Ok(response) => {
+            let received = response.data.map_or(0, |data| data.statuses.len());
+            (0..count)
+                .map(|_| {
+                    Err(HyperliquidError::InvalidResponse(format!(
+                        "Expected {count} statuses in batch response, got {received}"
+                    )))
+                })
+                .collect()
+        }
+        err @ ExchangeResponseStatus::Err(_) => {
+            (0..count).map(|_| Ok(err.clone())).collect()
+        }
+    }
 }
 
 impl OrderRequest {
@@ -246,8 +498,64 @@ impl OrderRequest {
 
 #[cfg(test)]
 mod tests {
+    use rust_decimal_macros::dec;
+
     use super::*;
     use crate::types::requests::{Limit, OrderType};
+    use crate::types::responses::ExchangeDataStatus;
+
+    // Records the requests of each batch and rests every order
+    #[derive(Default)]
+    struct RecordingExecutor {
+        batches: std::sync::Mutex<Vec<(&'static str, Vec<OrderRequest>)>>,
+    }
+
+    impl RecordingExecutor {
+        fn ok(count: usize) -> BatchResult {
+            let statuses = vec![r#"{"resting":{"oid":1}}"#; count].join(",");
+            Ok(serde_json::from_str(&format!(
+                r#"{{"status":"ok","response":{{"type":"order","data":{{"statuses":[{statuses}]}}}}}}"#
+            ))
+            .unwrap())
+        }
+    }
+
+    #[async_trait]
+    impl BatchExecutor for RecordingExecutor {
+        async fn orders(&self, orders: Vec<OrderRequest>) -> BatchResult {
+            let count = orders.len();
+            self.batches.lock().unwrap().push(("order", orders));
+            Self::ok(count)
+        }
+
+        async fn cancels(&self, cancels: Vec<CancelRequest>) -> BatchResult {
+            self.batches.lock().unwrap().push(("cancel", Vec::new()));
+            Self::ok(cancels.len())
+        }
+
+        async fn cancels_by_cloid(
+            &self,
+            cancels: Vec<CancelRequestCloid>,
+        ) -> BatchResult {
+            self.batches
+                .lock()
+                .unwrap()
+                .push(("cancelByCloid", Vec::new()));
+            Self::ok(cancels.len())
+        }
+
+        async fn modifies(&self, modifies: Vec<ModifyRequest>) -> BatchResult {
+            self.batches
+                .lock()
+                .unwrap()
+                .push(("batchModify", Vec::new()));
+            Self::ok(modifies.len())
+        }
+    }
+
+    fn order(tif: &str) -> OrderRequest {
+        OrderRequest::limit(0, true, dec!(50000), dec!(0.1), tif)
+    }
 
     #[tokio::test]
     async fn test_order_batching() {
@@ -258,8 +566,8 @@ mod tests {
         let order = OrderRequest {
             asset: 0,
             is_buy: true,
-            limit_px: "50000".to_string(),
-            sz: "0.1".to_string(),
+            limit_px: dec!(50000),
+            sz: dec!(0.1),
             reduce_only: false,
             order_type: OrderType::Limit(Limit {
                 tif: "Gtc".to_string(),
@@ -268,9 +576,163 @@ mod tests {
         };
 
         // Add to batch
-        let handle = batcher.add_order(order, 123456789).await;
+        let handle = batcher.add_order(order).await;
 
         // Should return pending handle
         assert!(matches!(handle, OrderHandle::Pending { .. }));
     }
+
+    #[test]
+    fn test_split_batch_response_by_index() {
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[
+                {"resting":{"oid":1}},
+                {"error":"Insufficient margin to place order. asset=0"}
+            ]}}}"#,
+        )
+        .unwrap();
+
+        let results = split_batch_response(response, 2);
+        let statuses: Vec<ExchangeDataStatus> = results
+            .into_iter()
+            .map(|result| {
+                let response = result.unwrap().into_result().unwrap();
+                let mut statuses = response.data.unwrap().statuses;
+                assert_eq!(statuses.len(), 1);
+                statuses.remove(0)
+            })
+            .collect();
+        assert_eq!(statuses[0].order_id(), Some(1));
+        assert!(matches!(statuses[1], ExchangeDataStatus::Error(_)));
+
+        let rejected = ExchangeResponseStatus::Err("Too many requests".to_string());
+        let results = split_batch_response(rejected, 3);
+        assert_eq!(results.len(), 3);
+        assert!(results
+            .iter()
+            .all(|r| r.as_ref().unwrap().error() == Some("Too many requests")));
+    }
+
+    #[tokio::test]
+    async fn test_batch_error_reaches_every_request() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let batch: Vec<_> = (0..3)
+            .map(|_| PendingOrder {
+                order: order("Gtc"),
+                id: Uuid::new_v4(),
+                queued_at: Instant::now(),
+                response_tx: tx.clone(),
+            })
+            .collect();
+
+        respond(
+            &batch,
+            Err(HyperliquidError::RateLimited {
+                available: 0,
+                required: 2,
+            }),
+        );
+        for _ in 0..3 {
+            assert!(matches!(
+                rx.recv().await.unwrap(),
+                Err(HyperliquidError::RateLimited {
+                    available: 0,
+                    required: 2
+                })
+            ));
+        }
+
+        respond(
+            &batch[..2],
+            Err(HyperliquidError::Http {
+                status: 502,
+                body: "Bad Gateway".to_string(),
+            }),
+        );
+        for _ in 0..2 {
+            assert!(matches!(
+                rx.recv().await.unwrap(),
+                Err(HyperliquidError::Http { status: 502, .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batches_split_and_flush_on_shutdown() {
+        let config = BatchConfig {
+            interval: Duration::from_secs(3600),
+            max_batch_size: 2,
+            prioritize_alo: true,
+            max_wait_time: Duration::from_secs(3600),
+        };
+        let (batcher, handle) = OrderBatcher::new(config);
+        let executor = Arc::new(RecordingExecutor::default());
+
+        let mut handles = Vec::new();
+        for tif in ["Gtc", "Gtc", "Alo"] {
+            handles.push(batcher.add_order(order(tif)).await);
+        }
+        handles.push(batcher.add_cancel(CancelRequest { asset: 0, oid: 1 }).await);
+        batcher.shutdown().await;
+        handle.run(executor.clone()).await;
+
+        for handle in handles {
+            let OrderHandle::Pending { mut rx, .. } = handle else {
+                panic!("expected a pending handle");
+            };
+            assert!(rx.recv().await.unwrap().is_ok());
+        }
+
+        let batches = executor.batches.lock().unwrap();
+        let shape: Vec<_> = batches
+            .iter()
+            .map(|(kind, orders)| (*kind, orders.len()))
+            .collect();
+        assert_eq!(shape, vec![("order", 1), ("order", 2), ("cancel", 0)]);
+        // The ALO order goes out first
+        assert!(batches[0].1[0].is_alo());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_requests_after_shutdown() {
+        let (batcher, handle) = OrderBatcher::new(BatchConfig::default());
+        let executor = Arc::new(RecordingExecutor::default());
+        batcher.shutdown().await;
+        handle.run(executor.clone()).await;
+
+        let handles = [
+            batcher.add_order(order("Gtc")).await,
+            batcher.add_cancel(CancelRequest { asset: 0, oid: 1 }).await,
+        ];
+        for handle in handles {
+            assert!(matches!(
+                handle,
+                OrderHandle::Immediate(Err(HyperliquidError::InvalidRequest(_)))
+            ));
+        }
+        assert!(executor.batches.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_flushes_after_max_wait_time() {
+        let config = BatchConfig {
+            interval: Duration::from_secs(3600),
+            max_batch_size: 100,
+            prioritize_alo: false,
+            max_wait_time: Duration::from_millis(50),
+        };
+        let (batcher, handle) = OrderBatcher::new(config);
+        let executor = Arc::new(RecordingExecutor::default());
+        let task = tokio::spawn(handle.run(executor.clone()));
+
+        let OrderHandle::Pending { mut rx, .. } = batcher.add_order(order("Gtc")).await
+        else {
+            panic!("expected a pending handle");
+        };
+        assert!(rx.recv().await.unwrap().is_ok());
+        assert_eq!(executor.batches.lock().unwrap().len(), 1);
+
+        batcher.shutdown().await;
+        task.await.unwrap();
+    }
 }
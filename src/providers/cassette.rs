@@ -0,0 +1,397 @@
+//! Recording and replay of API traffic
+//!
+//! A cassette is a JSON-lines file with one [`CassetteEntry`] per line: every
+//! HTTP request with its response and every WebSocket text frame, in the order
+//! they happened. Record a session by wrapping a transport in
+//! [`RecordingTransport`] and setting [`WsConfig::recorder`], then replay it
+//! without a network through [`ReplayTransport`] and [`WsConfig::replay`].
+//!
+//! [`WsConfig::recorder`]: crate::providers::WsConfig::recorder
+//! [`WsConfig::replay`]: crate::providers::WsConfig::replay
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use fastwebsockets::{Frame, OpCode, Role};
+use hyper::body::Bytes;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::DuplexStream;
+
+use crate::{
+    errors::HyperliquidError,
+    providers::transport::{Transport, TransportError},
+    providers::websocket::split_websocket,
+};
+
+/// Which way a WebSocket frame went
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WsDirection {
+    Sent,
+    Received,
+}
+
+/// One line of a cassette
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CassetteEntry {
+    /// An HTTP request and the response it got
+    Http {
+        /// Milliseconds since the Unix epoch
+        time: u64,
+        url: String,
+        request: Value,
+        status: u16,
+        /// Response body as received
+        response: String,
+    },
+    /// A WebSocket text frame
+    Ws {
+        /// Milliseconds since the Unix epoch
+        time: u64,
+        direction: WsDirection,
+        frame: String,
+    },
+}
+
+/// A recorded session, loaded for replay
+#[derive(Clone, Debug, Default)]
+pub struct Cassette {
+    entries: Vec<CassetteEntry>,
+}
+
+impl Cassette {
+    /// Load a cassette written by [`CassetteRecorder`]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, HyperliquidError> {
+        let file = File::open(path.as_ref()).map_err(storage_error)?;
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(storage_error)?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn from_entries(entries: Vec<CassetteEntry>) -> Self {
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[CassetteEntry] {
+        &self.entries
+    }
+}
+
+fn storage_error(e: std::io::Error) -> HyperliquidError {
+    HyperliquidError::Storage(e.to_string())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Appends entries to a cassette file as they happen
+///
+/// Each entry is flushed as soon as it is written, so a session that crashes
+/// still leaves a usable cassette. Failed writes are logged and otherwise
+/// ignored, so recording never interrupts the session itself.
+#[derive(Debug)]
+pub struct CassetteRecorder {
+    file: Mutex<File>,
+}
+
+impl CassetteRecorder {
+    /// Start a cassette at `path`, replacing any file already there
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, HyperliquidError> {
+        let file = File::create(path.as_ref()).map_err(storage_error)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn record(&self, entry: &CassetteEntry) {
+        let result = serde_json::to_string(entry)
+            .map_err(|e| e.to_string())
+            .and_then(|line| {
+                let mut file = self.file.lock();
+                writeln!(file, "{line}")
+                    .and_then(|_| file.flush())
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            tracing::warn!("Failed to record cassette entry: {e}");
+        }
+    }
+
+    pub(crate) fn record_ws(&self, direction: WsDirection, frame: &str) {
+        self.record(&CassetteEntry::Ws {
+            time: now_ms(),
+            direction,
+            frame: frame.to_string(),
+        });
+    }
+}
+
+/// [`Transport`] that records every request and response passing through it
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    recorder: Arc<CassetteRecorder>,
+}
+
+impl RecordingTransport {
+    pub fn new(inner: Arc<dyn Transport>, recorder: Arc<CassetteRecorder>) -> Self {
+        Self { inner, recorder }
+    }
+}
+
+#[async_trait]
+impl Transport for RecordingTransport {
+    async fn post(&self, url: &str, body: Bytes) -> Result<(u16, Bytes), TransportError> {
+        let request = serde_json::from_slice(&body)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&body).into()));
+        let (status, response) = self.inner.post(url, body).await?;
+
+        self.recorder.record(&CassetteEntry::Http {
+            time: now_ms(),
+            url: url.to_string(),
+            request,
+            status,
+            response: String::from_utf8_lossy(&response).into(),
+        });
+        Ok((status, response))
+    }
+}
+
+struct Recorded {
+    url: String,
+    key: Value,
+    status: u16,
+    response: String,
+}
+
+/// [`Transport`] that answers from a cassette instead of the network
+///
+/// Each request is answered by the first unplayed entry for the same URL with a
+/// matching request. Info requests match on their whole body. Exchange payloads
+/// carry a fresh nonce and signature every run, so they match on the action
+/// type alone and are answered in recorded order.
+pub struct ReplayTransport {
+    recorded: Mutex<Vec<Option<Recorded>>>,
+}
+
+impl ReplayTransport {
+    pub fn new(cassette: &Cassette) -> Self {
+        let recorded = cassette
+            .entries()
+            .iter()
+            .filter_map(|entry| match entry {
+                CassetteEntry::Http {
+                    url,
+                    request,
+                    status,
+                    response,
+                    ..
+                } => Some(Some(Recorded {
+                    url: url.clone(),
+                    key: replay_key(request),
+                    status: *status,
+                    response: response.clone(),
+                })),
+                CassetteEntry::Ws { .. } => None,
+            })
+            .collect();
+        Self {
+            recorded: Mutex::new(recorded),
+        }
+    }
+
+    /// Recorded responses not served yet
+    pub fn unplayed(&self) -> usize {
+        self.recorded.lock().iter().flatten().count()
+    }
+}
+
+fn replay_key(request: &Value) -> Value {
+    match request.get("action") {
+        Some(action) => action.get("type").cloned().unwrap_or(Value::Null),
+        None => request.clone(),
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn post(&self, url: &str, body: Bytes) -> Result<(u16, Bytes), TransportError> {
+        let request: Value = serde_json::from_slice(&body)
+            .map_err(|e| TransportError::Failed(e.to_string()))?;
+        let key = replay_key(&request);
+
+        let mut recorded = self.recorded.lock();
+        let slot = recorded
+            .iter_mut()
+            .find(|slot| {
+                slot.as_ref()
+                    .is_some_and(|entry| entry.url == url && entry.key == key)
+            })
+            .ok_or_else(|| {
+                TransportError::Failed(format!("No recorded response for {request}"))
+            })?;
+        let entry = slot.take().expect("matched slot is unplayed");
+        Ok((entry.status, Bytes::from(entry.response)))
+    }
+}
+
+// Pings and pongs depend on timing rather than on what the client does
+fn is_keepalive(frame: &str) -> bool {
+    serde_json::from_str::<Value>(frame).is_ok_and(|value| {
+        value.get("method").and_then(Value::as_str) == Some("ping")
+            || value.get("channel").and_then(Value::as_str) == Some("pong")
+    })
+}
+
+/// Serve the cassette's WebSocket frames over an in-memory stream
+///
+/// Received frames are sent to the client in recorded order. Before each frame
+/// that was sent during recording, the replay waits for the client to send its
+/// next frame, so responses never arrive ahead of their requests. Keepalive
+/// frames are skipped both ways, and once the cassette runs out the connection
+/// stays open until the client closes it.
+pub(crate) fn replay_stream(cassette: &Cassette) -> DuplexStream {
+    let frames: Vec<(WsDirection, String)> = cassette
+        .entries()
+        .iter()
+        .filter_map(|entry| match entry {
+            CassetteEntry::Ws {
+                direction, frame, ..
+            } if !is_keepalive(frame) => Some((*direction, frame.clone())),
+            _ => None,
+        })
+        .collect();
+
+    let (client, server) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        let (mut reader, mut writer) = split_websocket(server, Role::Server);
+
+        for (direction, frame) in frames {
+            match direction {
+                WsDirection::Sent => loop {
+                    let Ok(sent) = reader.read_frame().await else {
+                        return;
+                    };
+                    match sent.opcode {
+                        OpCode::Text
+                            if !is_keepalive(&String::from_utf8_lossy(&sent.payload)) =>
+                        {
+                            break
+                        }
+                        OpCode::Close => return,
+                        _ => {}
+                    }
+                },
+                WsDirection::Received => {
+                    let text = Frame::text(frame.into_bytes().into());
+                    if writer.write_frame(text).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+
+        while let Ok(frame) = reader.read_frame().await {
+            if frame.opcode == OpCode::Close {
+                break;
+            }
+        }
+    });
+
+    client
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http(url: &str, request: Value, response: &str) -> CassetteEntry {
+        CassetteEntry::Http {
+            time: 0,
+            url: url.to_string(),
+            request,
+            status: 200,
+            response: response.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_matches_requests() {
+        let cassette = Cassette::from_entries(vec![
+            http(
+                "/info",
+                serde_json::json!({"type": "allMids"}),
+                "{\"BTC\":\"1\"}",
+            ),
+            http("/info", serde_json::json!({"type": "meta"}), "{}"),
+            http(
+                "/exchange",
+                serde_json::json!({"action": {"type": "order"}, "nonce": 1}),
+                "first",
+            ),
+            http(
+                "/exchange",
+                serde_json::json!({"action": {"type": "order"}, "nonce": 2}),
+                "second",
+            ),
+        ]);
+        let replay = ReplayTransport::new(&cassette);
+        let post = |url: &'static str, body: Value| {
+            let replay = &replay;
+            async move {
+                let (_, body) = replay.post(url, Bytes::from(body.to_string())).await?;
+                Ok::<_, TransportError>(String::from_utf8(body.to_vec()).unwrap())
+            }
+        };
+
+        // Info requests match on the body, exchange actions on their type in order
+        let meta = post("/info", serde_json::json!({"type": "meta"}))
+            .await
+            .unwrap();
+        assert_eq!(meta, "{}");
+        let order = serde_json::json!({"action": {"type": "order"}, "nonce": 9});
+        assert_eq!(post("/exchange", order.clone()).await.unwrap(), "first");
+        assert_eq!(post("/exchange", order.clone()).await.unwrap(), "second");
+        assert!(post("/exchange", order).await.is_err());
+        assert_eq!(replay.unplayed(), 1);
+    }
+
+    #[test]
+    fn test_entries_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "ferrofluid-cassette-{}.jsonl",
+            uuid::Uuid::new_v4()
+        ));
+        let recorder = CassetteRecorder::create(&path).unwrap();
+        let entry = http("/info", serde_json::json!({"type": "allMids"}), "{}");
+        recorder.record(&entry);
+        recorder.record_ws(WsDirection::Received, "{\"channel\":\"pong\"}");
+
+        let cassette = Cassette::load(&path).unwrap();
+        assert_eq!(cassette.entries().len(), 2);
+        assert_eq!(cassette.entries()[0], entry);
+        assert!(matches!(
+            &cassette.entries()[1],
+            CassetteEntry::Ws { direction: WsDirection::Received, frame, .. }
+                if is_keepalive(frame)
+        ));
+        let _ = std::fs::remove_file(path);
+    }
+}
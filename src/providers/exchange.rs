@@ -4,10 +4,8 @@ use std::{
 };
 
 use alloy::primitives::{keccak256, Address, B256};
-use http_body_util::{BodyExt, Full};
-use hyper::{body::Bytes, Method, Request};
-use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
-use hyper_util::client::legacy::{connect::HttpConnector, Client};
+use hyper::body::Bytes;
+use rust_decimal::Decimal;
 use serde::Serialize;
 use serde_json::{json, Value};
 use uuid::Uuid;
@@ -15,55 +13,66 @@ use uuid::Uuid;
 use crate::{
     constants::*,
     errors::HyperliquidError,
+    providers::asset_registry::{AssetInfo, AssetRef, AssetRegistry},
+    providers::info::{InfoProvider, RateLimiter},
+    providers::nonce::NonceManager,
     providers::order_tracker::{OrderStatus, OrderTracker, TrackedOrder},
+    providers::retry::RetryPolicy,
+    providers::transport::{HyperTransport, Transport, TransportError},
     signers::{HyperliquidSignature, HyperliquidSigner},
     types::{
-        actions::*, eip712::HyperliquidAction, requests::*,
-        responses::ExchangeResponseStatus, Symbol,
+        actions::*,
+        eip712::HyperliquidAction,
+        info_types::{OpenOrdersResponse, PositionData},
+        precision::MAX_SPOT_DECIMALS,
+        requests::*,
+        responses::{ExchangeDataStatus, ExchangeResponse, ExchangeResponseStatus},
+        AssetPrecision, RoundingMode, Symbol,
     },
 };
 
 type Result<T> = std::result::Result<T, HyperliquidError>;
 
-/// Format a float for use in API requests
-/// Formats to 8 decimal places and removes trailing zeros
-fn format_float_string(value: f64) -> String {
-    let mut x = format!("{value:.8}");
-    while x.ends_with('0') {
-        x.pop();
-    }
-    if x.ends_with('.') {
-        x.pop();
-    }
-    if x == "-0" {
-        "0".to_string()
-    } else {
-        x
-    }
+/// Parse a decimal for use in API requests
+fn parse_decimal(value: &str, field: &str) -> Result<Decimal> {
+    value
+        .trim()
+        .parse::<Decimal>()
+        .map_err(|_| HyperliquidError::InvalidRequest(format!("Invalid {field} format")))
 }
 
 pub struct RawExchangeProvider<S: HyperliquidSigner> {
-    client: Client<HttpsConnector<HttpConnector>, Full<Bytes>>,
-    endpoint: &'static str,
-    rate_limiter: Arc<crate::providers::info::RateLimiter>,
+    transport: Arc<dyn Transport>,
+    network: Network,
+    endpoint: String,
+    rate_limiter: Arc<RateLimiter>,
+    retry_policy: RetryPolicy,
     signer: S,
     vault_address: Option<Address>,
     agent: Option<Address>,
     builder: Option<Address>,
     order_tracker: Option<OrderTracker>,
+    asset_registry: Option<Arc<AssetRegistry>>,
+    nonce_manager: Option<Arc<NonceManager>>,
+    info: InfoProvider,
 }
 
 impl<S: HyperliquidSigner> RawExchangeProvider<S> {
     // ==================== Helper Methods ====================
 
     pub(crate) fn infer_network(&self) -> (u64, &'static str) {
-        if self.endpoint.contains("testnet") {
-            (CHAIN_ID_TESTNET, AGENT_SOURCE_TESTNET)
-        } else {
+        if self.network.is_mainnet() {
             (CHAIN_ID_MAINNET, AGENT_SOURCE_MAINNET)
+        } else {
+            (CHAIN_ID_TESTNET, AGENT_SOURCE_TESTNET)
         }
     }
 
+    /// Get the network this provider sends actions to
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
     /// Get the configured builder address
     pub fn builder(&self) -> Option<Address> {
         self.builder
@@ -75,6 +84,111 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         self
     }
 
+    /// Resolve symbols passed to exchange methods through `registry`
+    pub fn with_asset_registry(mut self, registry: Arc<AssetRegistry>) -> Self {
+        self.asset_registry = Some(registry);
+        self
+    }
+
+    /// Draw L1 action nonces from `nonce_manager` instead of the clock
+    pub fn with_nonce_manager(mut self, nonce_manager: Arc<NonceManager>) -> Self {
+        self.nonce_manager = Some(nonce_manager);
+        self
+    }
+
+    /// Draw request weight from `rate_limiter`, e.g. one shared with an
+    /// [`InfoProvider`]
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.info = self.info.with_rate_limiter(rate_limiter.clone());
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Retry failed requests according to `policy`
+    ///
+    /// Actions are only resent when they cannot have reached the exchange; see
+    /// [`RetryPolicy`]. Info queries made by this provider use it as is.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.info = self.info.with_retry_policy(policy.clone());
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Send actions and info queries through `transport` instead of the
+    /// default hyper client
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.info = self.info.with_transport(transport.clone());
+        self.transport = transport;
+        self
+    }
+
+    /// Create a provider that signs with `signer` but otherwise shares this
+    /// provider's connection, rate limiter and settings
+    ///
+    /// The nonce manager is not carried over, since nonces are tracked per signer.
+    pub fn with_signer<T: HyperliquidSigner>(&self, signer: T) -> RawExchangeProvider<T> {
+        RawExchangeProvider {
+            transport: self.transport.clone(),
+            network: self.network.clone(),
+            endpoint: self.endpoint.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+            retry_policy: self.retry_policy.clone(),
+            signer,
+            vault_address: self.vault_address,
+            agent: self.agent,
+            builder: self.builder,
+            order_tracker: self.order_tracker.clone(),
+            asset_registry: self.asset_registry.clone(),
+            nonce_manager: None,
+            info: self.info.clone(),
+        }
+    }
+
+    /// Create a provider that trades for `sub_account`, signing as its master
+    ///
+    /// Shares this provider's connection, rate limiter, nonce manager and
+    /// settings. Order tracking, if enabled, starts empty for the sub-account.
+    pub fn for_sub_account(&self, sub_account: Address) -> RawExchangeProvider<S>
+    where
+        S: Clone,
+    {
+        RawExchangeProvider {
+            transport: self.transport.clone(),
+            network: self.network.clone(),
+            endpoint: self.endpoint.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+            retry_policy: self.retry_policy.clone(),
+            signer: self.signer.clone(),
+            vault_address: Some(sub_account),
+            agent: self.agent,
+            builder: self.builder,
+            order_tracker: self.order_tracker.as_ref().map(|_| OrderTracker::new()),
+            asset_registry: self.asset_registry.clone(),
+            nonce_manager: self.nonce_manager.clone(),
+            info: self.info.clone(),
+        }
+    }
+
+    /// Get the configured asset registry
+    pub fn asset_registry(&self) -> Option<&Arc<AssetRegistry>> {
+        self.asset_registry.as_ref()
+    }
+
+    /// Resolve an asset reference to its index
+    ///
+    /// Raw indices pass through unchanged; symbols require an asset registry.
+    pub fn resolve_asset(&self, asset: impl Into<AssetRef>) -> Result<u32> {
+        match asset.into() {
+            AssetRef::Index(index) => Ok(index),
+            AssetRef::Symbol(symbol) => match &self.asset_registry {
+                Some(registry) => registry.resolve(symbol),
+                None => Err(HyperliquidError::InvalidRequest(format!(
+                    "Cannot resolve {symbol} without an asset registry"
+                ))),
+            },
+        }
+    }
+
     // ==================== Order Tracking Methods ====================
 
     /// Get a tracked order by CLOID
@@ -140,72 +254,36 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
     // ==================== Constructors ====================
 
     pub fn mainnet(signer: S) -> Self {
-        Self::new(signer, EXCHANGE_ENDPOINT_MAINNET, None, None, None)
+        Self::new(signer, Network::Mainnet, None, None, None)
     }
 
     pub fn testnet(signer: S) -> Self {
-        Self::new(signer, EXCHANGE_ENDPOINT_TESTNET, None, None, None)
+        Self::new(signer, Network::Testnet, None, None, None)
     }
 
     pub fn mainnet_vault(signer: S, vault_address: Address) -> Self {
-        Self::new(
-            signer,
-            EXCHANGE_ENDPOINT_MAINNET,
-            Some(vault_address),
-            None,
-            None,
-        )
+        Self::new(signer, Network::Mainnet, Some(vault_address), None, None)
     }
 
     pub fn testnet_vault(signer: S, vault_address: Address) -> Self {
-        Self::new(
-            signer,
-            EXCHANGE_ENDPOINT_TESTNET,
-            Some(vault_address),
-            None,
-            None,
-        )
+        Self::new(signer, Network::Testnet, Some(vault_address), None, None)
     }
 
     pub fn mainnet_agent(signer: S, agent_address: Address) -> Self {
-        Self::new(
-            signer,
-            EXCHANGE_ENDPOINT_MAINNET,
-            None,
-            Some(agent_address),
-            None,
-        )
+        Self::new(signer, Network::Mainnet, None, Some(agent_address), None)
     }
 
     pub fn testnet_agent(signer: S, agent_address: Address) -> Self {
-        Self::new(
-            signer,
-            EXCHANGE_ENDPOINT_TESTNET,
-            None,
-            Some(agent_address),
-            None,
-        )
+        Self::new(signer, Network::Testnet, None, Some(agent_address), None)
     }
 
     // New builder-specific constructors
     pub fn mainnet_builder(signer: S, builder_address: Address) -> Self {
-        Self::new(
-            signer,
-            EXCHANGE_ENDPOINT_MAINNET,
-            None,
-            None,
-            Some(builder_address),
-        )
+        Self::new(signer, Network::Mainnet, None, None, Some(builder_address))
     }
 
     pub fn testnet_builder(signer: S, builder_address: Address) -> Self {
-        Self::new(
-            signer,
-            EXCHANGE_ENDPOINT_TESTNET,
-            None,
-            None,
-            Some(builder_address),
-        )
+        Self::new(signer, Network::Testnet, None, None, Some(builder_address))
     }
 
     // Combined constructors
@@ -215,7 +293,7 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         agent: Option<Address>,
         builder: Option<Address>,
     ) -> Self {
-        Self::new(signer, EXCHANGE_ENDPOINT_MAINNET, vault, agent, builder)
+        Self::new(signer, Network::Mainnet, vault, agent, builder)
     }
 
     pub fn testnet_with_options(
@@ -224,37 +302,50 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         agent: Option<Address>,
         builder: Option<Address>,
     ) -> Self {
-        Self::new(signer, EXCHANGE_ENDPOINT_TESTNET, vault, agent, builder)
+        Self::new(signer, Network::Testnet, vault, agent, builder)
+    }
+
+    /// Create a provider for any network, including custom endpoints
+    pub fn for_network(signer: S, network: Network) -> Self {
+        Self::new(signer, network, None, None, None)
+    }
+
+    pub fn for_network_with_options(
+        signer: S,
+        network: Network,
+        vault: Option<Address>,
+        agent: Option<Address>,
+        builder: Option<Address>,
+    ) -> Self {
+        Self::new(signer, network, vault, agent, builder)
     }
 
     fn new(
         signer: S,
-        endpoint: &'static str,
+        network: Network,
         vault_address: Option<Address>,
         agent: Option<Address>,
         builder: Option<Address>,
     ) -> Self {
-        let https = HttpsConnectorBuilder::new()
-            .with_native_roots()
-            .unwrap()
-            .https_only()
-            .enable_http1()
-            .build();
-        let client = Client::builder(hyper_util::rt::TokioExecutor::new()).build(https);
-        let rate_limiter = Arc::new(crate::providers::info::RateLimiter::new(
-            RATE_LIMIT_MAX_TOKENS,
-            RATE_LIMIT_REFILL_RATE,
-        ));
+        let transport: Arc<dyn Transport> = Arc::new(HyperTransport::new());
+        let rate_limiter = Arc::new(RateLimiter::default());
 
         Self {
-            client,
-            endpoint,
+            endpoint: network.exchange_url(),
+            info: InfoProvider::new(network.clone())
+                .with_rate_limiter(rate_limiter.clone())
+                .with_transport(transport.clone()),
+            transport,
+            network,
             rate_limiter,
+            retry_policy: RetryPolicy::default(),
             signer,
             vault_address,
             agent,
             builder,
             order_tracker: None,
+            asset_registry: None,
+            nonce_manager: None,
         }
     }
 
@@ -264,8 +355,6 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         &self,
         order: &OrderRequest,
     ) -> Result<ExchangeResponseStatus> {
-        self.rate_limiter.check_weight(WEIGHT_PLACE_ORDER)?;
-
         // Auto-generate CLOID if tracking is enabled and order doesn't have one
         let mut order = order.clone();
         let cloid = if let Some(tracker) = &self.order_tracker {
@@ -294,7 +383,7 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
 
         let bulk_order = BulkOrder {
             orders: vec![order],
-            grouping: "na".to_string(),
+            grouping: OrderGrouping::Na,
             builder: self.builder.map(|addr| BuilderInfo {
                 builder: format!("0x{}", hex::encode(addr)),
                 fee: 0, // Default fee, use place_order_with_builder_fee to specify
@@ -333,8 +422,6 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         order: &OrderRequest,
         builder_fee: u64,
     ) -> Result<ExchangeResponseStatus> {
-        self.rate_limiter.check_weight(WEIGHT_PLACE_ORDER)?;
-
         // Auto-generate CLOID if tracking is enabled and order doesn't have one
         let mut order = order.clone();
         let cloid = if let Some(tracker) = &self.order_tracker {
@@ -363,7 +450,7 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
 
         let bulk_order = BulkOrder {
             orders: vec![order],
-            grouping: "na".to_string(),
+            grouping: OrderGrouping::Na,
             builder: self.builder.map(|addr| BuilderInfo {
                 builder: format!("0x{}", hex::encode(addr)),
                 fee: builder_fee,
@@ -409,11 +496,10 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
 
     pub async fn cancel_order(
         &self,
-        asset: u32,
+        asset: impl Into<AssetRef>,
         oid: u64,
     ) -> Result<ExchangeResponseStatus> {
-        self.rate_limiter.check_weight(WEIGHT_CANCEL_ORDER)?;
-
+        let asset = self.resolve_asset(asset)?;
         let bulk_cancel = BulkCancel {
             cancels: vec![CancelRequest { asset, oid }],
         };
@@ -423,11 +509,10 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
 
     pub async fn cancel_order_by_cloid(
         &self,
-        asset: u32,
+        asset: impl Into<AssetRef>,
         cloid: Uuid,
     ) -> Result<ExchangeResponseStatus> {
-        self.rate_limiter.check_weight(WEIGHT_CANCEL_ORDER)?;
-
+        let asset = self.resolve_asset(asset)?;
         let bulk_cancel = BulkCancelCloid {
             cancels: vec![CancelRequestCloid::new(asset, cloid)],
         };
@@ -440,16 +525,216 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         oid: u64,
         new_order: OrderRequest,
     ) -> Result<ExchangeResponseStatus> {
-        self.rate_limiter.check_weight(WEIGHT_MODIFY_ORDER)?;
+        let modifies = vec![ModifyRequest::new(oid, new_order)];
+        self.send_modifies(modifies).await
+    }
+
+    /// Modify an order by client order ID
+    ///
+    /// The new order keeps the cloid unless it sets its own.
+    pub async fn modify_order_by_cloid(
+        &self,
+        cloid: Uuid,
+        mut new_order: OrderRequest,
+    ) -> Result<ExchangeResponseStatus> {
+        if new_order.cloid.is_none() {
+            new_order = new_order.with_cloid(Some(cloid));
+        }
+        let modifies = vec![ModifyRequest::new(cloid, new_order)];
+        self.send_modifies(modifies).await
+    }
+
+    // Send a batchModify, moving tracked orders over to their new requests
+    async fn send_modifies(
+        &self,
+        modifies: Vec<ModifyRequest>,
+    ) -> Result<ExchangeResponseStatus> {
+        // The original orders stay live until the exchange accepts a modify, so
+        // the tracker is only touched once the response is in
+        let tracked: Vec<(usize, Uuid, OrderRequest)> = match &self.order_tracker {
+            Some(_) => modifies
+                .iter()
+                .enumerate()
+                .filter_map(|(index, modify)| {
+                    let cloid = match modify.oid {
+                        OrderId::Cloid(cloid) => cloid,
+                        OrderId::Oid(_) => {
+                            Uuid::parse_str(modify.order.cloid.as_deref()?).ok()?
+                        }
+                    };
+                    Some((index, cloid, modify.order.clone()))
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let bulk_modify = BulkModify { modifies };
+        let result = self.send_l1_action("batchModify", &bulk_modify).await;
+
+        if let (Some(tracker), Ok(response @ ExchangeResponseStatus::Ok(ok))) =
+            (&self.order_tracker, &result)
+        {
+            let statuses = ok.data.as_ref().map(|data| &data.statuses);
+            for (index, cloid, order) in tracked {
+                let accepted = statuses
+                    .and_then(|statuses| statuses.get(index))
+                    .is_some_and(ExchangeDataStatus::is_success);
+                if accepted {
+                    let cloid = tracker.update_order(&cloid, order);
+                    tracker.update_order_status(
+                        &cloid,
+                        OrderStatus::Submitted,
+                        Some(response.clone()),
+                    );
+                }
+            }
+        }
 
-        let bulk_modify = BulkModify {
-            modifies: vec![ModifyRequest {
-                oid,
-                order: new_order,
-            }],
+        result
+    }
+
+    // ==================== TWAP Orders ====================
+
+    /// Start a TWAP order executed in slices over `minutes`
+    ///
+    /// The size is rounded to the asset's `szDecimals` when the asset registry
+    /// knows the asset. The TWAP ID is in the response's `data.status`.
+    pub async fn place_twap(
+        &self,
+        asset: impl Into<AssetRef>,
+        is_buy: bool,
+        sz: Decimal,
+        minutes: u32,
+        randomize: bool,
+        reduce_only: bool,
+    ) -> Result<ExchangeResponseStatus> {
+        let asset = self.resolve_asset(asset)?;
+        let registry = self.asset_registry.as_ref();
+        let sz = match registry.and_then(|registry| registry.get_by_index(asset)) {
+            Some(info) => info.precision().round_size(sz, RoundingMode::Nearest),
+            None => sz,
+        };
+        let twap = TwapOrder {
+            twap: TwapRequest {
+                asset,
+                is_buy,
+                sz,
+                reduce_only,
+                minutes,
+                randomize,
+            },
         };
 
-        self.send_l1_action("batchModify", &bulk_modify).await
+        self.send_l1_action("twapOrder", &twap).await
+    }
+
+    /// Cancel a running TWAP order
+    pub async fn cancel_twap(
+        &self,
+        asset: impl Into<AssetRef>,
+        twap_id: u64,
+    ) -> Result<ExchangeResponseStatus> {
+        let asset = self.resolve_asset(asset)?;
+        let cancel = TwapCancel { asset, twap_id };
+        self.send_l1_action("twapCancel", &cancel).await
+    }
+
+    // ==================== Market Orders ====================
+
+    /// Open a position with an IOC order priced `slippage` away from the mid
+    ///
+    /// `slippage` is a fraction below 1, e.g. `0.05` for 5%. The mid comes from
+    /// `allMids`, falling back to the L2 book. Requires an asset registry.
+    pub async fn market_open(
+        &self,
+        asset: impl Into<AssetRef>,
+        is_buy: bool,
+        sz: Decimal,
+        slippage: Decimal,
+    ) -> Result<ExchangeResponseStatus> {
+        let info = self.asset_info(asset)?;
+        let order = self.market_order(&info, is_buy, sz, slippage).await?;
+        self.place_order(&order).await
+    }
+
+    /// Close the current position with a reduce-only IOC order
+    ///
+    /// Closes the whole position unless `sz` is given. The position is read
+    /// from `clearinghouseState` for the vault, if set, otherwise the signer.
+    pub async fn market_close(
+        &self,
+        asset: impl Into<AssetRef>,
+        sz: Option<Decimal>,
+        slippage: Decimal,
+    ) -> Result<ExchangeResponseStatus> {
+        let info = self.asset_info(asset)?;
+        let user = self.vault_address.unwrap_or_else(|| self.signer.address());
+        let state = self.info.user_state(user).await?;
+
+        let szi = state
+            .asset_positions
+            .iter()
+            .find(|p| p.position.coin == info.coin())
+            .map(|p| p.position.szi)
+            .filter(|szi| !szi.is_zero())
+            .ok_or_else(|| {
+                HyperliquidError::InvalidRequest(format!(
+                    "No open position in {}",
+                    info.coin()
+                ))
+            })?;
+
+        let is_buy = szi.is_sign_negative();
+        let sz = sz.unwrap_or(szi.abs());
+        let order = self
+            .market_order(&info, is_buy, sz, slippage)
+            .await?
+            .reduce_only(true);
+        self.place_order(&order).await
+    }
+
+    fn asset_info(&self, asset: impl Into<AssetRef>) -> Result<AssetInfo> {
+        let registry = self.asset_registry.as_ref().ok_or_else(|| {
+            HyperliquidError::InvalidRequest(
+                "Market orders require an asset registry".to_string(),
+            )
+        })?;
+        let index = registry.resolve(asset)?;
+        registry
+            .get_by_index(index)
+            .ok_or_else(|| HyperliquidError::AssetNotFound(index.to_string()))
+    }
+
+    async fn market_order(
+        &self,
+        info: &AssetInfo,
+        is_buy: bool,
+        sz: Decimal,
+        slippage: Decimal,
+    ) -> Result<OrderRequest> {
+        check_slippage(slippage)?;
+        let mid = self.mid_price(info.coin()).await?;
+        Ok(market_order_at(info, is_buy, sz, slippage, mid))
+    }
+
+    async fn mid_price(&self, coin: &str) -> Result<Decimal> {
+        match self.info.all_mids().await?.get(coin) {
+            Some(mid) => Ok(*mid),
+            None => self.book_mid(coin).await,
+        }
+    }
+
+    async fn book_mid(&self, coin: &str) -> Result<Decimal> {
+        let book = self.info.l2_book(coin.to_string()).await?;
+        match (
+            book.levels.first().and_then(|bids| bids.first()),
+            book.levels.get(1).and_then(|asks| asks.first()),
+        ) {
+            (Some(bid), Some(ask)) => Ok((bid.px + ask.px) / Decimal::TWO),
+            _ => Err(HyperliquidError::InvalidResponse(format!(
+                "No mid price available for {coin}"
+            ))),
+        }
     }
 
     // ==================== Bulk Operations ====================
@@ -458,11 +743,9 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         &self,
         orders: Vec<OrderRequest>,
     ) -> Result<ExchangeResponseStatus> {
-        self.rate_limiter.check_weight(WEIGHT_BULK_ORDER)?;
-
         let bulk_order = BulkOrder {
             orders,
-            grouping: "na".to_string(),
+            grouping: OrderGrouping::Na,
             builder: self.builder.map(|addr| BuilderInfo {
                 builder: format!("0x{}", hex::encode(addr)),
                 fee: 0, // Default fee, use bulk_orders_with_builder_fee to specify
@@ -472,16 +755,32 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         self.send_l1_action("order", &bulk_order).await
     }
 
+    /// Send orders in one action, grouped as TP/SL brackets if requested
+    pub async fn bulk_orders_with_grouping(
+        &self,
+        orders: Vec<OrderRequest>,
+        grouping: OrderGrouping,
+    ) -> Result<ExchangeResponseStatus> {
+        let bulk_order = BulkOrder {
+            orders,
+            grouping,
+            builder: self.builder.map(|addr| BuilderInfo {
+                builder: format!("0x{}", hex::encode(addr)),
+                fee: 0,
+            }),
+        };
+
+        self.send_l1_action("order", &bulk_order).await
+    }
+
     pub async fn bulk_orders_with_builder_fee(
         &self,
         orders: Vec<OrderRequest>,
         builder_fee: u64,
     ) -> Result<ExchangeResponseStatus> {
-        self.rate_limiter.check_weight(WEIGHT_BULK_ORDER)?;
-
         let bulk_order = BulkOrder {
             orders,
-            grouping: "na".to_string(),
+            grouping: OrderGrouping::Na,
             builder: self.builder.map(|addr| BuilderInfo {
                 builder: format!("0x{}", hex::encode(addr)),
                 fee: builder_fee,
@@ -507,8 +806,6 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         &self,
         cancels: Vec<CancelRequest>,
     ) -> Result<ExchangeResponseStatus> {
-        self.rate_limiter.check_weight(WEIGHT_BULK_CANCEL)?;
-
         let bulk_cancel = BulkCancel { cancels };
         self.send_l1_action("cancel", &bulk_cancel).await
     }
@@ -517,8 +814,6 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         &self,
         cancels: Vec<CancelRequestCloid>,
     ) -> Result<ExchangeResponseStatus> {
-        self.rate_limiter.check_weight(WEIGHT_BULK_CANCEL)?;
-
         let bulk_cancel = BulkCancelCloid { cancels };
         self.send_l1_action("cancelByCloid", &bulk_cancel).await
     }
@@ -527,20 +822,121 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         &self,
         modifies: Vec<ModifyRequest>,
     ) -> Result<ExchangeResponseStatus> {
-        self.rate_limiter.check_weight(WEIGHT_BULK_ORDER)?;
+        self.send_modifies(modifies).await
+    }
 
-        let bulk_modify = BulkModify { modifies };
-        self.send_l1_action("batchModify", &bulk_modify).await
+    // ==================== Cancel All / Flatten All ====================
+
+    /// Cancel every open order, or only those in `symbol`
+    ///
+    /// Open orders are read for the vault, if set, otherwise the signer, and
+    /// cancelled in chunks of [`MAX_CANCELS_PER_ACTION`]. Coins are resolved
+    /// through the asset registry.
+    pub async fn cancel_all(
+        &self,
+        symbol: Option<Symbol>,
+    ) -> Result<BulkReport<OpenOrdersResponse>> {
+        let user = self.vault_address.unwrap_or_else(|| self.signer.address());
+        let open_orders = self.info.open_orders(user).await?;
+
+        let mut report = BulkReport::default();
+        let mut cancellable = Vec::new();
+        for order in open_orders {
+            if symbol.as_ref().is_some_and(|s| s.as_str() != order.coin) {
+                continue;
+            }
+            match self.resolve_asset(order.coin.clone()) {
+                Ok(asset) => {
+                    cancellable.push((CancelRequest::new(asset, order.oid), order))
+                }
+                Err(e) => report.failed.push((order, e.to_string())),
+            }
+        }
+
+        let mut cancellable = cancellable.into_iter().peekable();
+        while cancellable.peek().is_some() {
+            let (cancels, orders): (Vec<_>, Vec<_>) =
+                cancellable.by_ref().take(MAX_CANCELS_PER_ACTION).unzip();
+            let result = self.bulk_cancel(cancels).await;
+            report.record(orders, result);
+        }
+
+        Ok(report)
+    }
+
+    /// Close every open position with reduce-only IOC orders
+    ///
+    /// Each order is priced `slippage` away from the mid, as in
+    /// [`market_close`](Self::market_close), and all are sent in one action.
+    /// Positions an order only partly closed are reported as failed. Requires
+    /// an asset registry.
+    pub async fn flatten_all(
+        &self,
+        slippage: Decimal,
+    ) -> Result<BulkReport<PositionData>> {
+        check_slippage(slippage)?;
+        let user = self.vault_address.unwrap_or_else(|| self.signer.address());
+        let state = self.info.user_state(user).await?;
+
+        let mut report = BulkReport::default();
+        let open: Vec<PositionData> = state
+            .asset_positions
+            .into_iter()
+            .map(|asset_position| asset_position.position)
+            .filter(|position| !position.szi.is_zero())
+            .collect();
+        if open.is_empty() {
+            return Ok(report);
+        }
+
+        // Every close is priced from one snapshot of the mids
+        let mids = self.info.all_mids().await?;
+        let mut orders = Vec::new();
+        let mut positions = Vec::new();
+        for position in open {
+            let order = match self.asset_info(position.coin.clone()) {
+                Ok(info) => {
+                    let mid = match mids.get(info.coin()) {
+                        Some(mid) => Ok(*mid),
+                        None => self.book_mid(info.coin()).await,
+                    };
+                    let is_buy = position.szi.is_sign_negative();
+                    let sz = position.szi.abs();
+                    mid.map(|mid| market_order_at(&info, is_buy, sz, slippage, mid))
+                }
+                Err(e) => Err(e),
+            };
+            match order {
+                Ok(order) => {
+                    orders.push(order.reduce_only(true));
+                    positions.push(position);
+                }
+                Err(e) => report.failed.push((position, e.to_string())),
+            }
+        }
+
+        let result = self.bulk_orders(orders).await;
+        report.record_checked(positions, result, |position, status| {
+            let ExchangeDataStatus::Filled(filled) = status else {
+                return None;
+            };
+            let sz = position.szi.abs();
+            (filled.total_sz < sz)
+                .then(|| format!("Partially filled: {} of {sz}", filled.total_sz))
+        });
+
+        Ok(report)
     }
 
     // ==================== Account Management ====================
 
     pub async fn update_leverage(
         &self,
-        asset: u32,
+        asset: impl Into<AssetRef>,
         is_cross: bool,
         leverage: u32,
     ) -> Result<ExchangeResponseStatus> {
+        let asset = self.resolve_asset(asset)?;
         let update = UpdateLeverage {
             asset,
             is_cross,
@@ -551,10 +947,11 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
 
     pub async fn update_isolated_margin(
         &self,
-        asset: u32,
+        asset: impl Into<AssetRef>,
         is_buy: bool,
         ntli: i64,
     ) -> Result<ExchangeResponseStatus> {
+        let asset = self.resolve_asset(asset)?;
         let update = UpdateIsolatedMargin {
             asset,
             is_buy,
@@ -568,6 +965,27 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         self.send_l1_action("setReferrer", &referrer).await
     }
 
+    /// Cancel all open orders at `time` (milliseconds since epoch)
+    ///
+    /// The time must be at least 5 seconds in the future. `None` clears a
+    /// previously scheduled cancel.
+    pub async fn schedule_cancel(
+        &self,
+        time: Option<u64>,
+    ) -> Result<ExchangeResponseStatus> {
+        let schedule = ScheduleCancel { time };
+        self.send_l1_action("scheduleCancel", &schedule).await
+    }
+
+    /// Buy `weight` additional address-based requests, paid from the perp balance
+    pub async fn reserve_request_weight(
+        &self,
+        weight: u64,
+    ) -> Result<ExchangeResponseStatus> {
+        let reserve = ReserveRequestWeight { weight };
+        self.send_l1_action("reserveRequestWeight", &reserve).await
+    }
+
     // ==================== User Actions (EIP-712) ====================
 
     pub async fn usd_transfer(
@@ -686,11 +1104,10 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         let agent_address = signer.address();
 
         // Get chain info
-        let (_, _) = self.infer_network();
-        let chain = if self.endpoint.contains("testnet") {
-            "Testnet"
-        } else {
+        let chain = if self.network.is_mainnet() {
             "Mainnet"
+        } else {
+            "Testnet"
         };
 
         // Create the action with proper Address type
@@ -731,6 +1148,79 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         self.send_user_action(&action).await
     }
 
+    // ==================== Staking ====================
+
+    /// Delegate staked HYPE to `validator`, or undelegate from it
+    ///
+    /// `wei` is in units of 1e-8 HYPE.
+    pub async fn token_delegate(
+        &self,
+        validator: Address,
+        wei: u64,
+        is_undelegate: bool,
+    ) -> Result<ExchangeResponseStatus> {
+        let (chain_id, _) = self.infer_network();
+        let chain = if chain_id == CHAIN_ID_MAINNET {
+            "Mainnet"
+        } else {
+            "Testnet"
+        };
+
+        let action = TokenDelegate {
+            signature_chain_id: chain_id,
+            hyperliquid_chain: chain.to_string(),
+            validator,
+            wei,
+            is_undelegate,
+            nonce: Self::current_nonce(),
+        };
+
+        self.send_user_action(&action).await
+    }
+
+    /// Move HYPE from the spot balance into staking (`cDeposit`)
+    ///
+    /// `wei` is in units of 1e-8 HYPE.
+    pub async fn staking_deposit(&self, wei: u64) -> Result<ExchangeResponseStatus> {
+        let (chain_id, _) = self.infer_network();
+        let chain = if chain_id == CHAIN_ID_MAINNET {
+            "Mainnet"
+        } else {
+            "Testnet"
+        };
+
+        let action = CDeposit {
+            signature_chain_id: chain_id,
+            hyperliquid_chain: chain.to_string(),
+            wei,
+            nonce: Self::current_nonce(),
+        };
+
+        self.send_user_action(&action).await
+    }
+
+    /// Move undelegated HYPE from staking back to the spot balance (`cWithdraw`)
+    ///
+    /// Withdrawals are queued by the exchange before they arrive. `wei` is in
+    /// units of 1e-8 HYPE.
+    pub async fn staking_withdraw(&self, wei: u64) -> Result<ExchangeResponseStatus> {
+        let (chain_id, _) = self.infer_network();
+        let chain = if chain_id == CHAIN_ID_MAINNET {
+            "Mainnet"
+        } else {
+            "Testnet"
+        };
+
+        let action = CWithdraw {
+            signature_chain_id: chain_id,
+            hyperliquid_chain: chain.to_string(),
+            wei,
+            nonce: Self::current_nonce(),
+        };
+
+        self.send_user_action(&action).await
+    }
+
     // ==================== Vault Operations ====================
 
     pub async fn vault_transfer(
@@ -748,96 +1238,137 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         self.send_l1_action("vaultTransfer", &transfer).await
     }
 
-    // ==================== Spot Operations ====================
-
-    pub async fn spot_transfer_to_perp(
+    /// Create a vault led by the signer, seeded with `initial_usd`
+    ///
+    /// `initial_usd` is in millionths of a USDC. The new vault's address is in
+    /// the response's `data.address`.
+    pub async fn create_vault(
         &self,
-        usd_size: u64,
-        to_perp: bool,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        initial_usd: u64,
     ) -> Result<ExchangeResponseStatus> {
-        let transfer = ClassTransfer { usd_size, to_perp };
+        let nonce = self.next_l1_nonce();
+        let create = CreateVault {
+            name: name.into(),
+            description: description.into(),
+            initial_usd,
+            nonce,
+        };
+        self.send_l1_action_with_nonce("createVault", &create, nonce)
+            .await
+    }
 
-        let spot_user = SpotUser {
-            class_transfer: transfer,
+    /// Change a vault's settings, leaving those passed as `None` unchanged
+    pub async fn vault_modify(
+        &self,
+        vault_address: Address,
+        allow_deposits: Option<bool>,
+        always_close_on_withdraw: Option<bool>,
+    ) -> Result<ExchangeResponseStatus> {
+        let modify = VaultModify {
+            vault_address: format!("{vault_address:#x}"),
+            allow_deposits,
+            always_close_on_withdraw,
         };
+        self.send_l1_action("vaultModify", &modify).await
+    }
 
-        self.send_l1_action("spotUser", &spot_user).await
+    /// Distribute `usd` of a vault's profits to its followers
+    ///
+    /// `usd` is in millionths of a USDC.
+    pub async fn vault_distribute(
+        &self,
+        vault_address: Address,
+        usd: u64,
+    ) -> Result<ExchangeResponseStatus> {
+        let distribute = VaultDistribute {
+            vault_address: format!("{vault_address:#x}"),
+            usd,
+        };
+        self.send_l1_action("vaultDistribute", &distribute).await
     }
 
-    // ==================== Helper Methods ====================
+    // ==================== Sub-Accounts ====================
 
-    fn current_nonce() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64
+    /// Create a sub-account of the signer
+    ///
+    /// The new sub-account's address is in the response's `data.address`.
+    pub async fn create_sub_account(
+        &self,
+        name: impl Into<String>,
+    ) -> Result<ExchangeResponseStatus> {
+        let create = CreateSubAccount { name: name.into() };
+        self.send_l1_action("createSubAccount", &create).await
     }
 
-    fn hash_action<T: Serialize>(
-        action_type: &str,
-        action: &T,
-        timestamp: u64,
-        vault_address: Option<Address>,
-    ) -> Result<B256> {
-        // Create an enum wrapper for proper serialization
-        // This matches how the original Hyperliquid SDK serializes actions
-        // The enum variant becomes the "type" field in the serialized output
-        #[derive(serde::Serialize)]
-        #[serde(tag = "type")]
-        #[serde(rename_all = "camelCase")]
-        enum ActionWrapper<'a, T> {
-            Order(&'a T),
-            Cancel(&'a T),
-            CancelByCloid(&'a T),
-            BatchModify(&'a T),
-            UpdateLeverage(&'a T),
-            UpdateIsolatedMargin(&'a T),
-            UsdSend(&'a T),
-            SpotSend(&'a T),
-            SpotUser(&'a T),
-            VaultTransfer(&'a T),
-            SetReferrer(&'a T),
-            ApproveAgent(&'a T),
-            ApproveBuilderFee(&'a T),
-            Withdraw3(&'a T),
-        }
+    /// Move USDC between the master's and a sub-account's perp balance
+    ///
+    /// `usd` is in millionths of a USDC. Deposits go to the sub-account.
+    pub async fn sub_account_transfer(
+        &self,
+        sub_account: Address,
+        is_deposit: bool,
+        usd: u64,
+    ) -> Result<ExchangeResponseStatus> {
+        let transfer = SubAccountTransfer {
+            sub_account_user: format!("{sub_account:#x}"),
+            is_deposit,
+            usd,
+        };
+        self.send_l1_action("subAccountTransfer", &transfer).await
+    }
 
-        // Wrap the action based on type
-        let wrapped = match action_type {
-            "order" => ActionWrapper::Order(action),
-            "cancel" => ActionWrapper::Cancel(action),
-            "cancelByCloid" => ActionWrapper::CancelByCloid(action),
-            "batchModify" => ActionWrapper::BatchModify(action),
-            "updateLeverage" => ActionWrapper::UpdateLeverage(action),
-            "updateIsolatedMargin" => ActionWrapper::UpdateIsolatedMargin(action),
-            "usdSend" => ActionWrapper::UsdSend(action),
-            "spotSend" => ActionWrapper::SpotSend(action),
-            "spotUser" => ActionWrapper::SpotUser(action),
-            "vaultTransfer" => ActionWrapper::VaultTransfer(action),
-            "setReferrer" => ActionWrapper::SetReferrer(action),
-            "approveAgent" => ActionWrapper::ApproveAgent(action),
-            "approveBuilderFee" => ActionWrapper::ApproveBuilderFee(action),
-            "withdraw3" => ActionWrapper::Withdraw3(action),
-            _ => {
-                return Err(HyperliquidError::InvalidRequest(format!(
-                    "Unknown action type: {action_type}"
-                )))
-            }
+    /// Move a spot token between the master's and a sub-account's spot balance
+    ///
+    /// `token` is in `NAME:tokenId` form, e.g. `USDC:0x6d1e7cde53ba9467b783cb7c530ce054`.
+    pub async fn sub_account_spot_transfer(
+        &self,
+        sub_account: Address,
+        is_deposit: bool,
+        token: &str,
+        amount: &str,
+    ) -> Result<ExchangeResponseStatus> {
+        let transfer = SubAccountSpotTransfer {
+            sub_account_user: format!("{sub_account:#x}"),
+            is_deposit,
+            token: token.to_string(),
+            amount: amount.to_string(),
         };
+        self.send_l1_action("subAccountSpotTransfer", &transfer)
+            .await
+    }
 
-        // NOTE: Hyperliquid uses MessagePack (rmp_serde) for action serialization
-        // This is different from typical EVM systems that use RLP
-        let mut bytes = rmp_serde::to_vec_named(&wrapped).map_err(|e| {
-            HyperliquidError::InvalidRequest(format!("Failed to serialize action: {e}"))
-        })?;
-        bytes.extend(timestamp.to_be_bytes());
-        if let Some(vault) = vault_address {
-            bytes.push(1);
-            bytes.extend(vault.as_slice());
-        } else {
-            bytes.push(0);
+    // ==================== Spot Operations ====================
+
+    pub async fn spot_transfer_to_perp(
+        &self,
+        usd_size: u64,
+        to_perp: bool,
+    ) -> Result<ExchangeResponseStatus> {
+        let transfer = ClassTransfer { usd_size, to_perp };
+
+        let spot_user = SpotUser {
+            class_transfer: transfer,
+        };
+
+        self.send_l1_action("spotUser", &spot_user).await
+    }
+
+    // ==================== Helper Methods ====================
+
+    fn current_nonce() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    fn next_l1_nonce(&self) -> u64 {
+        match &self.nonce_manager {
+            Some(nonce_manager) => nonce_manager.next_nonce(None),
+            None => Self::current_nonce(),
         }
-        Ok(keccak256(bytes))
     }
 
     async fn send_l1_action<T: Serialize>(
@@ -845,9 +1376,29 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         action_type: &str,
         action: &T,
     ) -> Result<ExchangeResponseStatus> {
-        let nonce = Self::current_nonce();
+        let nonce = self.next_l1_nonce();
+        self.send_l1_action_with_nonce(action_type, action, nonce)
+            .await
+    }
+
+    // For actions that repeat the nonce in their body
+    async fn send_l1_action_with_nonce<T: Serialize>(
+        &self,
+        action_type: &str,
+        action: &T,
+        nonce: u64,
+    ) -> Result<ExchangeResponseStatus> {
+        // Build action value with type tag
+        let mut action_value = serde_json::to_value(action)?;
+        if let Value::Object(ref mut map) = action_value {
+            map.insert("type".to_string(), json!(action_type));
+        }
+        self.rate_limiter
+            .acquire(exchange_weight(&action_value))
+            .await?;
+
         let connection_id =
-            Self::hash_action(action_type, action, nonce, self.vault_address)?;
+            hash_l1_action(action_type, action, nonce, self.vault_address)?;
 
         // Create Agent L1 action
         let (_, agent_source) = self.infer_network();
@@ -861,12 +1412,6 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         let signing_hash = agent.eip712_signing_hash(&domain);
         let signature = self.signer.sign_hash(signing_hash).await?;
 
-        // Build action value with type tag
-        let mut action_value = serde_json::to_value(action)?;
-        if let Value::Object(ref mut map) = action_value {
-            map.insert("type".to_string(), json!(action_type));
-        }
-
         // Wrap action if using agent
         let final_action = if let Some(agent_address) = &self.agent {
             let (_, agent_source) = self.infer_network();
@@ -887,6 +1432,8 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
         &self,
         action: &T,
     ) -> Result<ExchangeResponseStatus> {
+        self.rate_limiter.acquire(WEIGHT_EXCHANGE_DEFAULT).await?;
+
         let domain = action.domain();
         let signing_hash = action.eip712_signing_hash(&domain);
         let signature = self.signer.sign_hash(signing_hash).await?;
@@ -913,6 +1460,9 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
             "Withdraw" => "withdraw3",
             "SpotSend" => "spotSend",
             "ApproveBuilderFee" => "approveBuilderFee",
+            "TokenDelegate" => "tokenDelegate",
+            "CDeposit" => "cDeposit",
+            "CWithdraw" => "cWithdraw",
             _ => action_type,
         };
 
@@ -944,36 +1494,37 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
             "vaultAddress": self.vault_address,
         });
 
-        let body = Full::new(Bytes::from(serde_json::to_vec(&payload)?));
-        let request = Request::builder()
-            .method(Method::POST)
-            .uri(self.endpoint)
-            .header("Content-Type", "application/json")
-            .body(body)
-            .map_err(|e| HyperliquidError::Network(e.to_string()))?;
-
-        let response = self
-            .client
-            .request(request)
-            .await
-            .map_err(|e| HyperliquidError::Network(e.to_string()))?;
-        let status = response.status();
-        let body_bytes = response
-            .into_body()
-            .collect()
-            .await
-            .map_err(|e| HyperliquidError::Network(e.to_string()))?
-            .to_bytes();
+        let body = Bytes::from(serde_json::to_vec(&payload)?);
+
+        // Retries resend the same nonce and signature, and only when the action
+        // cannot have been processed
+        let mut retry = 0;
+        let (status, body_bytes) = loop {
+            let policy = &self.retry_policy;
+            let can_retry = retry + 1 < policy.max_attempts;
+            match self.transport.post(&self.endpoint, body.clone()).await {
+                // Nothing was sent
+                Err(TransportError::NotSent(_))
+                    if policy.retry_network_errors && can_retry => {}
+                Err(e) => return Err(e.into()),
+                // Turned away before being processed
+                Ok((429, _)) if policy.retry_statuses.contains(&429) && can_retry => {}
+                Ok(response) => break response,
+            }
+
+            tokio::time::sleep(policy.backoff(retry)).await;
+            retry += 1;
+        };
 
         // Always try to deserialize the response as ExchangeResponseStatus
         // The API returns this format even for error status codes
         serde_json::from_slice(&body_bytes).map_err(|e| {
             // If deserialization fails and we have an error status,
             // return the HTTP error with the body
-            if !status.is_success() {
+            if !(200..300).contains(&status) {
                 let body_text = String::from_utf8_lossy(&body_bytes);
                 HyperliquidError::Http {
-                    status: status.as_u16(),
+                    status,
                     body: body_text.to_string(),
                 }
             } else {
@@ -985,30 +1536,108 @@ impl<S: HyperliquidSigner> RawExchangeProvider<S> {
     }
 }
 
+/// Per-item outcome of [`RawExchangeProvider::cancel_all`] and
+/// [`RawExchangeProvider::flatten_all`]
+#[derive(Debug, Clone)]
+pub struct BulkReport<T> {
+    pub succeeded: Vec<T>,
+    /// Items that failed, with the exchange or transport error
+    pub failed: Vec<(T, String)>,
+}
+
+impl<T> Default for BulkReport<T> {
+    fn default() -> Self {
+        Self {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+}
+
+impl<T> BulkReport<T> {
+    /// Whether every item succeeded
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    // Match items to the statuses of the action that carried them
+    fn record(&mut self, items: Vec<T>, result: Result<ExchangeResponseStatus>) {
+        self.record_checked(items, result, |_, _| None);
+    }
+
+    // As `record`, also failing items whose status `check` finds fault with
+    fn record_checked(
+        &mut self,
+        items: Vec<T>,
+        result: Result<ExchangeResponseStatus>,
+        check: impl Fn(&T, &ExchangeDataStatus) -> Option<String>,
+    ) {
+        let statuses = match result.map(ExchangeResponseStatus::into_result) {
+            Ok(Ok(response)) => response.data.map(|data| data.statuses),
+            Ok(Err(e)) => {
+                self.fail_all(items, &e);
+                return;
+            }
+            Err(e) => {
+                self.fail_all(items, &e.to_string());
+                return;
+            }
+        };
+
+        let mut statuses = statuses.unwrap_or_default().into_iter();
+        for item in items {
+            match statuses.next() {
+                Some(ExchangeDataStatus::Error(e)) => self.failed.push((item, e)),
+                Some(status) => match check(&item, &status) {
+                    Some(e) => self.failed.push((item, e)),
+                    None => self.succeeded.push(item),
+                },
+                None => self.failed.push((item, "Missing status".to_string())),
+            }
+        }
+    }
+
+    fn fail_all(&mut self, items: Vec<T>, error: &str) {
+        self.failed
+            .extend(items.into_iter().map(|item| (item, error.to_string())));
+    }
+}
+
 // ==================== OrderBuilder Pattern ====================
 
+// Order type as last set on an `OrderBuilder`
+enum BuilderOrderType {
+    Given(OrderType),
+    // Trigger price parsed on build
+    MarketTrigger { trigger_px: String, tpsl: String },
+}
+
 pub struct OrderBuilder<'a, S: HyperliquidSigner> {
     provider: &'a RawExchangeProvider<S>,
-    asset: u32,
+    asset: AssetRef,
     is_buy: Option<bool>,
     limit_px: Option<String>,
     sz: Option<String>,
     reduce_only: bool,
-    order_type: Option<OrderType>,
+    order_type: Option<BuilderOrderType>,
     cloid: Option<Uuid>,
+    precision: Option<AssetPrecision>,
+    rounding: RoundingMode,
 }
 
 impl<'a, S: HyperliquidSigner> OrderBuilder<'a, S> {
-    pub fn new(provider: &'a RawExchangeProvider<S>, asset: u32) -> Self {
+    pub fn new(provider: &'a RawExchangeProvider<S>, asset: impl Into<AssetRef>) -> Self {
         Self {
             provider,
-            asset,
+            asset: asset.into(),
             is_buy: None,
             limit_px: None,
             sz: None,
             reduce_only: false,
             order_type: None,
             cloid: None,
+            precision: None,
+            rounding: RoundingMode::default(),
         }
     }
 
@@ -1038,7 +1667,7 @@ impl<'a, S: HyperliquidSigner> OrderBuilder<'a, S> {
     }
 
     pub fn order_type(mut self, order_type: OrderType) -> Self {
-        self.order_type = Some(order_type);
+        self.order_type = Some(BuilderOrderType::Given(order_type));
         self
     }
 
@@ -1047,6 +1676,20 @@ impl<'a, S: HyperliquidSigner> OrderBuilder<'a, S> {
         self
     }
 
+    /// Precision rules used to snap price and size
+    ///
+    /// Defaults to the asset's metadata from the provider's asset registry.
+    pub fn precision(mut self, precision: AssetPrecision) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    /// How price and size are rounded (defaults to nearest)
+    pub fn rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
     // Convenience methods for common order types
     pub fn limit_buy(self, price: impl ToString, size: impl ToString) -> Self {
         self.buy().limit_px(price).size(size)
@@ -1057,64 +1700,93 @@ impl<'a, S: HyperliquidSigner> OrderBuilder<'a, S> {
     }
 
     pub fn trigger_buy(
-        self,
+        mut self,
         trigger_px: impl ToString,
         size: impl ToString,
         tpsl: &str,
     ) -> Self {
-        self.buy()
-            .size(size)
-            .order_type(OrderType::Trigger(Trigger {
-                trigger_px: trigger_px.to_string(),
-                is_market: true,
-                tpsl: tpsl.to_string(),
-            }))
+        self.order_type = Some(BuilderOrderType::MarketTrigger {
+            trigger_px: trigger_px.to_string(),
+            tpsl: tpsl.to_string(),
+        });
+        self.buy().size(size)
     }
 
     pub fn trigger_sell(
-        self,
+        mut self,
         trigger_px: impl ToString,
         size: impl ToString,
         tpsl: &str,
     ) -> Self {
-        self.sell()
-            .size(size)
-            .order_type(OrderType::Trigger(Trigger {
-                trigger_px: trigger_px.to_string(),
-                is_market: true,
-                tpsl: tpsl.to_string(),
-            }))
+        self.order_type = Some(BuilderOrderType::MarketTrigger {
+            trigger_px: trigger_px.to_string(),
+            tpsl: tpsl.to_string(),
+        });
+        self.sell().size(size)
     }
 
     pub fn build(self) -> Result<OrderRequest> {
-        let limit_px = self.limit_px.ok_or(HyperliquidError::InvalidRequest(
-            "limit_px must be specified".to_string(),
-        ))?;
+        let order_type = match self.order_type {
+            Some(BuilderOrderType::Given(order_type)) => order_type,
+            Some(BuilderOrderType::MarketTrigger { trigger_px, tpsl }) => {
+                OrderType::Trigger(Trigger {
+                    trigger_px: parse_decimal(&trigger_px, "trigger_px")?,
+                    is_market: true,
+                    tpsl,
+                })
+            }
+            None => OrderType::Limit(Limit {
+                tif: TIF_GTC.to_string(),
+            }),
+        };
+
+        // Market triggers use the trigger price as their limit unless one is set
+        let limit_px = match (self.limit_px, &order_type) {
+            (Some(limit_px), _) => parse_decimal(&limit_px, "limit_px")?,
+            (None, OrderType::Trigger(trigger)) if trigger.is_market => {
+                trigger.trigger_px
+            }
+            (None, _) => {
+                return Err(HyperliquidError::InvalidRequest(
+                    "limit_px must be specified".to_string(),
+                ))
+            }
+        };
         let sz = self.sz.ok_or(HyperliquidError::InvalidRequest(
             "sz must be specified".to_string(),
         ))?;
+        let sz = parse_decimal(&sz, "sz")?;
 
-        // Parse and format the prices to match API expectations
-        let limit_px_f64 = limit_px.parse::<f64>().map_err(|_| {
-            HyperliquidError::InvalidRequest("Invalid limit_px format".to_string())
-        })?;
-        let sz_f64 = sz.parse::<f64>().map_err(|_| {
-            HyperliquidError::InvalidRequest("Invalid sz format".to_string())
-        })?;
+        let asset = self.provider.resolve_asset(self.asset)?;
+        let precision = self.precision.or_else(|| {
+            self.provider
+                .asset_registry
+                .as_ref()
+                .and_then(|registry| registry.get_by_index(asset))
+                .map(|info| info.precision())
+        });
 
-        Ok(OrderRequest {
-            asset: self.asset,
+        let order = OrderRequest {
+            asset,
             is_buy: self.is_buy.ok_or(HyperliquidError::InvalidRequest(
                 "is_buy must be specified".to_string(),
             ))?,
-            limit_px: format_float_string(limit_px_f64),
-            sz: format_float_string(sz_f64),
+            limit_px,
+            sz,
             reduce_only: self.reduce_only,
-            order_type: self.order_type.unwrap_or(OrderType::Limit(Limit {
-                tif: TIF_GTC.to_string(),
-            })),
+            order_type,
             cloid: self.cloid.map(|id| format!("{:032x}", id.as_u128())),
-        })
+        };
+
+        match precision {
+            Some(precision) => Ok(order.normalize(precision, self.rounding)),
+            // Without asset metadata, only cap the values at 8 decimals
+            None => Ok(OrderRequest {
+                limit_px: order.limit_px.round_dp(MAX_SPOT_DECIMALS),
+                sz: order.sz.round_dp(MAX_SPOT_DECIMALS),
+                ..order
+            }),
+        }
     }
 
     pub async fn send(self) -> Result<ExchangeResponseStatus> {
@@ -1122,24 +1794,333 @@ impl<'a, S: HyperliquidSigner> OrderBuilder<'a, S> {
         let order = self.build()?;
         provider.place_order(&order).await
     }
+
+    /// Attach take-profit and stop-loss triggers to this order
+    pub fn bracket(self) -> BracketOrder<'a, S> {
+        BracketOrder {
+            provider: self.provider,
+            asset: self.asset.clone(),
+            is_buy: self.is_buy,
+            sz: self.sz.clone(),
+            precision: self.precision,
+            rounding: self.rounding,
+            take_profit: None,
+            stop_loss: None,
+            entry: Some(self),
+        }
+    }
+}
+
+/// Take-profit and stop-loss triggers sent in one action, optionally with an entry
+///
+/// With an entry the grouping is `normalTpsl` and the triggers activate once
+/// the entry fills. Without one the grouping is `positionTpsl` and the triggers
+/// close the open position. Triggers are reduce-only market orders on the
+/// opposite side.
+pub struct BracketOrder<'a, S: HyperliquidSigner> {
+    provider: &'a RawExchangeProvider<S>,
+    entry: Option<OrderBuilder<'a, S>>,
+    asset: AssetRef,
+    // Side of the entry or position being protected
+    is_buy: Option<bool>,
+    sz: Option<String>,
+    precision: Option<AssetPrecision>,
+    rounding: RoundingMode,
+    take_profit: Option<String>,
+    stop_loss: Option<String>,
+}
+
+impl<'a, S: HyperliquidSigner> BracketOrder<'a, S> {
+    pub fn take_profit(mut self, trigger_px: impl ToString) -> Self {
+        self.take_profit = Some(trigger_px.to_string());
+        self
+    }
+
+    pub fn stop_loss(mut self, trigger_px: impl ToString) -> Self {
+        self.stop_loss = Some(trigger_px.to_string());
+        self
+    }
+
+    pub fn grouping(&self) -> OrderGrouping {
+        if self.entry.is_some() {
+            OrderGrouping::NormalTpsl
+        } else {
+            OrderGrouping::PositionTpsl
+        }
+    }
+
+    /// Build the entry (if any) followed by the take-profit and stop-loss
+    pub fn build(self) -> Result<Vec<OrderRequest>> {
+        if self.take_profit.is_none() && self.stop_loss.is_none() {
+            return Err(HyperliquidError::InvalidRequest(
+                "A bracket needs a take-profit or a stop-loss".to_string(),
+            ));
+        }
+        let is_buy = self.is_buy.ok_or(HyperliquidError::InvalidRequest(
+            "is_buy must be specified".to_string(),
+        ))?;
+        let sz = self.sz.ok_or(HyperliquidError::InvalidRequest(
+            "sz must be specified".to_string(),
+        ))?;
+
+        let mut orders = Vec::with_capacity(3);
+        if let Some(entry) = self.entry {
+            orders.push(entry.build()?);
+        }
+
+        let triggers = [(self.take_profit, "tp"), (self.stop_loss, "sl")];
+        for (trigger_px, tpsl) in triggers {
+            let Some(trigger_px) = trigger_px else {
+                continue;
+            };
+            let mut child = OrderBuilder::new(self.provider, self.asset.clone())
+                .rounding(self.rounding)
+                .reduce_only(true);
+            if let Some(precision) = self.precision {
+                child = child.precision(precision);
+            }
+            let child = if is_buy {
+                child.trigger_sell(trigger_px, &sz, tpsl)
+            } else {
+                child.trigger_buy(trigger_px, &sz, tpsl)
+            };
+            orders.push(child.build()?);
+        }
+
+        Ok(orders)
+    }
+
+    pub async fn send(self) -> Result<BracketResponse> {
+        let provider = self.provider;
+        let grouping = self.grouping();
+        let legs = (
+            self.entry.is_some(),
+            self.take_profit.is_some(),
+            self.stop_loss.is_some(),
+        );
+        let orders = self.build()?;
+        let response = provider.bulk_orders_with_grouping(orders, grouping).await?;
+        Ok(BracketResponse::new(response, legs))
+    }
+}
+
+/// A bracket's response with each leg's status
+#[derive(Debug, Clone)]
+pub struct BracketResponse {
+    pub response: ExchangeResponseStatus,
+    pub entry: Option<ExchangeDataStatus>,
+    pub take_profit: Option<ExchangeDataStatus>,
+    pub stop_loss: Option<ExchangeDataStatus>,
+}
+
+impl BracketResponse {
+    // Statuses come back in the order the legs were sent
+    fn new(response: ExchangeResponseStatus, legs: (bool, bool, bool)) -> Self {
+        let statuses = match &response {
+            ExchangeResponseStatus::Ok(ExchangeResponse {
+                data: Some(data), ..
+            }) => data.statuses.clone(),
+            _ => Vec::new(),
+        };
+        let mut statuses = statuses.into_iter();
+        let (has_entry, has_take_profit, has_stop_loss) = legs;
+
+        Self {
+            entry: has_entry.then(|| statuses.next()).flatten(),
+            take_profit: has_take_profit.then(|| statuses.next()).flatten(),
+            stop_loss: has_stop_loss.then(|| statuses.next()).flatten(),
+            response,
+        }
+    }
 }
 
 impl<S: HyperliquidSigner> RawExchangeProvider<S> {
-    pub fn order(&self, asset: u32) -> OrderBuilder<'_, S> {
+    pub fn order(&self, asset: impl Into<AssetRef>) -> OrderBuilder<'_, S> {
         OrderBuilder::new(self, asset)
     }
+
+    /// Take-profit and stop-loss triggers for an open position
+    pub fn position_tpsl(
+        &self,
+        asset: impl Into<AssetRef>,
+        is_long: bool,
+        sz: impl ToString,
+    ) -> BracketOrder<'_, S> {
+        BracketOrder {
+            provider: self,
+            entry: None,
+            asset: asset.into(),
+            is_buy: Some(is_long),
+            sz: Some(sz.to_string()),
+            precision: None,
+            rounding: RoundingMode::default(),
+            take_profit: None,
+            stop_loss: None,
+        }
+    }
 }
 
 // ==================== Managed Exchange Provider ====================
 
-use tokio::sync::Mutex as TokioMutex;
+use alloy::signers::local::PrivateKeySigner;
+use tokio::sync::{oneshot, Mutex as TokioMutex};
 
 use crate::providers::{
+    address_budget::{AddressBudget, AddressBudgetConfig, LowBudgetPolicy},
     agent::{AgentConfig, AgentManager, AgentWallet},
-    batcher::{BatchConfig, OrderBatcher, OrderHandle},
-    nonce::NonceManager,
+    agent_store::AgentStore,
+    batcher::{BatchConfig, BatchExecutor, OrderBatcher, OrderHandle},
 };
 
+#[async_trait::async_trait]
+impl<S: HyperliquidSigner> BatchExecutor for RawExchangeProvider<S> {
+    async fn orders(&self, orders: Vec<OrderRequest>) -> Result<ExchangeResponseStatus> {
+        self.bulk_orders(orders).await
+    }
+
+    async fn cancels(
+        &self,
+        cancels: Vec<CancelRequest>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_cancel(cancels).await
+    }
+
+    async fn cancels_by_cloid(
+        &self,
+        cancels: Vec<CancelRequestCloid>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_cancel_by_cloid(cancels).await
+    }
+
+    async fn modifies(
+        &self,
+        modifies: Vec<ModifyRequest>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_modify(modifies).await
+    }
+}
+
+/// Routes managed actions to the provider that should sign them
+///
+/// With agents enabled, L1 actions are signed by the active agent wallet using
+/// its own nonce stream. The master key only signs `approveAgent`.
+struct ManagedSender<S: HyperliquidSigner> {
+    /// Provider signed by the master key
+    master: Arc<RawExchangeProvider<S>>,
+    agent_manager: Option<Arc<AgentManager<S>>>,
+    agent_name: String,
+    /// Provider signed by the current agent, replaced on rotation
+    agent: parking_lot::Mutex<Option<(Address, AgentProvider)>>,
+}
+
+type AgentProvider = Arc<RawExchangeProvider<PrivateKeySigner>>;
+
+enum Route<S: HyperliquidSigner> {
+    Master(Arc<RawExchangeProvider<S>>),
+    Agent(AgentProvider),
+}
+
+// Call the same method on whichever provider the route points at
+macro_rules! routed {
+    ($route:expr, |$raw:ident| $call:expr) => {
+        match $route {
+            Route::Master($raw) => $call,
+            Route::Agent($raw) => $call,
+        }
+    };
+}
+
+impl<S: HyperliquidSigner + Clone> ManagedSender<S> {
+    async fn route(&self) -> Result<Route<S>> {
+        let Some(agent_mgr) = &self.agent_manager else {
+            return Ok(Route::Master(self.master.clone()));
+        };
+        let agent = agent_mgr.get_or_rotate_agent(&self.agent_name).await?;
+
+        let mut current = self.agent.lock();
+        if let Some((address, raw)) = &*current {
+            if *address == agent.address {
+                return Ok(Route::Agent(raw.clone()));
+            }
+        }
+
+        let raw = Arc::new(
+            self.master
+                .with_signer(agent.signer.clone())
+                .with_nonce_manager(agent.nonce_manager.clone()),
+        );
+        *current = Some((agent.address, raw.clone()));
+        Ok(Route::Agent(raw))
+    }
+}
+
+impl<S: HyperliquidSigner + Clone + 'static> ManagedSender<S> {
+    /// Push the scheduled cancel forward every interval until `stop` fires
+    async fn run_heartbeat(
+        self: Arc<Self>,
+        heartbeat: CancelHeartbeat,
+        mut stop: oneshot::Receiver<()>,
+    ) {
+        let mut interval = tokio::time::interval(heartbeat.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let time = RawExchangeProvider::<S>::current_nonce()
+                        + heartbeat.cancel_after.as_millis() as u64;
+                    let result = match self.route().await {
+                        Ok(route) => {
+                            routed!(route, |raw| raw.schedule_cancel(Some(time)).await)
+                        }
+                        Err(e) => Err(e),
+                    };
+                    match result {
+                        Ok(ExchangeResponseStatus::Err(e)) => {
+                            tracing::warn!("Schedule cancel rejected: {e}");
+                        }
+                        Err(e) => tracing::warn!("Schedule cancel failed: {e}"),
+                        Ok(_) => {}
+                    }
+                }
+                _ = &mut stop => break,
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: HyperliquidSigner + Clone> BatchExecutor for ManagedSender<S> {
+    async fn orders(&self, orders: Vec<OrderRequest>) -> Result<ExchangeResponseStatus> {
+        let route = self.route().await?;
+        routed!(route, |raw| raw.bulk_orders(orders).await)
+    }
+
+    async fn cancels(
+        &self,
+        cancels: Vec<CancelRequest>,
+    ) -> Result<ExchangeResponseStatus> {
+        let route = self.route().await?;
+        routed!(route, |raw| raw.bulk_cancel(cancels).await)
+    }
+
+    async fn cancels_by_cloid(
+        &self,
+        cancels: Vec<CancelRequestCloid>,
+    ) -> Result<ExchangeResponseStatus> {
+        let route = self.route().await?;
+        routed!(route, |raw| raw.bulk_cancel_by_cloid(cancels).await)
+    }
+
+    async fn modifies(
+        &self,
+        modifies: Vec<ModifyRequest>,
+    ) -> Result<ExchangeResponseStatus> {
+        let route = self.route().await?;
+        routed!(route, |raw| raw.bulk_modify(modifies).await)
+    }
+}
+
 /// Configuration for managed exchange provider
 #[derive(Clone, Debug)]
 pub struct ManagedExchangeConfig {
@@ -1159,6 +2140,21 @@ pub struct ManagedExchangeConfig {
     /// Safety features
     pub prevent_agent_address_queries: bool,
     pub warn_on_high_nonce_velocity: bool,
+    /// Dead man's switch, disabled by default
+    pub cancel_heartbeat: Option<CancelHeartbeat>,
+    /// Address-based request budget tracking, disabled by default
+    pub address_budget: Option<AddressBudgetConfig>,
+}
+
+/// Keeps a `scheduleCancel` pushed into the future while the process is alive
+///
+/// Every `interval` the cancel time is moved to `cancel_after` from now. If the
+/// heartbeat stops, all open orders are cancelled once that time passes.
+#[derive(Clone, Debug)]
+pub struct CancelHeartbeat {
+    pub interval: std::time::Duration,
+    /// Must be at least 5 seconds and longer than `interval`
+    pub cancel_after: std::time::Duration,
 }
 
 impl Default for ManagedExchangeConfig {
@@ -1171,27 +2167,180 @@ impl Default for ManagedExchangeConfig {
             isolate_subaccount_nonces: true,
             prevent_agent_address_queries: true,
             warn_on_high_nonce_velocity: true,
+            cancel_heartbeat: None,
+            address_budget: None,
         }
     }
 }
 
-/// Managed exchange provider with safety features and optimizations
-pub struct ManagedExchangeProvider<S: HyperliquidSigner> {
-    /// Inner raw provider
-    inner: Arc<RawExchangeProvider<S>>,
+/// Connection id an L1 action is signed over: the msgpack of the tagged action,
+/// followed by the nonce and vault flag
+pub(crate) fn hash_l1_action<T: Serialize>(
+    action_type: &str,
+    action: &T,
+    timestamp: u64,
+    vault_address: Option<Address>,
+) -> Result<B256> {
+    // Create an enum wrapper for proper serialization
+    // This matches how the original Hyperliquid SDK serializes actions
+    // The enum variant becomes the "type" field in the serialized output
+    #[derive(serde::Serialize)]
+    #[serde(tag = "type")]
+    #[serde(rename_all = "camelCase")]
+    enum ActionWrapper<'a, T> {
+        Order(&'a T),
+        Cancel(&'a T),
+        CancelByCloid(&'a T),
+        BatchModify(&'a T),
+        UpdateLeverage(&'a T),
+        UpdateIsolatedMargin(&'a T),
+        UsdSend(&'a T),
+        SpotSend(&'a T),
+        SpotUser(&'a T),
+        VaultTransfer(&'a T),
+        CreateVault(&'a T),
+        VaultModify(&'a T),
+        VaultDistribute(&'a T),
+        SetReferrer(&'a T),
+        CreateSubAccount(&'a T),
+        SubAccountTransfer(&'a T),
+        SubAccountSpotTransfer(&'a T),
+        ScheduleCancel(&'a T),
+        ReserveRequestWeight(&'a T),
+        TwapOrder(&'a T),
+        TwapCancel(&'a T),
+        ApproveAgent(&'a T),
+        ApproveBuilderFee(&'a T),
+        Withdraw3(&'a T),
+    }
+
+    // Wrap the action based on type
+    let wrapped = match action_type {
+        "order" => ActionWrapper::Order(action),
+        "cancel" => ActionWrapper::Cancel(action),
+        "cancelByCloid" => ActionWrapper::CancelByCloid(action),
+        "batchModify" => ActionWrapper::BatchModify(action),
+        "updateLeverage" => ActionWrapper::UpdateLeverage(action),
+        "updateIsolatedMargin" => ActionWrapper::UpdateIsolatedMargin(action),
+        "usdSend" => ActionWrapper::UsdSend(action),
+        "spotSend" => ActionWrapper::SpotSend(action),
+        "spotUser" => ActionWrapper::SpotUser(action),
+        "vaultTransfer" => ActionWrapper::VaultTransfer(action),
+        "createVault" => ActionWrapper::CreateVault(action),
+        "vaultModify" => ActionWrapper::VaultModify(action),
+        "vaultDistribute" => ActionWrapper::VaultDistribute(action),
+        "setReferrer" => ActionWrapper::SetReferrer(action),
+        "createSubAccount" => ActionWrapper::CreateSubAccount(action),
+        "subAccountTransfer" => ActionWrapper::SubAccountTransfer(action),
+        "subAccountSpotTransfer" => ActionWrapper::SubAccountSpotTransfer(action),
+        "scheduleCancel" => ActionWrapper::ScheduleCancel(action),
+        "reserveRequestWeight" => ActionWrapper::ReserveRequestWeight(action),
+        "twapOrder" => ActionWrapper::TwapOrder(action),
+        "twapCancel" => ActionWrapper::TwapCancel(action),
+        "approveAgent" => ActionWrapper::ApproveAgent(action),
+        "approveBuilderFee" => ActionWrapper::ApproveBuilderFee(action),
+        "withdraw3" => ActionWrapper::Withdraw3(action),
+        _ => {
+            return Err(HyperliquidError::InvalidRequest(format!(
+                "Unknown action type: {action_type}"
+            )))
+        }
+    };
+
+    // NOTE: Hyperliquid uses MessagePack (rmp_serde) for action serialization
+    // This is different from typical EVM systems that use RLP
+    let mut bytes = rmp_serde::to_vec_named(&wrapped).map_err(|e| {
+        HyperliquidError::InvalidRequest(format!("Failed to serialize action: {e}"))
+    })?;
+    bytes.extend(timestamp.to_be_bytes());
+    if let Some(vault) = vault_address {
+        bytes.push(1);
+        bytes.extend(vault.as_slice());
+    } else {
+        bytes.push(0);
+    }
+    Ok(keccak256(bytes))
+}
 
-    /// Agent manager for lifecycle
-    agent_manager: Option<Arc<AgentManager<S>>>,
+/// Rate limit weight of an L1 action, by its `type` and batch size
+fn exchange_weight(action: &Value) -> u32 {
+    let items = ["orders", "cancels", "modifies"]
+        .iter()
+        .find_map(|key| action.get(*key).and_then(Value::as_array))
+        .map_or(1, Vec::len);
+    let single = items <= 1;
+    match action.get("type").and_then(Value::as_str) {
+        Some("order") if single => WEIGHT_PLACE_ORDER,
+        Some("order") => WEIGHT_BULK_ORDER,
+        Some("cancel" | "cancelByCloid") if single => WEIGHT_CANCEL_ORDER,
+        Some("cancel" | "cancelByCloid") => WEIGHT_BULK_CANCEL,
+        Some("batchModify") if single => WEIGHT_MODIFY_ORDER,
+        Some("batchModify") => WEIGHT_BULK_ORDER,
+        Some("twapOrder") => WEIGHT_PLACE_ORDER,
+        Some("twapCancel") => WEIGHT_CANCEL_ORDER,
+        _ => WEIGHT_EXCHANGE_DEFAULT,
+    }
+}
 
-    /// Nonce tracking
-    nonce_manager: Arc<NonceManager>,
+fn check_slippage(slippage: Decimal) -> Result<()> {
+    if slippage < Decimal::ZERO || slippage >= Decimal::ONE {
+        return Err(HyperliquidError::InvalidRequest(format!(
+            "Slippage must be at least 0 and below 1, got {slippage}"
+        )));
+    }
+    Ok(())
+}
+
+// IOC order priced `slippage` away from `mid`
+fn market_order_at(
+    info: &AssetInfo,
+    is_buy: bool,
+    sz: Decimal,
+    slippage: Decimal,
+    mid: Decimal,
+) -> OrderRequest {
+    let px = if is_buy {
+        mid * (Decimal::ONE + slippage)
+    } else {
+        mid * (Decimal::ONE - slippage)
+    };
+
+    OrderRequest::limit_normalized(
+        info.index(),
+        is_buy,
+        px,
+        sz,
+        TIF_IOC,
+        info.precision(),
+        RoundingMode::Nearest,
+    )
+}
+
+// Refresh the address budget from `userRateLimit`
+async fn sync_budget(info: &InfoProvider, budget: &AddressBudget) {
+    match info.user_rate_limit(budget.user()).await {
+        Ok(limit) => budget.sync(&limit),
+        Err(e) => {
+            tracing::warn!("Address budget sync failed: {e}");
+            budget.sync_failed();
+        }
+    }
+}
+
+/// Managed exchange provider with safety features and optimizations
+pub struct ManagedExchangeProvider<S: HyperliquidSigner> {
+    /// Signs through the active agent, or the master key without agents
+    sender: Arc<ManagedSender<S>>,
 
     /// Order batching
     batcher: Option<Arc<OrderBatcher>>,
     batcher_handle: Option<Arc<TokioMutex<Option<tokio::task::JoinHandle<()>>>>>,
 
-    /// Configuration
-    config: ManagedExchangeConfig,
+    /// Dead man's switch task and its stop signal
+    heartbeat: TokioMutex<Option<(oneshot::Sender<()>, tokio::task::JoinHandle<()>)>>,
+
+    /// Address-based request budget
+    budget: Option<Arc<AddressBudget>>,
 }
 
 impl<S: HyperliquidSigner + Clone + 'static> ManagedExchangeProvider<S> {
@@ -1218,70 +2367,184 @@ impl<S: HyperliquidSigner + Clone + 'static> ManagedExchangeProvider<S> {
 
     /// Place an order with all managed features
     pub async fn place_order(&self, order: &OrderRequest) -> Result<OrderHandle> {
-        // Get nonce based on configuration
-        let nonce = if self.config.auto_rotate_agents {
-            if let Some(agent_mgr) = &self.agent_manager {
-                let agent = agent_mgr.get_or_rotate_agent("default").await?;
-                // Use agent's nonce
-                agent.next_nonce()
-            } else {
-                // Fallback to regular nonce
-                self.nonce_manager.next_nonce(None)
+        self.spend_budget(1).await?;
+
+        // Batch or direct execution
+        match &self.batcher {
+            Some(batcher) => Ok(batcher.add_order(order.clone()).await),
+            None => {
+                let route = self.sender.route().await?;
+                let result = routed!(route, |raw| raw.place_order(order).await)?;
+                Ok(OrderHandle::Immediate(Ok(result)))
             }
-        } else {
-            // Not using agents, use regular nonce
-            if self.config.isolate_subaccount_nonces {
-                // For subaccounts, we'd need to extract the address from somewhere
-                // For now, just use global nonce
-                self.nonce_manager.next_nonce(None)
-            } else {
-                self.nonce_manager.next_nonce(None)
+        }
+    }
+
+    /// Cancel an order, batched with other cancels when batching is enabled
+    pub async fn cancel_order(
+        &self,
+        asset: impl Into<AssetRef>,
+        oid: u64,
+    ) -> Result<OrderHandle> {
+        self.spend_budget(1).await?;
+
+        match &self.batcher {
+            Some(batcher) => {
+                let asset = self.sender.master.resolve_asset(asset)?;
+                let cancel = CancelRequest { asset, oid };
+                Ok(batcher.add_cancel(cancel).await)
             }
-        };
+            None => {
+                let route = self.sender.route().await?;
+                let result = routed!(route, |raw| raw.cancel_order(asset, oid).await)?;
+                Ok(OrderHandle::Immediate(Ok(result)))
+            }
+        }
+    }
 
-        // Check nonce validity
-        if !NonceManager::is_valid_nonce(nonce) {
-            return Err(HyperliquidError::InvalidRequest(
-                "Generated nonce is outside valid time bounds".to_string(),
-            ));
+    /// Cancel an order by client order ID, batched when batching is enabled
+    pub async fn cancel_order_by_cloid(
+        &self,
+        asset: impl Into<AssetRef>,
+        cloid: Uuid,
+    ) -> Result<OrderHandle> {
+        self.spend_budget(1).await?;
+
+        match &self.batcher {
+            Some(batcher) => {
+                let asset = self.sender.master.resolve_asset(asset)?;
+                let cancel = CancelRequestCloid::new(asset, cloid);
+                Ok(batcher.add_cancel_by_cloid(cancel).await)
+            }
+            None => {
+                let route = self.sender.route().await?;
+                let result =
+                    routed!(route, |raw| raw.cancel_order_by_cloid(asset, cloid).await)?;
+                Ok(OrderHandle::Immediate(Ok(result)))
+            }
         }
+    }
 
-        // For now, we always use the main provider
-        // In a full implementation, we'd need to handle agent signing differently
-        // This is a limitation of the current design where we can't easily swap signers
+    /// Modify an order, batched with other modifies when batching is enabled
+    pub async fn modify_order(
+        &self,
+        oid: u64,
+        new_order: OrderRequest,
+    ) -> Result<OrderHandle> {
+        self.spend_budget(1).await?;
 
-        // Batch or direct execution
-        if self.config.batch_orders {
-            if let Some(batcher) = &self.batcher {
-                Ok(batcher.add_order(order.clone(), nonce).await)
-            } else {
-                // Fallback to direct
-                let result = self.inner.place_order(order).await?;
+        match &self.batcher {
+            Some(batcher) => {
+                let modify = ModifyRequest::new(oid, new_order);
+                Ok(batcher.add_modify(modify).await)
+            }
+            None => {
+                let route = self.sender.route().await?;
+                let result =
+                    routed!(route, |raw| raw.modify_order(oid, new_order).await)?;
                 Ok(OrderHandle::Immediate(Ok(result)))
             }
-        } else {
-            // Direct execution
-            let result = self.inner.place_order(order).await?;
-            Ok(OrderHandle::Immediate(Ok(result)))
         }
     }
 
+    /// Modify an order by client order ID, batched when batching is enabled
+    pub async fn modify_order_by_cloid(
+        &self,
+        cloid: Uuid,
+        new_order: OrderRequest,
+    ) -> Result<OrderHandle> {
+        self.spend_budget(1).await?;
+
+        match &self.batcher {
+            Some(batcher) => {
+                let new_order = match new_order.cloid {
+                    Some(_) => new_order,
+                    None => new_order.with_cloid(Some(cloid)),
+                };
+                let modify = ModifyRequest::new(cloid, new_order);
+                Ok(batcher.add_modify(modify).await)
+            }
+            None => {
+                let route = self.sender.route().await?;
+                let result = routed!(route, |raw| raw
+                    .modify_order_by_cloid(cloid, new_order)
+                    .await)?;
+                Ok(OrderHandle::Immediate(Ok(result)))
+            }
+        }
+    }
+
+    /// Get the address-based request budget, if tracking is enabled
+    pub fn address_budget(&self) -> Option<&AddressBudget> {
+        self.budget.as_deref()
+    }
+
+    // Count requests against the address budget, reserving more weight or
+    // blocking when it runs low. Stale counters are resynced in the background.
+    async fn spend_budget(&self, requests: u64) -> Result<()> {
+        let Some(budget) = &self.budget else {
+            return Ok(());
+        };
+
+        if budget.try_begin_sync() {
+            let info = self.sender.master.info.clone();
+            let budget = budget.clone();
+            tokio::spawn(async move { sync_budget(&info, &budget).await });
+        }
+
+        if budget.is_low() {
+            if let Some(weight) = budget.config().reserve_weight {
+                let route = self.sender.route().await?;
+                match routed!(route, |raw| raw.reserve_request_weight(weight).await) {
+                    Ok(ExchangeResponseStatus::Ok(_)) => budget.add_capacity(weight),
+                    Ok(ExchangeResponseStatus::Err(e)) => {
+                        tracing::warn!("Reserve request weight rejected: {e}")
+                    }
+                    Err(e) => tracing::warn!("Reserve request weight failed: {e}"),
+                }
+            }
+        }
+
+        if budget.is_low() {
+            let remaining = budget.remaining();
+            match budget.config().policy {
+                LowBudgetPolicy::Warn => tracing::warn!(
+                    "Address budget low: {remaining} requests left for {}",
+                    budget.user()
+                ),
+                LowBudgetPolicy::Block => {
+                    return Err(HyperliquidError::RateLimited {
+                        available: remaining.min(u32::MAX as u64) as u32,
+                        required: requests as u32,
+                    })
+                }
+            }
+        }
+
+        budget.record(requests);
+        Ok(())
+    }
+
     /// Place order immediately, bypassing batch
     pub async fn place_order_immediate(
         &self,
         order: &OrderRequest,
     ) -> Result<ExchangeResponseStatus> {
-        self.inner.place_order(order).await
+        self.spend_budget(1).await?;
+        let route = self.sender.route().await?;
+        routed!(route, |raw| raw.place_order(order).await)
     }
 
     /// Access the raw provider for advanced usage
+    ///
+    /// The raw provider signs with the master key, not the active agent.
     pub fn raw(&self) -> &RawExchangeProvider<S> {
-        &self.inner
+        &self.sender.master
     }
 
     /// Get current agent status
     pub async fn get_agent_status(&self) -> Option<Vec<(String, AgentWallet)>> {
-        if let Some(agent_mgr) = &self.agent_manager {
+        if let Some(agent_mgr) = &self.sender.agent_manager {
             Some(agent_mgr.get_active_agents().await)
         } else {
             None
@@ -1289,11 +2552,22 @@ impl<S: HyperliquidSigner + Clone + 'static> ManagedExchangeProvider<S> {
     }
 
     /// Shutdown the managed provider cleanly
+    ///
+    /// Requests still queued in the batcher are sent before this returns. The
+    /// cancel heartbeat stops, leaving its last scheduled cancel in place.
     pub async fn shutdown(self: Arc<Self>) {
-        // Stop batcher if running
+        if let Some((stop, handle)) = self.heartbeat.lock().await.take() {
+            let _ = stop.send(());
+            let _ = handle.await;
+        }
+
+        // Stop batcher if running, letting it flush its queues
+        if let Some(batcher) = &self.batcher {
+            batcher.shutdown().await;
+        }
         if let Some(handle_mutex) = &self.batcher_handle {
             if let Some(handle) = handle_mutex.lock().await.take() {
-                handle.abort();
+                let _ = handle.await;
             }
         }
     }
@@ -1307,6 +2581,11 @@ pub struct ManagedExchangeProviderBuilder<S: HyperliquidSigner> {
     vault_address: Option<Address>,
     initial_agent: Option<String>,
     builder_address: Option<Address>,
+    asset_registry: Option<Arc<AssetRegistry>>,
+    agent_store: Option<Arc<dyn AgentStore>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    retry_policy: Option<RetryPolicy>,
+    transport: Option<Arc<dyn Transport>>,
 }
 
 impl<S: HyperliquidSigner + Clone + 'static> ManagedExchangeProviderBuilder<S> {
@@ -1318,6 +2597,11 @@ impl<S: HyperliquidSigner + Clone + 'static> ManagedExchangeProviderBuilder<S> {
             vault_address: None,
             initial_agent: None,
             builder_address: None,
+            asset_registry: None,
+            agent_store: None,
+            rate_limiter: None,
+            retry_policy: None,
+            transport: None,
         }
     }
 
@@ -1334,6 +2618,13 @@ impl<S: HyperliquidSigner + Clone + 'static> ManagedExchangeProviderBuilder<S> {
         self
     }
 
+    /// Enable automatic batching with a full batch configuration
+    pub fn with_batch_config(mut self, config: BatchConfig) -> Self {
+        self.config.batch_orders = true;
+        self.config.batch_config = config;
+        self
+    }
+
     /// Configure agent rotation
     pub fn with_agent_rotation(mut self, ttl: std::time::Duration) -> Self {
         self.config.auto_rotate_agents = true;
@@ -1348,6 +2639,12 @@ impl<S: HyperliquidSigner + Clone + 'static> ManagedExchangeProviderBuilder<S> {
         self
     }
 
+    /// Persist agents in `store` so they are reused after a restart
+    pub fn with_agent_store(mut self, store: Arc<dyn AgentStore>) -> Self {
+        self.agent_store = Some(store);
+        self
+    }
+
     /// Set vault address
     pub fn with_vault(mut self, vault: Address) -> Self {
         self.vault_address = Some(vault);
@@ -1360,6 +2657,50 @@ impl<S: HyperliquidSigner + Clone + 'static> ManagedExchangeProviderBuilder<S> {
         self
     }
 
+    /// Resolve symbols through an asset registry
+    pub fn with_asset_registry(mut self, registry: Arc<AssetRegistry>) -> Self {
+        self.asset_registry = Some(registry);
+        self
+    }
+
+    /// Draw request weight from a shared rate limiter
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Retry failed requests according to `policy`
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Send requests, including agent approvals, through `transport`
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Track the address-based request budget of the trading account
+    pub fn with_address_budget(mut self, config: AddressBudgetConfig) -> Self {
+        self.config.address_budget = Some(config);
+        self
+    }
+
+    /// Keep a scheduled cancel of all orders `cancel_after` in the future,
+    /// refreshed every `interval`
+    pub fn with_cancel_heartbeat(
+        mut self,
+        interval: std::time::Duration,
+        cancel_after: std::time::Duration,
+    ) -> Self {
+        self.config.cancel_heartbeat = Some(CancelHeartbeat {
+            interval,
+            cancel_after,
+        });
+        self
+    }
+
     /// Disable agent rotation
     pub fn without_agent_rotation(mut self) -> Self {
         self.config.auto_rotate_agents = false;
@@ -1368,37 +2709,73 @@ impl<S: HyperliquidSigner + Clone + 'static> ManagedExchangeProviderBuilder<S> {
 
     /// Build the provider
     pub async fn build(self) -> Result<Arc<ManagedExchangeProvider<S>>> {
+        // Requests count against the account being traded
+        let budget = self.config.address_budget.clone().map(|config| {
+            let user = self.vault_address.unwrap_or_else(|| self.signer.address());
+            Arc::new(AddressBudget::new(user, config))
+        });
+
         // Create raw provider
-        let raw = match self.network {
-            Network::Mainnet => {
-                if let Some(vault) = self.vault_address {
-                    RawExchangeProvider::mainnet_vault(self.signer.clone(), vault)
-                } else if let Some(builder) = self.builder_address {
-                    RawExchangeProvider::mainnet_builder(self.signer.clone(), builder)
-                } else {
-                    RawExchangeProvider::mainnet(self.signer.clone())
-                }
-            }
-            Network::Testnet => {
-                if let Some(vault) = self.vault_address {
-                    RawExchangeProvider::testnet_vault(self.signer.clone(), vault)
-                } else if let Some(builder) = self.builder_address {
-                    RawExchangeProvider::testnet_builder(self.signer.clone(), builder)
-                } else {
-                    RawExchangeProvider::testnet(self.signer.clone())
-                }
-            }
+        let builder = if self.vault_address.is_none() {
+            self.builder_address
+        } else {
+            None
         };
+        let mut raw = RawExchangeProvider::for_network_with_options(
+            self.signer.clone(),
+            self.network.clone(),
+            self.vault_address,
+            None,
+            builder,
+        );
+        if let Some(registry) = self.asset_registry {
+            raw = raw.with_asset_registry(registry);
+        }
+        if let Some(rate_limiter) = self.rate_limiter {
+            raw = raw.with_rate_limiter(rate_limiter);
+        }
+        if let Some(policy) = self.retry_policy {
+            raw = raw.with_retry_policy(policy);
+        }
+        if let Some(transport) = &self.transport {
+            raw = raw.with_transport(transport.clone());
+        }
 
-        let inner = Arc::new(raw);
+        // Know the budget before the first request; later syncs run in the background
+        if let Some(budget) = &budget {
+            if budget.try_begin_sync() {
+                sync_budget(&raw.info, budget).await;
+            }
+        }
 
         // Create agent manager if needed
         let agent_manager = if self.config.auto_rotate_agents {
-            Some(Arc::new(AgentManager::new(
-                self.signer,
-                self.config.agent_config.clone(),
-                self.network,
-            )))
+            let agent_config = self.config.agent_config.clone();
+            let mut manager = match self.agent_store {
+                Some(store) => {
+                    AgentManager::with_store(
+                        self.signer,
+                        agent_config,
+                        self.network.clone(),
+                        store,
+                    )
+                    .await?
+                }
+                None => {
+                    AgentManager::new(self.signer, agent_config, self.network.clone())
+                }
+            };
+            if let Some(transport) = self.transport {
+                manager = manager.with_transport(transport);
+            }
+            if let Some(budget) = &budget {
+                manager = manager.with_address_budget(budget.clone());
+            }
+            Some(Arc::new(
+                manager
+                    .with_rate_limiter(raw.rate_limiter.clone())
+                    .with_retry_policy(raw.retry_policy.clone()),
+            ))
         } else {
             None
         };
@@ -1407,80 +2784,21 @@ impl<S: HyperliquidSigner + Clone + 'static> ManagedExchangeProviderBuilder<S> {
         let nonce_manager =
             Arc::new(NonceManager::new(self.config.isolate_subaccount_nonces));
 
+        let approve_agent = self.initial_agent.is_some() && agent_manager.is_some();
+        let sender = Arc::new(ManagedSender {
+            master: Arc::new(raw.with_nonce_manager(nonce_manager.clone())),
+            agent_manager,
+            agent_name: self.initial_agent.unwrap_or_else(|| "default".to_string()),
+            agent: parking_lot::Mutex::new(None),
+        });
+
         // Create batcher if needed
         let (batcher, batcher_handle) = if self.config.batch_orders {
             let (batcher, handle) = OrderBatcher::new(self.config.batch_config.clone());
             let batcher = Arc::new(batcher);
 
             // Spawn batch processing task
-            let inner_clone = inner.clone();
-            let inner_clone2 = inner.clone();
-            let handle_future = tokio::spawn(async move {
-                handle
-                    .run(
-                        move |orders| {
-                            let inner = inner_clone.clone();
-                            Box::pin(async move {
-                                // Execute batch
-                                let order_requests: Vec<OrderRequest> =
-                                    orders.iter().map(|o| o.order.clone()).collect();
-
-                                match inner.bulk_orders(order_requests).await {
-                                    Ok(status) => {
-                                        // Return same status for all orders in batch
-                                        orders
-                                            .iter()
-                                            .map(|_| Ok(status.clone()))
-                                            .collect()
-                                    }
-                                    Err(e) => {
-                                        // Return same error for all orders in batch
-                                        let err_str = e.to_string();
-                                        orders
-                                            .iter()
-                                            .map(|_| {
-                                                Err(HyperliquidError::InvalidResponse(
-                                                    err_str.clone(),
-                                                ))
-                                            })
-                                            .collect()
-                                    }
-                                }
-                            })
-                        },
-                        move |cancels| {
-                            let inner = inner_clone2.clone();
-                            Box::pin(async move {
-                                // Execute cancel batch
-                                let cancel_requests: Vec<CancelRequest> =
-                                    cancels.iter().map(|c| c.cancel.clone()).collect();
-
-                                match inner.bulk_cancel(cancel_requests).await {
-                                    Ok(status) => {
-                                        // Return same status for all cancels in batch
-                                        cancels
-                                            .iter()
-                                            .map(|_| Ok(status.clone()))
-                                            .collect()
-                                    }
-                                    Err(e) => {
-                                        // Return same error for all cancels in batch
-                                        let err_str = e.to_string();
-                                        cancels
-                                            .iter()
-                                            .map(|_| {
-                                                Err(HyperliquidError::InvalidResponse(
-                                                    err_str.clone(),
-                                                ))
-                                            })
-                                            .collect()
-                                    }
-                                }
-                            })
-                        },
-                    )
-                    .await;
-            });
+            let handle_future = tokio::spawn(handle.run(sender.clone()));
 
             (
                 Some(batcher),
@@ -1490,20 +2808,35 @@ impl<S: HyperliquidSigner + Clone + 'static> ManagedExchangeProviderBuilder<S> {
             (None, None)
         };
 
+        // Start the dead man's switch
+        let heartbeat = match self.config.cancel_heartbeat.clone() {
+            Some(heartbeat) => {
+                if heartbeat.cancel_after < std::time::Duration::from_secs(5)
+                    || heartbeat.cancel_after <= heartbeat.interval
+                {
+                    return Err(HyperliquidError::InvalidRequest(
+                        "Cancel heartbeat must schedule at least 5s ahead and longer than its interval"
+                            .to_string(),
+                    ));
+                }
+                let (stop_tx, stop_rx) = oneshot::channel();
+                let task = tokio::spawn(sender.clone().run_heartbeat(heartbeat, stop_rx));
+                Some((stop_tx, task))
+            }
+            None => None,
+        };
+
         let provider = Arc::new(ManagedExchangeProvider {
-            inner,
-            agent_manager,
-            nonce_manager,
+            sender,
             batcher,
             batcher_handle,
-            config: self.config,
+            heartbeat: TokioMutex::new(heartbeat),
+            budget,
         });
 
         // Initialize agent if requested
-        if let Some(agent_name) = self.initial_agent {
-            if let Some(agent_mgr) = &provider.agent_manager {
-                agent_mgr.get_or_rotate_agent(&agent_name).await?;
-            }
+        if approve_agent {
+            provider.sender.route().await?;
         }
 
         Ok(provider)
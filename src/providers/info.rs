@@ -1,67 +1,166 @@
 use std::collections::HashMap;
-use std::sync::Mutex;
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use alloy::primitives::Address;
-use http::{Method, Request};
-use http_body_util::{BodyExt, Full};
 use hyper::body::Bytes;
-use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
-use hyper_util::client::legacy::{connect::HttpConnector, Client};
-use hyper_util::rt::TokioExecutor;
+use rust_decimal::Decimal;
 use serde_json::json;
 
-use crate::constants::Network;
+use crate::constants::*;
 use crate::errors::HyperliquidError;
+use crate::providers::retry::RetryPolicy;
+use crate::providers::transport::{HyperTransport, Transport};
 use crate::types::info_types::*;
 use crate::types::Symbol;
 
-// Rate limiter implementation
+/// Token bucket that keeps request weight under Hyperliquid's per-IP budget
+///
+/// Providers each create their own limiter by default. Share one
+/// `Arc<RateLimiter>` through their `with_rate_limiter` methods to keep a whole
+/// process under a single budget.
+#[derive(Debug)]
 pub struct RateLimiter {
-    tokens: Mutex<f64>,
+    bucket: Mutex<Bucket>,
     max_tokens: f64,
+    // Tokens added per second
     refill_rate: f64,
-    last_refill: Mutex<Instant>,
+    // Held while waiting, so callers are served in arrival order
+    queue: tokio::sync::Mutex<()>,
+    queue_timeout: Duration,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
 }
 
 impl RateLimiter {
+    /// Create a full limiter holding up to `max_tokens` weight and refilling
+    /// `refill_rate` weight per minute
     pub fn new(max_tokens: u32, refill_rate: u32) -> Self {
         Self {
-            tokens: Mutex::new(max_tokens as f64),
+            bucket: Mutex::new(Bucket {
+                tokens: max_tokens as f64,
+                last_refill: Instant::now(),
+            }),
             max_tokens: max_tokens as f64,
-            refill_rate: refill_rate as f64,
-            last_refill: Mutex::new(Instant::now()),
+            refill_rate: refill_rate as f64 / 60.0,
+            queue: tokio::sync::Mutex::new(()),
+            queue_timeout: Duration::from_millis(RATE_LIMIT_QUEUE_TIMEOUT_MS),
         }
     }
 
+    /// Give up on [`acquire`](Self::acquire) once it has waited `timeout`
+    pub fn with_queue_timeout(mut self, timeout: Duration) -> Self {
+        self.queue_timeout = timeout;
+        self
+    }
+
+    /// Weight currently available
+    pub fn available(&self) -> u32 {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+        bucket.tokens as u32
+    }
+
+    /// Take `weight` tokens without waiting
     pub fn check_weight(&self, weight: u32) -> Result<(), HyperliquidError> {
-        let mut tokens = self.tokens.lock().unwrap();
-        let mut last_refill = self.last_refill.lock().unwrap();
+        self.try_take(weight)
+            .map_err(|available| HyperliquidError::RateLimited {
+                available: available as u32,
+                required: weight,
+            })
+    }
 
-        // Refill tokens based on elapsed time
-        let now = Instant::now();
-        let elapsed = now.duration_since(*last_refill).as_secs_f64();
-        let tokens_to_add = elapsed * self.refill_rate;
+    /// Take `weight` tokens, waiting for them to refill if needed
+    ///
+    /// Waiters are served first come, first served. Fails with
+    /// [`HyperliquidError::RateLimited`] if the tokens cannot be had within the
+    /// queue timeout.
+    pub async fn acquire(&self, weight: u32) -> Result<(), HyperliquidError> {
+        let deadline = tokio::time::Instant::now() + self.queue_timeout;
+        let rate_limited = |available: f64| HyperliquidError::RateLimited {
+            available: available as u32,
+            required: weight,
+        };
+
+        let Ok(_turn) = tokio::time::timeout_at(deadline, self.queue.lock()).await else {
+            return Err(rate_limited(self.available() as f64));
+        };
+
+        loop {
+            let available = match self.try_take(weight) {
+                Ok(()) => return Ok(()),
+                Err(available) => available,
+            };
+            if weight as f64 > self.max_tokens || self.refill_rate <= 0.0 {
+                return Err(rate_limited(available));
+            }
+
+            let wait =
+                Duration::from_secs_f64((weight as f64 - available) / self.refill_rate);
+            if tokio::time::Instant::now() + wait > deadline {
+                return Err(rate_limited(available));
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
 
-        *tokens = (*tokens + tokens_to_add).min(self.max_tokens);
-        *last_refill = now;
+    // Take `weight` tokens, or return how many are available
+    fn try_take(&self, weight: u32) -> Result<(), f64> {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
 
-        // Check if we have enough tokens
-        if *tokens >= weight as f64 {
-            *tokens -= weight as f64;
+        if bucket.tokens >= weight as f64 {
+            bucket.tokens -= weight as f64;
             Ok(())
         } else {
-            Err(HyperliquidError::RateLimited {
-                available: *tokens as u32,
-                required: weight,
-            })
+            Err(bucket.tokens)
         }
     }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_rate).min(self.max_tokens);
+        bucket.last_refill = now;
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(RATE_LIMIT_MAX_TOKENS, RATE_LIMIT_REFILL_RATE)
+    }
 }
 
+/// Rate limit weight of an info request, by its `type`
+pub(crate) fn info_weight(request: &serde_json::Value) -> u32 {
+    match request.get("type").and_then(|t| t.as_str()) {
+        Some("allMids") => WEIGHT_ALL_MIDS,
+        Some("l2Book") => WEIGHT_L2_BOOK,
+        Some("clearinghouseState") => WEIGHT_USER_STATE,
+        Some("userFills") => WEIGHT_USER_FILLS,
+        Some("userFunding") => WEIGHT_USER_FUNDING,
+        Some("userFees") => WEIGHT_USER_FEES,
+        Some("openOrders") => WEIGHT_OPEN_ORDERS,
+        Some("orderStatus") => WEIGHT_ORDER_STATUS,
+        Some("recentTrades") => WEIGHT_RECENT_TRADES,
+        Some("candleSnapshot") => WEIGHT_CANDLES,
+        Some("fundingHistory") => WEIGHT_FUNDING_HISTORY,
+        Some("spotClearinghouseState") => WEIGHT_TOKEN_BALANCES,
+        Some("referral") => WEIGHT_REFERRAL,
+        _ => WEIGHT_INFO_DEFAULT,
+    }
+}
+
+#[derive(Clone)]
 pub struct InfoProvider {
-    client: Client<HttpsConnector<HttpConnector>, Full<Bytes>>,
-    endpoint: &'static str,
+    transport: Arc<dyn Transport>,
+    endpoint: String,
+    rate_limiter: Arc<RateLimiter>,
+    retry_policy: RetryPolicy,
 }
 
 impl InfoProvider {
@@ -74,25 +173,35 @@ impl InfoProvider {
     }
 
     pub fn new(network: Network) -> Self {
-        // Initialize rustls crypto provider if not already set
-        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        Self {
+            transport: Arc::new(HyperTransport::new()),
+            endpoint: network.info_url(),
+            rate_limiter: Arc::new(RateLimiter::default()),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
 
-        let https = HttpsConnectorBuilder::new()
-            .with_native_roots()
-            .expect("TLS initialization failed")
-            .https_only()
-            .enable_http1()
-            .build();
+    /// Retry failed requests according to `policy`
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
 
-        let client = Client::builder(TokioExecutor::new()).build(https);
+    /// Send requests through `transport` instead of the default hyper client
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = transport;
+        self
+    }
 
-        Self {
-            client,
-            endpoint: match network {
-                Network::Mainnet => "https://api.hyperliquid.xyz/info",
-                Network::Testnet => "https://api.hyperliquid-testnet.xyz/info",
-            },
-        }
+    /// Draw request weight from `rate_limiter`, e.g. one shared with other providers
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Get the rate limiter this provider draws from
+    pub fn rate_limiter(&self) -> &Arc<RateLimiter> {
+        &self.rate_limiter
     }
 
     async fn request<T>(
@@ -102,32 +211,38 @@ impl InfoProvider {
     where
         T: serde::de::DeserializeOwned,
     {
-        let body_string = serde_json::to_string(&request_json)?;
-        let body_bytes = Bytes::from(body_string);
-
-        let req = Request::builder()
-            .method(Method::POST)
-            .uri(self.endpoint)
-            .header("Content-Type", "application/json")
-            .body(Full::new(body_bytes))?;
-
-        let res = self
-            .client
-            .request(req)
-            .await
-            .map_err(|e| HyperliquidError::Network(e.to_string()))?;
-        let status = res.status();
-
-        let body_bytes = res
-            .collect()
-            .await
-            .map_err(|e| HyperliquidError::Network(e.to_string()))?
-            .to_bytes();
+        let body_bytes = Bytes::from(serde_json::to_string(&request_json)?);
+        let weight = info_weight(&request_json);
+
+        let mut retry = 0;
+        loop {
+            // Every attempt costs weight
+            self.rate_limiter.acquire(weight).await?;
+
+            match self.send(body_bytes.clone()).await {
+                Err(e)
+                    if retry + 1 < self.retry_policy.max_attempts
+                        && self.retry_policy.is_retryable(&e) =>
+                {
+                    tokio::time::sleep(self.retry_policy.backoff(retry)).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send<T>(&self, body_bytes: Bytes) -> Result<T, HyperliquidError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let (status, body_bytes) =
+            self.transport.post(&self.endpoint, body_bytes).await?;
         let body_str = String::from_utf8_lossy(&body_bytes);
 
-        if !status.is_success() {
+        if !(200..300).contains(&status) {
             return Err(HyperliquidError::Http {
-                status: status.as_u16(),
+                status,
                 body: body_str.to_string(),
             });
         }
@@ -138,7 +253,7 @@ impl InfoProvider {
 
     // ==================== Simple Direct Methods ====================
 
-    pub async fn all_mids(&self) -> Result<HashMap<String, String>, HyperliquidError> {
+    pub async fn all_mids(&self) -> Result<HashMap<String, Decimal>, HyperliquidError> {
         let request = json!({
             "type": "allMids"
         });
@@ -256,6 +371,105 @@ impl InfoProvider {
         self.request(request).await
     }
 
+    /// Sub-accounts of `user` with their perp and spot state
+    pub async fn sub_accounts(
+        &self,
+        user: Address,
+    ) -> Result<Vec<SubAccount>, HyperliquidError> {
+        let request = json!({
+            "type": "subAccounts",
+            "user": user
+        });
+        // Users without sub-accounts get `null`
+        let sub_accounts: Option<Vec<SubAccount>> = self.request(request).await?;
+        Ok(sub_accounts.unwrap_or_default())
+    }
+
+    /// Details of a vault, with `user`'s stake in `follower_state` if given
+    pub async fn vault_details(
+        &self,
+        vault_address: Address,
+        user: Option<Address>,
+    ) -> Result<VaultDetails, HyperliquidError> {
+        let mut request = json!({
+            "type": "vaultDetails",
+            "vaultAddress": vault_address
+        });
+        if let Some(user) = user {
+            request["user"] = json!(user);
+        }
+        self.request(request).await
+    }
+
+    /// Equity `user` holds in each vault they follow
+    pub async fn user_vault_equities(
+        &self,
+        user: Address,
+    ) -> Result<Vec<UserVaultEquity>, HyperliquidError> {
+        let request = json!({
+            "type": "userVaultEquities",
+            "user": user
+        });
+        self.request(request).await
+    }
+
+    pub async fn delegations(
+        &self,
+        user: Address,
+    ) -> Result<Vec<Delegation>, HyperliquidError> {
+        let request = json!({
+            "type": "delegations",
+            "user": user
+        });
+        self.request(request).await
+    }
+
+    pub async fn delegator_summary(
+        &self,
+        user: Address,
+    ) -> Result<DelegatorSummary, HyperliquidError> {
+        let request = json!({
+            "type": "delegatorSummary",
+            "user": user
+        });
+        self.request(request).await
+    }
+
+    /// Delegations, staking deposits and withdrawals, most recent first
+    pub async fn delegator_history(
+        &self,
+        user: Address,
+    ) -> Result<Vec<DelegatorHistoryEntry>, HyperliquidError> {
+        let request = json!({
+            "type": "delegatorHistory",
+            "user": user
+        });
+        self.request(request).await
+    }
+
+    pub async fn delegator_rewards(
+        &self,
+        user: Address,
+    ) -> Result<Vec<DelegatorReward>, HyperliquidError> {
+        let request = json!({
+            "type": "delegatorRewards",
+            "user": user
+        });
+        self.request(request).await
+    }
+
+    /// Requests used and allowed for `user` under the address-based limit
+    pub async fn user_rate_limit(
+        &self,
+        user: Address,
+    ) -> Result<UserRateLimit, HyperliquidError> {
+        let request = json!({
+            "type": "userRateLimit",
+            "user": user
+        });
+        self.request(request).await
+    }
+
     pub async fn referral(
         &self,
         user: Address,
@@ -411,3 +625,48 @@ impl<'a> FundingHistoryBuilder<'a> {
         self.provider.request(request).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_refill() {
+        // 10 tokens, refilling 10 per second
+        let limiter = RateLimiter::new(10, 600);
+
+        limiter.acquire(10).await.unwrap();
+        assert!(limiter.check_weight(5).is_err());
+
+        let start = Instant::now();
+        limiter.acquire(5).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_gives_up_after_queue_timeout() {
+        let limiter =
+            RateLimiter::new(10, 60).with_queue_timeout(Duration::from_millis(50));
+
+        limiter.acquire(10).await.unwrap();
+        match limiter.acquire(5).await {
+            Err(HyperliquidError::RateLimited { required, .. }) => {
+                assert_eq!(required, 5)
+            }
+            other => panic!("expected rate limited, got {other:?}"),
+        }
+
+        // More than the bucket holds can never be acquired
+        assert!(RateLimiter::new(10, 600).acquire(11).await.is_err());
+    }
+
+    #[test]
+    fn test_info_weights() {
+        assert_eq!(info_weight(&json!({"type": "l2Book"})), WEIGHT_L2_BOOK);
+        assert_eq!(
+            info_weight(&json!({"type": "userFills"})),
+            WEIGHT_USER_FILLS
+        );
+        assert_eq!(info_weight(&json!({"type": "meta"})), WEIGHT_INFO_DEFAULT);
+    }
+}
@@ -1,22 +1,37 @@
+pub mod address_budget;
 pub mod agent;
+pub mod agent_store;
+pub mod asset_registry;
 pub mod batcher;
+pub mod cassette;
 pub mod exchange;
 pub mod info;
 pub mod nonce;
 pub mod order_tracker;
+pub mod retry;
+pub mod transport;
 pub mod websocket;
 
 // Raw providers (backwards compatibility)
-pub use batcher::OrderHandle;
+pub use batcher::{BatchConfig, OrderHandle};
 // Common types
-pub use exchange::OrderBuilder;
+pub use address_budget::{AddressBudget, AddressBudgetConfig, LowBudgetPolicy};
+pub use agent_store::{AgentStore, FileAgentStore};
+pub use asset_registry::{AssetInfo, AssetRef, AssetRegistry, SpotAsset};
+pub use cassette::{
+    Cassette, CassetteEntry, CassetteRecorder, RecordingTransport, ReplayTransport,
+    WsDirection,
+};
 pub use exchange::RawExchangeProvider as ExchangeProvider;
+pub use exchange::{BracketOrder, BracketResponse, BulkReport, OrderBuilder};
 // Explicit raw exports
 pub use exchange::RawExchangeProvider;
 // Managed providers
-pub use exchange::{ManagedExchangeConfig, ManagedExchangeProvider};
+pub use exchange::{CancelHeartbeat, ManagedExchangeConfig, ManagedExchangeProvider};
 pub use info::InfoProvider;
 pub use info::RateLimiter;
+pub use retry::RetryPolicy;
+pub use transport::{HyperTransport, Transport, TransportError};
 pub use websocket::RawWsProvider as WsProvider;
 pub use websocket::RawWsProvider;
 pub use websocket::SubscriptionId;
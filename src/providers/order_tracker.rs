@@ -62,6 +62,28 @@ impl OrderTracker {
         }
     }
 
+    /// Replace a tracked order's request after it was modified
+    ///
+    /// If the new request carries a different cloid, the order is tracked
+    /// under that one from now on. Returns the cloid the order is tracked under.
+    pub fn update_order(&self, cloid: &Uuid, order: OrderRequest) -> Uuid {
+        let new_cloid = order
+            .cloid
+            .as_deref()
+            .and_then(|c| Uuid::parse_str(c).ok())
+            .unwrap_or(*cloid);
+
+        let mut orders = self.orders.write().unwrap();
+        if let Some(mut tracked) = orders.remove(cloid) {
+            tracked.cloid = new_cloid;
+            tracked.order = order;
+            tracked.status = OrderStatus::Pending;
+            tracked.response = None;
+            orders.insert(new_cloid, tracked);
+        }
+        new_cloid
+    }
+
     /// Get a specific order by CLOID
     pub fn get_order(&self, cloid: &Uuid) -> Option<TrackedOrder> {
         let orders = self.orders.read().unwrap();
@@ -0,0 +1,121 @@
+//! Retry policy for failed HTTP requests
+
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::errors::HyperliquidError;
+
+/// When and how often to retry a failed request
+///
+/// Info requests are read-only and retried on any retryable status or network
+/// error. Exchange actions are only resent when they cannot have been processed
+/// (the connection failed or the exchange answered 429), and always with the
+/// original nonce and signature, so a retry can never execute an action twice.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Attempts including the first, so 1 disables retries
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each retry after it
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between attempts
+    pub max_backoff: Duration,
+    /// Pick each delay uniformly between zero and its backoff
+    pub jitter: bool,
+    /// HTTP statuses worth retrying
+    pub retry_statuses: Vec<u16>,
+    /// Retry network errors such as connection resets and timeouts
+    pub retry_network_errors: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            jitter: true,
+            retry_statuses: vec![429, 500, 502, 503, 504],
+            retry_network_errors: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Whether a request that failed with `error` is worth retrying
+    pub fn is_retryable(&self, error: &HyperliquidError) -> bool {
+        match error {
+            HyperliquidError::Http { status, .. } => self.retry_statuses.contains(status),
+            HyperliquidError::Network(_) => self.retry_network_errors,
+            _ => false,
+        }
+    }
+
+    /// Delay before retry number `retry` (starting at 0)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+        if self.jitter {
+            rand::thread_rng().gen_range(Duration::ZERO..=backoff)
+        } else {
+            backoff
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            jitter: false,
+            max_backoff: Duration::from_millis(500),
+            ..RetryPolicy::default()
+        };
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(3), Duration::from_millis(500));
+        assert_eq!(policy.backoff(40), Duration::from_millis(500));
+
+        let jittered = RetryPolicy::default();
+        assert!(jittered.backoff(1) <= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        let policy = RetryPolicy::default();
+        let http = |status| HyperliquidError::Http {
+            status,
+            body: String::new(),
+        };
+
+        assert!(policy.is_retryable(&http(503)));
+        assert!(policy.is_retryable(&http(429)));
+        assert!(!policy.is_retryable(&http(422)));
+        assert!(policy.is_retryable(&HyperliquidError::Network("reset".into())));
+        assert!(!policy.is_retryable(&HyperliquidError::RateLimited {
+            available: 0,
+            required: 1
+        }));
+
+        let strict = RetryPolicy {
+            retry_network_errors: false,
+            ..RetryPolicy::default()
+        };
+        assert!(!strict.is_retryable(&HyperliquidError::Network("reset".into())));
+    }
+}
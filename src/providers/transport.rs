@@ -0,0 +1,101 @@
+//! HTTP transport used by the info and exchange providers
+
+use async_trait::async_trait;
+use http::{Method, Request};
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::client::legacy::{connect::HttpConnector, Client};
+use hyper_util::rt::TokioExecutor;
+use thiserror::Error;
+
+use crate::errors::HyperliquidError;
+
+/// Why a request produced no response
+#[derive(Error, Debug)]
+pub enum TransportError {
+    /// The request never left this process, e.g. the connection could not be
+    /// established, so it is always safe to resend
+    #[error("not sent: {0}")]
+    NotSent(String),
+
+    /// The request may have reached the server before failing
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl From<TransportError> for HyperliquidError {
+    fn from(e: TransportError) -> Self {
+        HyperliquidError::Network(e.to_string())
+    }
+}
+
+/// Sends JSON request bodies and returns the raw response
+///
+/// Providers use [`HyperTransport`] unless given another through their
+/// `with_transport` methods, e.g. to share one connection pool, go through a
+/// proxy, record traffic or answer from memory in tests.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// POST `body` as JSON to `url`, returning the status code and response body
+    async fn post(&self, url: &str, body: Bytes) -> Result<(u16, Bytes), TransportError>;
+}
+
+/// Default [`Transport`] over a pooled hyper client with native TLS roots
+#[derive(Clone)]
+pub struct HyperTransport {
+    client: Client<HttpsConnector<HttpConnector>, Full<Bytes>>,
+}
+
+impl HyperTransport {
+    pub fn new() -> Self {
+        // Initialize rustls crypto provider if not already set
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+        let https = HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .expect("TLS initialization failed")
+            .https_or_http()
+            .enable_http1()
+            .build();
+
+        Self {
+            client: Client::builder(TokioExecutor::new()).build(https),
+        }
+    }
+}
+
+impl Default for HyperTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Transport for HyperTransport {
+    async fn post(&self, url: &str, body: Bytes) -> Result<(u16, Bytes), TransportError> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(url)
+            .header("Content-Type", "application/json")
+            .body(Full::new(body))
+            .map_err(|e| TransportError::NotSent(e.to_string()))?;
+
+        let response = self.client.request(request).await.map_err(|e| {
+            if e.is_connect() {
+                TransportError::NotSent(e.to_string())
+            } else {
+                TransportError::Failed(e.to_string())
+            }
+        })?;
+        let status = response.status().as_u16();
+
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .map_err(|e| TransportError::Failed(e.to_string()))?
+            .to_bytes();
+        Ok((status, body))
+    }
+}
@@ -1,8 +1,12 @@
 //! WebSocket provider for real-time market data and user events
 
-use std::sync::{
-    atomic::{AtomicU32, Ordering},
-    Arc,
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
 };
 
 use dashmap::DashMap;
@@ -10,23 +14,124 @@ use fastwebsockets::{handshake, Frame, OpCode, Role, WebSocket};
 use http_body_util::Empty;
 use hyper::{body::Bytes, header, upgrade::Upgraded, Request, StatusCode};
 use hyper_util::rt::TokioIo;
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf, ReadHalf, WriteHalf};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
 
 use crate::{
     errors::HyperliquidError,
-    types::ws::{Message, Subscription, WsRequest},
+    providers::cassette::{self, Cassette, CassetteRecorder, WsDirection},
+    providers::info::{info_weight, RateLimiter},
+    types::ws::{Message, PostRequest, PostResponse, Subscription, WsRequest},
     types::Symbol,
     Network,
 };
 
 pub type SubscriptionId = u32;
 
+type PendingPosts = Arc<DashMap<u64, oneshot::Sender<PostResponse>>>;
+
 #[derive(Clone)]
 struct SubscriptionHandle {
     subscription: Subscription,
     tx: UnboundedSender<Message>,
 }
 
+/// One half of a split stream paired with an inert counterpart, so reading and
+/// writing can each be driven by their own `WebSocket` from separate tasks
+pub(crate) struct HalfStream<R, W> {
+    read: R,
+    write: W,
+}
+
+impl<R: AsyncRead + Unpin, W: Unpin> AsyncRead for HalfStream<R, W> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.read).poll_read(cx, buf)
+    }
+}
+
+impl<R: Unpin, W: AsyncWrite + Unpin> AsyncWrite for HalfStream<R, W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.write).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.write).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.write.is_write_vectored()
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.write).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.write).poll_shutdown(cx)
+    }
+}
+
+pub(crate) type WsReader<S> = WebSocket<HalfStream<ReadHalf<S>, tokio::io::Sink>>;
+pub(crate) type WsWriter<S> = WebSocket<HalfStream<tokio::io::Empty, WriteHalf<S>>>;
+
+/// Split an upgraded stream into independently owned read and write sockets
+///
+/// Automatic pong and close replies are disabled on the read side, since it
+/// cannot write; callers answer those frames through the write side.
+pub(crate) fn split_websocket<S>(stream: S, role: Role) -> (WsReader<S>, WsWriter<S>)
+where
+    S: AsyncRead + AsyncWrite,
+{
+    let (read, write) = tokio::io::split(stream);
+
+    let mut reader = WebSocket::after_handshake(
+        HalfStream {
+            read,
+            write: tokio::io::sink(),
+        },
+        role,
+    );
+    reader.set_auto_pong(false);
+    reader.set_auto_close(false);
+
+    let writer = WebSocket::after_handshake(
+        HalfStream {
+            read: tokio::io::empty(),
+            write,
+        },
+        role,
+    );
+
+    (reader, writer)
+}
+
+pub(crate) trait WsStream: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<S: AsyncRead + AsyncWrite + Send + Unpin> WsStream for S {}
+
+// A socket to the exchange, or an in-memory stream replaying a cassette
+type ClientStream = Box<dyn WsStream>;
+
 /// Raw WebSocket provider for Hyperliquid
 ///
 /// This is a thin wrapper around fastwebsockets that provides:
@@ -35,47 +140,86 @@ struct SubscriptionHandle {
 /// - No automatic reconnection (user controls retry logic)
 pub struct RawWsProvider {
     _network: Network,
-    ws: Option<WebSocket<TokioIo<Upgraded>>>,
+    writer: Arc<tokio::sync::Mutex<WsWriter<ClientStream>>>,
+    reader: Option<WsReader<ClientStream>>,
+    connected: Arc<AtomicBool>,
     subscriptions: Arc<DashMap<SubscriptionId, SubscriptionHandle>>,
     next_id: Arc<AtomicU32>,
+    pending_posts: PendingPosts,
+    next_post_id: AtomicU64,
     message_tx: Option<UnboundedSender<String>>,
     task_handle: Option<tokio::task::JoinHandle<()>>,
+    reader_handle: Option<tokio::task::JoinHandle<()>>,
+    recorder: Option<Arc<CassetteRecorder>>,
 }
 
 impl RawWsProvider {
     /// Connect to Hyperliquid WebSocket
     pub async fn connect(network: Network) -> Result<Self, HyperliquidError> {
-        let url = match network {
-            Network::Mainnet => "https://api.hyperliquid.xyz/ws",
-            Network::Testnet => "https://api.hyperliquid-testnet.xyz/ws",
+        // The upgrade handshake is a plain HTTP(S) request
+        let ws_url = network.ws_url();
+        let url = if let Some(rest) = ws_url.strip_prefix("wss://") {
+            format!("https://{rest}")
+        } else if let Some(rest) = ws_url.strip_prefix("ws://") {
+            format!("http://{rest}")
+        } else {
+            ws_url.to_string()
         };
 
-        let ws = Self::establish_connection(url).await?;
+        let stream = Self::establish_connection(&url).await?;
+        Ok(Self::from_stream(network, Box::new(stream)))
+    }
+
+    /// Replay the WebSocket frames of `cassette` instead of connecting
+    ///
+    /// Recorded frames are delivered as the provider sends its own requests,
+    /// in the order they were recorded.
+    pub fn replay(network: Network, cassette: &Cassette) -> Self {
+        Self::from_stream(network, Box::new(cassette::replay_stream(cassette)))
+    }
+
+    /// Record every text frame sent and received to `recorder`
+    pub fn with_recorder(mut self, recorder: Arc<CassetteRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    fn from_stream(network: Network, stream: ClientStream) -> Self {
+        let (reader, writer) = split_websocket(stream, Role::Client);
         let subscriptions = Arc::new(DashMap::new());
         let next_id = Arc::new(AtomicU32::new(1));
+        let pending_posts: PendingPosts = Arc::new(DashMap::new());
 
         // Create message routing channel
         let (message_tx, message_rx) = mpsc::unbounded_channel();
 
         // Spawn message routing task
         let subscriptions_clone = subscriptions.clone();
+        let pending_posts_clone = pending_posts.clone();
         let task_handle = tokio::spawn(async move {
-            Self::message_router(message_rx, subscriptions_clone).await;
+            Self::message_router(message_rx, subscriptions_clone, pending_posts_clone)
+                .await;
         });
 
-        Ok(Self {
+        Self {
             _network: network,
-            ws: Some(ws),
+            writer: Arc::new(tokio::sync::Mutex::new(writer)),
+            reader: Some(reader),
+            connected: Arc::new(AtomicBool::new(true)),
             subscriptions,
             next_id,
+            pending_posts,
+            next_post_id: AtomicU64::new(1),
             message_tx: Some(message_tx),
             task_handle: Some(task_handle),
-        })
+            reader_handle: None,
+            recorder: None,
+        }
     }
 
     async fn establish_connection(
         url: &str,
-    ) -> Result<WebSocket<TokioIo<Upgraded>>, HyperliquidError> {
+    ) -> Result<TokioIo<Upgraded>, HyperliquidError> {
         use hyper_rustls::HttpsConnectorBuilder;
         use hyper_util::client::legacy::Client;
 
@@ -89,7 +233,7 @@ impl RawWsProvider {
             .map_err(|e| {
                 HyperliquidError::WebSocket(format!("Failed to load native roots: {e}"))
             })?
-            .https_only()
+            .https_or_http()
             .enable_http1()
             .build();
 
@@ -98,7 +242,8 @@ impl RawWsProvider {
 
         // Create WebSocket upgrade request
         let host = uri
-            .host()
+            .authority()
+            .map(|authority| authority.as_str())
             .ok_or_else(|| HyperliquidError::WebSocket("No host in URL".to_string()))?;
 
         let req = Request::builder()
@@ -129,10 +274,27 @@ impl RawWsProvider {
             .await
             .map_err(|e| HyperliquidError::WebSocket(format!("Upgrade failed: {e}")))?;
 
-        Ok(WebSocket::after_handshake(
-            TokioIo::new(upgraded),
-            Role::Client,
-        ))
+        Ok(TokioIo::new(upgraded))
+    }
+
+    // Write a text frame, marking the connection as lost if the write fails
+    async fn send_text(&mut self, payload: String) -> Result<(), String> {
+        if !self.is_connected() {
+            return Err("Not connected".to_string());
+        }
+
+        if let Some(recorder) = &self.recorder {
+            recorder.record_ws(WsDirection::Sent, &payload);
+        }
+
+        let mut writer = self.writer.lock().await;
+        let result = writer
+            .write_frame(Frame::text(payload.into_bytes().into()))
+            .await;
+        if result.is_err() {
+            self.connected.store(false, Ordering::SeqCst);
+        }
+        result.map_err(|e| e.to_string())
     }
 
     /// Subscribe to L2 order book updates
@@ -171,21 +333,14 @@ impl RawWsProvider {
         &mut self,
         subscription: Subscription,
     ) -> Result<(SubscriptionId, UnboundedReceiver<Message>), HyperliquidError> {
-        let ws = self
-            .ws
-            .as_mut()
-            .ok_or_else(|| HyperliquidError::WebSocket("Not connected".to_string()))?;
-
         // Send subscription request
         let request = WsRequest::subscribe(subscription.clone());
         let payload = serde_json::to_string(&request)
             .map_err(|e| HyperliquidError::Serialize(e.to_string()))?;
 
-        ws.write_frame(Frame::text(payload.into_bytes().into()))
-            .await
-            .map_err(|e| {
-                HyperliquidError::WebSocket(format!("Failed to send subscription: {e}"))
-            })?;
+        self.send_text(payload).await.map_err(|e| {
+            HyperliquidError::WebSocket(format!("Failed to send subscription: {e}"))
+        })?;
 
         // Create channel for this subscription
         let (tx, rx) = mpsc::unbounded_channel();
@@ -203,77 +358,118 @@ impl RawWsProvider {
         id: SubscriptionId,
     ) -> Result<(), HyperliquidError> {
         if let Some((_, handle)) = self.subscriptions.remove(&id) {
-            let ws = self.ws.as_mut().ok_or_else(|| {
-                HyperliquidError::WebSocket("Not connected".to_string())
-            })?;
-
             let request = WsRequest::unsubscribe(handle.subscription);
             let payload = serde_json::to_string(&request)
                 .map_err(|e| HyperliquidError::Serialize(e.to_string()))?;
 
-            ws.write_frame(Frame::text(payload.into_bytes().into()))
-                .await
-                .map_err(|e| {
-                    HyperliquidError::WebSocket(format!(
-                        "Failed to send unsubscribe: {e}"
-                    ))
-                })?;
+            self.send_text(payload).await.map_err(|e| {
+                HyperliquidError::WebSocket(format!("Failed to send unsubscribe: {e}"))
+            })?;
         }
 
         Ok(())
     }
 
+    /// Send an info request over the socket instead of HTTP
+    ///
+    /// `request` is the body that would be posted to `/info`; the response's
+    /// `data` is deserialized into `T`. Reading must have been started.
+    pub async fn post_info<T: DeserializeOwned>(
+        &mut self,
+        request: serde_json::Value,
+    ) -> Result<T, HyperliquidError> {
+        let response = self.send_post(PostRequest::Info(request)).await?;
+        info_data(response.await)
+    }
+
+    // Send a post request, returning a receiver for its response
+    async fn send_post(
+        &mut self,
+        request: PostRequest,
+    ) -> Result<oneshot::Receiver<PostResponse>, HyperliquidError> {
+        let id = self.next_post_id.fetch_add(1, Ordering::SeqCst);
+        let payload = serde_json::to_string(&WsRequest::post(id, request))
+            .map_err(|e| HyperliquidError::Serialize(e.to_string()))?;
+
+        let (tx, rx) = oneshot::channel();
+        self.pending_posts.insert(id, tx);
+        if let Err(e) = self.send_text(payload).await {
+            self.pending_posts.remove(&id);
+            return Err(HyperliquidError::WebSocket(format!(
+                "Failed to send post request: {e}"
+            )));
+        }
+
+        Ok(rx)
+    }
+
     /// Send a ping to keep connection alive
     pub async fn ping(&mut self) -> Result<(), HyperliquidError> {
-        let ws = self
-            .ws
-            .as_mut()
-            .ok_or_else(|| HyperliquidError::WebSocket("Not connected".to_string()))?;
-
         let request = WsRequest::ping();
         let payload = serde_json::to_string(&request)
             .map_err(|e| HyperliquidError::Serialize(e.to_string()))?;
 
-        ws.write_frame(Frame::text(payload.into_bytes().into()))
-            .await
-            .map_err(|e| {
-                HyperliquidError::WebSocket(format!("Failed to send ping: {e}"))
-            })?;
+        self.send_text(payload).await.map_err(|e| {
+            HyperliquidError::WebSocket(format!("Failed to send ping: {e}"))
+        })?;
 
         Ok(())
     }
 
     /// Check if connected
     pub fn is_connected(&self) -> bool {
-        self.ws.is_some()
+        self.connected.load(Ordering::SeqCst)
     }
 
     /// Start reading messages (must be called after connecting)
+    ///
+    /// Subscriptions can still be added after reading has started.
     pub async fn start_reading(&mut self) -> Result<(), HyperliquidError> {
-        let mut ws = self
-            .ws
-            .take()
-            .ok_or_else(|| HyperliquidError::WebSocket("Not connected".to_string()))?;
+        let mut reader = self.reader.take().ok_or_else(|| {
+            HyperliquidError::WebSocket("Already reading or not connected".to_string())
+        })?;
 
         let message_tx = self.message_tx.clone().ok_or_else(|| {
             HyperliquidError::WebSocket("Message channel not initialized".to_string())
         })?;
 
-        tokio::spawn(async move {
-            while let Ok(frame) = ws.read_frame().await {
+        let writer = self.writer.clone();
+        let connected = self.connected.clone();
+        let pending_posts = self.pending_posts.clone();
+        let recorder = self.recorder.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok(frame) = reader.read_frame().await {
                 match frame.opcode {
                     OpCode::Text => {
                         if let Ok(text) = String::from_utf8(frame.payload.to_vec()) {
+                            if let Some(recorder) = &recorder {
+                                recorder.record_ws(WsDirection::Received, &text);
+                            }
                             let _ = message_tx.send(text);
                         }
                     }
+                    OpCode::Ping => {
+                        let pong = Frame::pong(frame.payload.to_vec().into());
+                        if writer.lock().await.write_frame(pong).await.is_err() {
+                            break;
+                        }
+                    }
                     OpCode::Close => {
+                        let _ = writer
+                            .lock()
+                            .await
+                            .write_frame(Frame::close_raw(vec![].into()))
+                            .await;
                         break;
                     }
                     _ => {}
                 }
             }
+            connected.store(false, Ordering::SeqCst);
+            // No response will arrive for posts still in flight
+            pending_posts.clear();
         });
+        self.reader_handle = Some(handle);
 
         Ok(())
     }
@@ -281,16 +477,24 @@ impl RawWsProvider {
     async fn message_router(
         mut rx: UnboundedReceiver<String>,
         subscriptions: Arc<DashMap<SubscriptionId, SubscriptionHandle>>,
+        pending_posts: PendingPosts,
     ) {
         while let Some(text) = rx.recv().await {
             // Use simd-json for fast parsing
             let mut text_bytes = text.into_bytes();
             match simd_json::from_slice::<Message>(&mut text_bytes) {
+                Ok(Message::Post(post)) => {
+                    if let Some((_, tx)) = pending_posts.remove(&post.data.id) {
+                        let _ = tx.send(post.data.response);
+                    }
+                }
                 Ok(message) => {
-                    // Route to all active subscriptions
-                    // In a more sophisticated implementation, we'd match by subscription type
+                    // Route only to the subscriptions that produced this message
                     for entry in subscriptions.iter() {
-                        let _ = entry.value().tx.send(message.clone());
+                        let handle = entry.value();
+                        if handle.subscription.matches(&message) {
+                            let _ = handle.tx.send(message.clone());
+                        }
                     }
                 }
                 Err(_) => {
@@ -307,6 +511,27 @@ impl Drop for RawWsProvider {
         if let Some(handle) = self.task_handle.take() {
             handle.abort();
         }
+        if let Some(handle) = self.reader_handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+// Extract the `data` of an info post response
+fn info_data<T: DeserializeOwned>(
+    response: Result<PostResponse, oneshot::error::RecvError>,
+) -> Result<T, HyperliquidError> {
+    match response {
+        Ok(PostResponse::Info(mut payload)) => {
+            Ok(serde_json::from_value(payload["data"].take())?)
+        }
+        Ok(PostResponse::Error(e)) => Err(HyperliquidError::InvalidResponse(e)),
+        Ok(PostResponse::Action(_)) => Err(HyperliquidError::InvalidResponse(
+            "Expected an info response to an info request".to_string(),
+        )),
+        Err(_) => Err(HyperliquidError::WebSocket(
+            "Connection closed before the post response arrived".to_string(),
+        )),
     }
 }
 
@@ -334,6 +559,15 @@ pub struct WsConfig {
     pub exponential_backoff: bool,
     /// Maximum backoff delay when using exponential backoff
     pub max_reconnect_delay: Duration,
+    /// Timeout waiting for the response to a post request
+    pub post_timeout: Duration,
+    /// Limiter post requests draw weight from, e.g. one shared with the HTTP
+    /// providers (None to send them unlimited)
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Record every frame of every connection to a cassette
+    pub recorder: Option<Arc<CassetteRecorder>>,
+    /// Replay a cassette instead of connecting, again on every reconnect
+    pub replay: Option<Arc<Cassette>>,
 }
 
 impl Default for WsConfig {
@@ -346,6 +580,10 @@ impl Default for WsConfig {
             max_reconnect_attempts: None,
             exponential_backoff: true,
             max_reconnect_delay: Duration::from_secs(60),
+            post_timeout: Duration::from_secs(10),
+            rate_limiter: None,
+            recorder: None,
+            replay: None,
         }
     }
 }
@@ -380,7 +618,7 @@ impl ManagedWsProvider {
         config: WsConfig,
     ) -> Result<Arc<Self>, HyperliquidError> {
         // Create initial connection
-        let raw_provider = RawWsProvider::connect(network).await?;
+        let raw_provider = Self::connect_raw(&network, &config).await?;
 
         let provider = Arc::new(Self {
             network,
@@ -409,6 +647,21 @@ impl ManagedWsProvider {
         Ok(provider)
     }
 
+    // Open a raw connection, or a replay, as configured
+    async fn connect_raw(
+        network: &Network,
+        config: &WsConfig,
+    ) -> Result<RawWsProvider, HyperliquidError> {
+        let raw_provider = match &config.replay {
+            Some(cassette) => RawWsProvider::replay(network.clone(), cassette),
+            None => RawWsProvider::connect(network.clone()).await?,
+        };
+        Ok(match &config.recorder {
+            Some(recorder) => raw_provider.with_recorder(recorder.clone()),
+            None => raw_provider,
+        })
+    }
+
     /// Connect with default configuration
     pub async fn connect_with_defaults(
         network: Network,
@@ -491,19 +744,34 @@ impl ManagedWsProvider {
         );
 
         // Forward messages from raw to managed
-        let subscriptions = self.subscriptions.clone();
+        Self::forward(self.subscriptions.clone(), managed_id, rx);
+
+        Ok((managed_id, managed_rx))
+    }
+
+    // Forward messages from a raw subscription channel to a managed subscriber.
+    // The raw channel closes whenever the underlying connection is replaced, so
+    // the managed entry is only dropped once its own receiver goes away.
+    fn forward(
+        subscriptions: Arc<DashMap<SubscriptionId, ManagedSubscription>>,
+        managed_id: SubscriptionId,
+        mut rx: UnboundedReceiver<Message>,
+    ) {
         tokio::spawn(async move {
-            let mut rx = rx;
             while let Some(msg) = rx.recv().await {
-                if let Some(entry) = subscriptions.get(&managed_id) {
-                    let _ = entry.tx.send(msg);
+                let Some(entry) = subscriptions.get(&managed_id) else {
+                    break;
+                };
+                if !entry.subscription.matches(&msg) {
+                    continue;
+                }
+                if entry.tx.send(msg).is_err() {
+                    drop(entry);
+                    subscriptions.remove(&managed_id);
+                    break;
                 }
             }
-            // Clean up when channel closes
-            subscriptions.remove(&managed_id);
         });
-
-        Ok((managed_id, managed_rx))
     }
 
     /// Unsubscribe and stop automatic replay
@@ -518,6 +786,37 @@ impl ManagedWsProvider {
         Ok(())
     }
 
+    /// Send an info request over the socket instead of HTTP
+    ///
+    /// Draws the request's weight from the configured rate limiter first. See
+    /// [`RawWsProvider::post_info`].
+    pub async fn post_info<T: DeserializeOwned>(
+        &self,
+        request: serde_json::Value,
+    ) -> Result<T, HyperliquidError> {
+        if let Some(rate_limiter) = &self.config.rate_limiter {
+            rate_limiter.acquire(info_weight(&request)).await?;
+        }
+
+        // Don't hold the connection while waiting for the response
+        let response = {
+            let mut inner = self.inner.lock().await;
+            let raw_provider = inner.as_mut().ok_or_else(|| {
+                HyperliquidError::WebSocket("Not connected".to_string())
+            })?;
+            raw_provider.send_post(PostRequest::Info(request)).await?
+        };
+
+        let response = tokio::time::timeout(self.config.post_timeout, response)
+            .await
+            .map_err(|_| {
+                HyperliquidError::WebSocket(
+                    "Timed out waiting for post response".to_string(),
+                )
+            })?;
+        info_data(response)
+    }
+
     /// Start reading messages (must be called after connecting)
     pub async fn start_reading(&self) -> Result<(), HyperliquidError> {
         let mut inner = self.inner.lock().await;
@@ -566,7 +865,7 @@ impl ManagedWsProvider {
 
                 println!("Attempting reconnection #{}", reconnect_attempts + 1);
 
-                match RawWsProvider::connect(self.network).await {
+                match Self::connect_raw(&self.network, &self.config).await {
                     Ok(mut new_provider) => {
                         // Start reading before replaying subscriptions
                         if let Err(e) = new_provider.start_reading().await {
@@ -576,12 +875,24 @@ impl ManagedWsProvider {
 
                         // Replay all subscriptions
                         let mut replay_errors = 0;
-                        for entry in self.subscriptions.iter() {
-                            if let Err(e) =
-                                new_provider.subscribe(entry.subscription.clone()).await
-                            {
-                                eprintln!("Failed to replay subscription: {e}");
-                                replay_errors += 1;
+                        let replay: Vec<_> = self
+                            .subscriptions
+                            .iter()
+                            .map(|entry| (*entry.key(), entry.subscription.clone()))
+                            .collect();
+                        for (managed_id, subscription) in replay {
+                            match new_provider.subscribe(subscription).await {
+                                Ok((_raw_id, rx)) => {
+                                    Self::forward(
+                                        self.subscriptions.clone(),
+                                        managed_id,
+                                        rx,
+                                    );
+                                }
+                                Err(e) => {
+                                    eprintln!("Failed to replay subscription: {e}");
+                                    replay_errors += 1;
+                                }
                             }
                         }
 
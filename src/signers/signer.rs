@@ -20,7 +20,7 @@ pub trait HyperliquidSigner: Send + Sync {
     fn address(&self) -> Address;
 }
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Clone, Debug, thiserror::Error)]
 pub enum SignerError {
     #[error("signing failed: {0}")]
     SigningFailed(String),
@@ -100,7 +100,9 @@ mod tests {
     use alloy::{primitives::b256, signers::local::PrivateKeySigner};
 
     use super::*;
-    use crate::types::{Agent, HyperliquidAction, UsdSend, Withdraw};
+    use crate::types::{
+        Agent, CDeposit, CWithdraw, HyperliquidAction, TokenDelegate, UsdSend, Withdraw,
+    };
 
     fn get_test_signer() -> AlloySigner<PrivateKeySigner> {
         let private_key =
@@ -214,4 +216,65 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_sign_token_delegate_action() -> Result<(), Box<dyn std::error::Error>> {
+        let signer = get_test_signer();
+
+        let delegate = TokenDelegate {
+            signature_chain_id: 421614,
+            hyperliquid_chain: "Testnet".to_string(),
+            validator: "0x5aC99df645F3414876C816Caa18b2d234024b487".parse()?,
+            wei: 100000000,
+            is_undelegate: false,
+            nonce: 1690393044548,
+        };
+
+        let signing_hash = delegate.eip712_signing_hash(&delegate.domain());
+        let sig = signer.sign_hash(signing_hash).await?;
+
+        let expected = "4ac2988821f24c19ab789fbe572d11f4b9b98fd98eef6f04f9ba858abb3494dc35458ac91367d27aca4a5819efcd1a20eb304c31ec9bf3f757270a84199dab101c";
+        let actual = format!("{:064x}{:064x}{:02x}", sig.r, sig.s, sig.v);
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_staking_transfer_actions() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let signer = get_test_signer();
+
+        let deposit = CDeposit {
+            signature_chain_id: 421614,
+            hyperliquid_chain: "Testnet".to_string(),
+            wei: 100000000,
+            nonce: 1690393044548,
+        };
+        let sig = signer
+            .sign_hash(deposit.eip712_signing_hash(&deposit.domain()))
+            .await?;
+        assert_eq!(
+            format!("{:064x}{:064x}{:02x}", sig.r, sig.s, sig.v),
+            "236016598893e096f334bc60e1e70ad73558b073f439852a703617725fecf42535d704febfb519dd41f47d889899504fb10dee1dc1183131c936b5546159994b1b"
+        );
+
+        // Same fields as cDeposit, so only the type name tells them apart
+        let withdraw = CWithdraw {
+            signature_chain_id: 421614,
+            hyperliquid_chain: "Testnet".to_string(),
+            wei: 100000000,
+            nonce: 1690393044548,
+        };
+        let sig = signer
+            .sign_hash(withdraw.eip712_signing_hash(&withdraw.domain()))
+            .await?;
+        assert_eq!(
+            format!("{:064x}{:064x}{:02x}", sig.r, sig.s, sig.v),
+            "9cb3f555c1c4b9861fe4ee3a294add9cf9d9c362ef02e2e54c1735bf21d65574758a01f015666b7353d87f74e910a3080dabf31ee9b80dd4347b34f37f7c1c7f1b"
+        );
+
+        Ok(())
+    }
 }
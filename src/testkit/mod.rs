@@ -0,0 +1,551 @@
+//! In-process mock of the Hyperliquid API for offline integration tests
+//!
+//! Enabled with the `testkit` feature. [`MockServer`] serves `/info`, `/exchange`
+//! and `/ws` on a local port and keeps a small amount of exchange state:
+//!
+//! - Every `/exchange` payload has its EIP-712 signature recovered and checked
+//!   against the signers registered with [`MockServer::expect_signer`] or
+//!   approved through `approveAgent`
+//! - Orders are matched against the configured mid price: crossing orders fill
+//!   at the mid, everything else rests until [`MockServer::set_mid`] crosses it
+//!   (IOC fills can be capped with [`MockServer::set_ioc_liquidity`])
+//! - Order and fill events are pushed to matching WebSocket subscribers
+//! - Exchange requests count against a per-address budget reported by
+//!   `userRateLimit`, set with [`MockServer::set_requests_used`]
+//!
+//! ```ignore
+//! let server = MockServer::start().await?;
+//! server.expect_signer(signer.address());
+//! let btc = server.add_perp("BTC", 5, 50);
+//! server.set_mid("BTC", "50000");
+//!
+//! let exchange = RawExchangeProvider::for_network(signer, server.network());
+//! ```
+
+mod state;
+mod verify;
+
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use alloy::primitives::Address;
+use dashmap::DashMap;
+use fastwebsockets::{upgrade, Frame, OpCode, Role};
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    body::{Bytes, Incoming},
+    server::conn::http1,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tokio::{net::TcpListener, sync::mpsc};
+
+pub use state::{MockOrder, MockPosition, RecordedRequest};
+use state::{MockState, PerpAsset, SpotPair, WsEvent};
+
+use crate::{
+    providers::{asset_registry::SPOT_ASSET_OFFSET, websocket::split_websocket},
+    Network,
+};
+
+enum Outgoing {
+    Text(String),
+    Pong(Vec<u8>),
+    Close,
+}
+
+struct WsClient {
+    tx: mpsc::UnboundedSender<Outgoing>,
+    subscriptions: Vec<Value>,
+}
+
+struct Shared {
+    state: Mutex<MockState>,
+    clients: DashMap<u64, WsClient>,
+    next_client: AtomicU64,
+}
+
+impl Shared {
+    // Run `f` against the state, then push any events it produced
+    fn with_state<R>(&self, f: impl FnOnce(&mut MockState) -> R) -> R {
+        let (result, events) = {
+            let mut state = self.state.lock();
+            let result = f(&mut state);
+            (result, std::mem::take(&mut state.events))
+        };
+        for event in events {
+            self.broadcast(event);
+        }
+        result
+    }
+
+    fn broadcast(&self, event: WsEvent) {
+        let message = json!({"channel": event.channel, "data": event.data}).to_string();
+        for client in self.clients.iter() {
+            let subscribed = client.subscriptions.iter().any(|subscription| {
+                subscription_channel(subscription) == Some(event.channel.as_str())
+                    && match (event.user, subscription_user(subscription)) {
+                        (Some(user), Some(subscriber)) => user == subscriber,
+                        _ => true,
+                    }
+            });
+            if subscribed {
+                let _ = client.tx.send(Outgoing::Text(message.clone()));
+            }
+        }
+    }
+}
+
+// Channel on which messages for a subscription arrive
+fn subscription_channel(subscription: &Value) -> Option<&str> {
+    match subscription.get("type")?.as_str()? {
+        "userEvents" => Some("user"),
+        other => Some(other),
+    }
+}
+
+fn subscription_user(subscription: &Value) -> Option<Address> {
+    subscription.get("user")?.as_str()?.parse().ok()
+}
+
+/// In-process mock of the Hyperliquid HTTP and WebSocket API
+pub struct MockServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    accept_task: tokio::task::JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Start a mock server on a random local port
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(MockState::new(false)),
+            clients: DashMap::new(),
+            next_client: AtomicU64::new(1),
+        });
+
+        let accept_shared = shared.clone();
+        let accept_task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let shared = accept_shared.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |req| handle(shared.clone(), req));
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .with_upgrades()
+                        .await;
+                });
+            }
+        });
+
+        Ok(Self {
+            addr,
+            shared,
+            accept_task,
+        })
+    }
+
+    /// Address the server is listening on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// A [`Network`] pointing at this server, signing as testnet
+    pub fn network(&self) -> Network {
+        Network::custom(
+            format!("http://{}", self.addr),
+            format!("ws://{}/ws", self.addr),
+            false,
+        )
+    }
+
+    /// Accept `/exchange` payloads signed by `address`
+    pub fn expect_signer(&self, address: Address) {
+        self.shared.state.lock().users.insert(address);
+    }
+
+    /// List a perpetual, returning its asset index
+    pub fn add_perp(&self, name: &str, sz_decimals: u32, max_leverage: u32) -> u32 {
+        let mut state = self.shared.state.lock();
+        state.perps.push(PerpAsset {
+            name: name.to_string(),
+            sz_decimals,
+            max_leverage,
+        });
+        state.perps.len() as u32 - 1
+    }
+
+    /// List a `{base}/USDC` spot pair, returning its asset index
+    pub fn add_spot_pair(&self, base: &str, sz_decimals: u32) -> u32 {
+        let mut state = self.shared.state.lock();
+        let quote = state.spot_token("USDC", 8);
+        let base_index = state.spot_token(base, sz_decimals);
+        state.spot_pairs.push(SpotPair {
+            name: format!("{base}/USDC"),
+            base: base_index,
+            quote,
+        });
+        SPOT_ASSET_OFFSET + state.spot_pairs.len() as u32 - 1
+    }
+
+    /// Set the mid price of `coin`, filling resting orders it crosses
+    pub fn set_mid(&self, coin: &str, px: &str) {
+        let mid = Decimal::from_str(px).expect("invalid mid price");
+        self.shared.with_state(|state| state.set_mid(coin, mid));
+    }
+
+    /// Cap how much of `coin` an IOC order fills; the rest is cancelled
+    pub fn set_ioc_liquidity(&self, coin: &str, sz: &str) {
+        let sz = Decimal::from_str(sz).expect("invalid liquidity");
+        self.shared
+            .state
+            .lock()
+            .ioc_liquidity
+            .insert(coin.to_string(), sz);
+    }
+
+    /// Set the USDC balance of an account
+    pub fn set_balance(&self, user: Address, usd: &str) {
+        let balance = Decimal::from_str(usd).expect("invalid balance");
+        self.shared.state.lock().account(user).balance = balance;
+    }
+
+    /// Set how many address-based requests `user` has used
+    pub fn set_requests_used(&self, user: Address, used: u64) {
+        self.shared.state.lock().account(user).requests_used = used;
+    }
+
+    /// Answer every `/info` request of `request_type` with `response`
+    pub fn set_info_response(&self, request_type: &str, response: Value) {
+        self.shared
+            .state
+            .lock()
+            .info_overrides
+            .insert(request_type.to_string(), response);
+    }
+
+    /// Reject the next correctly signed `/exchange` request with `message`
+    pub fn reject_next_exchange(&self, message: &str) {
+        self.shared
+            .state
+            .lock()
+            .rejections
+            .push_back(message.to_string());
+    }
+
+    /// Fail the next `/info` request with HTTP `status`, before it is handled
+    pub fn fail_next_info(&self, status: u16) {
+        self.fail_next("/info", status);
+    }
+
+    /// Fail the next `/exchange` request with HTTP `status`, before it is handled
+    pub fn fail_next_exchange(&self, status: u16) {
+        self.fail_next("/exchange", status);
+    }
+
+    fn fail_next(&self, path: &str, status: u16) {
+        self.shared
+            .state
+            .lock()
+            .http_failures
+            .entry(path.to_string())
+            .or_default()
+            .push_back(status);
+    }
+
+    /// All requests received so far, in order
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.shared.state.lock().requests.clone()
+    }
+
+    /// All `/exchange` requests received so far, in order
+    pub fn exchange_requests(&self) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.path == "/exchange")
+            .collect()
+    }
+
+    /// Orders resting for `user`
+    pub fn open_orders(&self, user: Address) -> Vec<MockOrder> {
+        self.shared
+            .state
+            .lock()
+            .orders
+            .iter()
+            .filter(|order| order.user == user)
+            .cloned()
+            .collect()
+    }
+
+    /// Position held by `user` in `coin`, if any
+    pub fn position(&self, user: Address, coin: &str) -> Option<MockPosition> {
+        self.shared
+            .state
+            .lock()
+            .accounts
+            .get(&user)
+            .and_then(|account| account.positions.get(coin).cloned())
+    }
+
+    /// Agents approved through `approveAgent`, with the user each acts for
+    pub fn approved_agents(&self) -> Vec<(Address, Address)> {
+        self.shared
+            .state
+            .lock()
+            .agents
+            .iter()
+            .map(|(agent, user)| (*agent, *user))
+            .collect()
+    }
+
+    /// Push a raw message to every client subscribed to its channel
+    ///
+    /// When the message data carries a `user`, only subscriptions for that
+    /// user (or without one) receive it.
+    pub fn push_ws(&self, message: Value) {
+        let Some(channel) = message.get("channel").and_then(Value::as_str) else {
+            return;
+        };
+        let data = message.get("data").cloned().unwrap_or(Value::Null);
+        let user = data
+            .get("user")
+            .and_then(Value::as_str)
+            .and_then(|user| user.parse().ok());
+        self.shared.broadcast(WsEvent {
+            channel: channel.to_string(),
+            user,
+            data,
+        });
+    }
+
+    /// Close every open WebSocket connection
+    pub fn disconnect_ws_clients(&self) {
+        self.shared.clients.retain(|_, client| {
+            let _ = client.tx.send(Outgoing::Close);
+            false
+        });
+    }
+
+    /// Number of open WebSocket connections
+    pub fn ws_client_count(&self) -> usize {
+        self.shared.clients.len()
+    }
+
+    /// Active subscriptions across all WebSocket connections
+    pub fn ws_subscriptions(&self) -> Vec<Value> {
+        self.shared
+            .clients
+            .iter()
+            .flat_map(|client| client.subscriptions.clone())
+            .collect()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        self.disconnect_ws_clients();
+    }
+}
+
+async fn handle(
+    shared: Arc<Shared>,
+    mut req: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    if req.uri().path() == "/ws" && upgrade::is_upgrade_request(&req) {
+        return Ok(match upgrade::upgrade(&mut req) {
+            Ok((response, websocket)) => {
+                tokio::spawn(async move {
+                    if let Ok(websocket) = websocket.await {
+                        serve_ws(shared, websocket.into_inner()).await;
+                    }
+                });
+                response.map(|_| Full::new(Bytes::new()))
+            }
+            Err(e) => reply(StatusCode::BAD_REQUEST, e.to_string()),
+        });
+    }
+
+    if req.method() != Method::POST {
+        return Ok(reply(StatusCode::METHOD_NOT_ALLOWED, String::new()));
+    }
+
+    let path = req.uri().path().to_string();
+    let body = match req.into_body().collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(e) => return Ok(reply(StatusCode::BAD_REQUEST, e.to_string())),
+    };
+    let Ok(body) = serde_json::from_slice::<Value>(&body) else {
+        return Ok(reply(
+            StatusCode::BAD_REQUEST,
+            "Failed to parse the request body as JSON".to_string(),
+        ));
+    };
+
+    // Injected failures are answered before the request is recorded or handled
+    let failure = shared
+        .state
+        .lock()
+        .http_failures
+        .get_mut(&path)
+        .and_then(|failures| failures.pop_front());
+    if let Some(status) = failure {
+        let status =
+            StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        return Ok(reply(status, String::new()));
+    }
+
+    let response = shared.with_state(|state| {
+        let (response, signer) = match path.as_str() {
+            "/info" => (state.handle_info(&body), None),
+            "/exchange" => {
+                let (response, signer) = state.handle_exchange(&body);
+                (Some(response), signer)
+            }
+            _ => (None, None),
+        };
+        state.requests.push(RecordedRequest {
+            path: path.clone(),
+            body,
+            signer,
+        });
+        response
+    });
+
+    Ok(match response {
+        Some(response) => reply(StatusCode::OK, response.to_string()),
+        None if path == "/info" || path == "/exchange" => reply(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Failed to deserialize the JSON body into the target type".to_string(),
+        ),
+        None => reply(StatusCode::NOT_FOUND, String::new()),
+    })
+}
+
+fn reply(status: StatusCode, body: String) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+}
+
+// Answer an info request sent over the WebSocket, recording it like `/info`
+fn post_response(shared: &Shared, request: &Value) -> Value {
+    let request = &request["request"];
+    if request["type"] != "info" {
+        return json!({"type": "error", "payload": "Only info posts are supported"});
+    }
+
+    let body = request["payload"].clone();
+    let response = shared.with_state(|state| {
+        let response = state.handle_info(&body);
+        state.requests.push(RecordedRequest {
+            path: "/info".to_string(),
+            body: body.clone(),
+            signer: None,
+        });
+        response
+    });
+
+    match response {
+        Some(data) => json!({
+            "type": "info",
+            "payload": {"type": body["type"], "data": data},
+        }),
+        None => json!({"type": "error", "payload": "Unsupported info request"}),
+    }
+}
+
+async fn serve_ws(shared: Arc<Shared>, stream: TokioIo<hyper::upgrade::Upgraded>) {
+    let (mut reader, mut writer) = split_websocket(stream, Role::Server);
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let id = shared.next_client.fetch_add(1, Ordering::SeqCst);
+    shared.clients.insert(
+        id,
+        WsClient {
+            tx: tx.clone(),
+            subscriptions: Vec::new(),
+        },
+    );
+
+    let write_task = tokio::spawn(async move {
+        while let Some(outgoing) = rx.recv().await {
+            let frame = match outgoing {
+                Outgoing::Text(text) => Frame::text(text.into_bytes().into()),
+                Outgoing::Pong(payload) => Frame::pong(payload.into()),
+                Outgoing::Close => {
+                    let _ = writer.write_frame(Frame::close(1000, b"")).await;
+                    break;
+                }
+            };
+            if writer.write_frame(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Ok(frame) = reader.read_frame().await {
+        match frame.opcode {
+            OpCode::Text => {
+                let Ok(request) = serde_json::from_slice::<Value>(&frame.payload) else {
+                    continue;
+                };
+                let subscription = request.get("subscription").cloned();
+                let reply = match request.get("method").and_then(Value::as_str) {
+                    Some("ping") => json!({"channel": "pong"}),
+                    Some(method @ ("subscribe" | "unsubscribe")) => {
+                        let Some(subscription) = subscription else {
+                            continue;
+                        };
+                        if let Some(mut client) = shared.clients.get_mut(&id) {
+                            if method == "subscribe" {
+                                client.subscriptions.push(subscription.clone());
+                            } else {
+                                client.subscriptions.retain(|s| *s != subscription);
+                            }
+                        }
+                        json!({
+                            "channel": "subscriptionResponse",
+                            "data": {"method": method, "subscription": subscription},
+                        })
+                    }
+                    Some("post") => {
+                        let Some(post_id) = request.get("id").cloned() else {
+                            continue;
+                        };
+                        json!({
+                            "channel": "post",
+                            "data": {"id": post_id, "response": post_response(&shared, &request)},
+                        })
+                    }
+                    _ => continue,
+                };
+                let _ = tx.send(Outgoing::Text(reply.to_string()));
+            }
+            OpCode::Ping => {
+                let _ = tx.send(Outgoing::Pong(frame.payload.to_vec()));
+            }
+            OpCode::Close => break,
+            _ => {}
+        }
+    }
+
+    shared.clients.remove(&id);
+    drop(tx);
+    let _ = write_task.await;
+}
@@ -0,0 +1,1216 @@
+//! Account, order book and request handling behind the mock server
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::Address;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use super::verify;
+use crate::providers::asset_registry::SPOT_ASSET_OFFSET;
+
+/// A request received by the mock server
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    /// Endpoint path, `/info` or `/exchange`
+    pub path: String,
+    /// Parsed JSON body
+    pub body: Value,
+    /// Recovered signer, for `/exchange` requests with a valid signature
+    pub signer: Option<Address>,
+}
+
+/// An order resting on the mock book
+#[derive(Debug, Clone)]
+pub struct MockOrder {
+    pub user: Address,
+    pub oid: u64,
+    pub asset: u32,
+    pub coin: String,
+    pub is_buy: bool,
+    pub limit_px: Decimal,
+    pub sz: Decimal,
+    pub orig_sz: Decimal,
+    pub reduce_only: bool,
+    pub tif: String,
+    pub cloid: Option<String>,
+    pub timestamp: u64,
+}
+
+/// An open position held by a mock account
+#[derive(Debug, Clone)]
+pub struct MockPosition {
+    pub coin: String,
+    /// Signed size, positive for longs
+    pub szi: Decimal,
+    pub entry_px: Decimal,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PerpAsset {
+    pub name: String,
+    pub sz_decimals: u32,
+    pub max_leverage: u32,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SpotToken {
+    pub name: String,
+    pub sz_decimals: u32,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SpotPair {
+    pub name: String,
+    pub base: u32,
+    pub quote: u32,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SubAccount {
+    pub name: String,
+    pub address: Address,
+    pub master: Address,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Vault {
+    pub name: String,
+    pub description: String,
+    pub address: Address,
+    pub leader: Address,
+    pub allow_deposits: bool,
+    pub always_close_on_withdraw: bool,
+    pub equities: HashMap<Address, Decimal>,
+}
+
+#[derive(Default)]
+pub(crate) struct Account {
+    pub balance: Decimal,
+    pub positions: HashMap<String, MockPosition>,
+    pub leverage: HashMap<String, u32>,
+    pub fills: Vec<Value>,
+    /// Address-based requests used and bought with `reserveRequestWeight`
+    pub requests_used: u64,
+    pub requests_reserved: u64,
+}
+
+/// An event to push to WebSocket subscribers of `channel`
+pub(crate) struct WsEvent {
+    pub channel: String,
+    pub user: Option<Address>,
+    pub data: Value,
+}
+
+pub(crate) struct MockState {
+    pub is_mainnet: bool,
+    pub perps: Vec<PerpAsset>,
+    pub spot_tokens: Vec<SpotToken>,
+    pub spot_pairs: Vec<SpotPair>,
+    pub mids: HashMap<String, Decimal>,
+    /// Size an IOC order can fill at most, by coin
+    pub ioc_liquidity: HashMap<String, Decimal>,
+    pub users: HashSet<Address>,
+    pub agents: HashMap<Address, Address>,
+    pub accounts: HashMap<Address, Account>,
+    pub orders: Vec<MockOrder>,
+    pub history: HashMap<u64, (MockOrder, &'static str, u64)>,
+    pub info_overrides: HashMap<String, Value>,
+    pub rejections: VecDeque<String>,
+    /// HTTP statuses to fail upcoming requests with, by path
+    pub http_failures: HashMap<String, VecDeque<u16>>,
+    pub requests: Vec<RecordedRequest>,
+    pub events: Vec<WsEvent>,
+    pub twaps: HashMap<u64, Address>,
+    pub sub_accounts: Vec<SubAccount>,
+    pub vaults: Vec<Vault>,
+    next_oid: u64,
+    next_tid: u64,
+}
+
+/// Address-based requests allowed before any volume is traded
+const BASE_REQUEST_CAP: u64 = 10_000;
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+fn fmt(value: Decimal) -> String {
+    value.normalize().to_string()
+}
+
+fn side(is_buy: bool) -> &'static str {
+    if is_buy {
+        "B"
+    } else {
+        "A"
+    }
+}
+
+fn vault_details(vault: &Vault, user: Option<Address>) -> Value {
+    let follower = |user: &Address, equity: &Decimal| {
+        json!({
+            "user": user,
+            "vaultEquity": fmt(*equity),
+            "pnl": "0",
+            "allTimePnl": "0",
+            "daysFollowing": 0,
+            "vaultEntryTime": 0,
+            "lockupUntil": 0,
+        })
+    };
+    let total: Decimal = vault.equities.values().sum();
+    let leader_equity = vault
+        .equities
+        .get(&vault.leader)
+        .copied()
+        .unwrap_or_default();
+    let leader_fraction = if total.is_zero() {
+        Decimal::ZERO
+    } else {
+        leader_equity / total
+    };
+    let user_equity = user.and_then(|user| vault.equities.get_key_value(&user));
+
+    json!({
+        "name": vault.name,
+        "vaultAddress": vault.address,
+        "leader": vault.leader,
+        "description": vault.description,
+        "portfolio": [],
+        "apr": 0.0,
+        "followerState": user_equity.map(|(user, equity)| follower(user, equity)),
+        "leaderFraction": fmt(leader_fraction),
+        "leaderCommission": "0.1",
+        "followers": vault
+            .equities
+            .iter()
+            .map(|(user, equity)| follower(user, equity))
+            .collect::<Vec<_>>(),
+        "maxDistributable": "0",
+        "maxWithdrawable": fmt(user_equity.map(|(_, equity)| *equity).unwrap_or_default()),
+        "isClosed": false,
+        "allowDeposits": vault.allow_deposits,
+        "alwaysCloseOnWithdraw": vault.always_close_on_withdraw,
+    })
+}
+
+fn ok_default() -> Value {
+    json!({"status": "ok", "response": {"type": "default"}})
+}
+
+fn ok_statuses(response_type: &str, statuses: Vec<Value>) -> Value {
+    json!({
+        "status": "ok",
+        "response": {"type": response_type, "data": {"statuses": statuses}}
+    })
+}
+
+fn ok_status(response_type: &str, status: Value) -> Value {
+    json!({
+        "status": "ok",
+        "response": {"type": response_type, "data": {"status": status}}
+    })
+}
+
+fn err(message: impl Into<String>) -> Value {
+    json!({"status": "err", "response": message.into()})
+}
+
+fn status_error(message: impl Into<String>) -> Value {
+    json!({"error": message.into()})
+}
+
+impl MockState {
+    pub fn new(is_mainnet: bool) -> Self {
+        Self {
+            is_mainnet,
+            perps: Vec::new(),
+            spot_tokens: Vec::new(),
+            spot_pairs: Vec::new(),
+            mids: HashMap::new(),
+            ioc_liquidity: HashMap::new(),
+            users: HashSet::new(),
+            agents: HashMap::new(),
+            accounts: HashMap::new(),
+            orders: Vec::new(),
+            history: HashMap::new(),
+            info_overrides: HashMap::new(),
+            rejections: VecDeque::new(),
+            http_failures: HashMap::new(),
+            requests: Vec::new(),
+            events: Vec::new(),
+            twaps: HashMap::new(),
+            sub_accounts: Vec::new(),
+            vaults: Vec::new(),
+            next_oid: 1,
+            next_tid: 1,
+        }
+    }
+
+    pub fn coin_for_asset(&self, asset: u32) -> Option<String> {
+        match asset.checked_sub(SPOT_ASSET_OFFSET) {
+            Some(pair) => self
+                .spot_pairs
+                .get(pair as usize)
+                .map(|pair| pair.name.clone()),
+            None => self.perps.get(asset as usize).map(|perp| perp.name.clone()),
+        }
+    }
+
+    /// Index of the spot token `name`, listing it if needed
+    pub fn spot_token(&mut self, name: &str, sz_decimals: u32) -> u32 {
+        if let Some(index) = self.spot_tokens.iter().position(|t| t.name == name) {
+            return index as u32;
+        }
+        self.spot_tokens.push(SpotToken {
+            name: name.to_string(),
+            sz_decimals,
+        });
+        self.spot_tokens.len() as u32 - 1
+    }
+
+    pub fn account(&mut self, user: Address) -> &mut Account {
+        self.accounts.entry(user).or_default()
+    }
+
+    /// Update a mid price, filling any resting orders it crosses
+    pub fn set_mid(&mut self, coin: &str, mid: Decimal) {
+        self.mids.insert(coin.to_string(), mid);
+
+        let (crossed, resting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.orders)
+            .into_iter()
+            .partition(|order| {
+                order.coin == coin
+                    && if order.is_buy {
+                        order.limit_px >= mid
+                    } else {
+                        order.limit_px <= mid
+                    }
+            });
+        self.orders = resting;
+
+        for order in crossed {
+            let px = order.limit_px;
+            self.fill(&order, px, false);
+        }
+    }
+
+    // ==================== /info ====================
+
+    pub fn handle_info(&mut self, request: &Value) -> Option<Value> {
+        let request_type = request.get("type")?.as_str()?;
+        if let Some(response) = self.info_overrides.get(request_type) {
+            return Some(response.clone());
+        }
+
+        let user = request
+            .get("user")
+            .and_then(Value::as_str)
+            .and_then(|user| user.parse::<Address>().ok());
+
+        match request_type {
+            "allMids" => Some(json!(self
+                .mids
+                .iter()
+                .map(|(coin, mid)| (coin.clone(), fmt(*mid)))
+                .collect::<HashMap<_, _>>())),
+            "meta" => Some(json!({
+                "universe": self.perps.iter().map(|perp| json!({
+                    "name": perp.name,
+                    "szDecimals": perp.sz_decimals,
+                    "maxLeverage": perp.max_leverage,
+                    "onlyIsolated": false,
+                })).collect::<Vec<_>>()
+            })),
+            "spotMeta" => Some(json!({
+                "universe": self.spot_pairs.iter().enumerate().map(|(index, pair)| json!({
+                    "name": pair.name,
+                    "tokens": [pair.base, pair.quote],
+                    "index": index,
+                    "isCanonical": true,
+                })).collect::<Vec<_>>(),
+                "tokens": self.spot_tokens.iter().enumerate().map(|(index, token)| json!({
+                    "name": token.name,
+                    "szDecimals": token.sz_decimals,
+                    "weiDecimals": 8,
+                    "index": index,
+                    "tokenId": format!("0x{index:032x}"),
+                    "isCanonical": true,
+                })).collect::<Vec<_>>(),
+            })),
+            "clearinghouseState" => Some(self.clearinghouse_state(user?)),
+            "openOrders" => {
+                let user = user?;
+                Some(json!(self
+                    .orders
+                    .iter()
+                    .filter(|order| order.user == user)
+                    .map(|order| json!({
+                        "coin": order.coin,
+                        "limitPx": fmt(order.limit_px),
+                        "oid": order.oid,
+                        "side": side(order.is_buy),
+                        "sz": fmt(order.sz),
+                        "timestamp": order.timestamp,
+                        "cloid": order.cloid,
+                    }))
+                    .collect::<Vec<_>>()))
+            }
+            "orderStatus" => {
+                let oid = request.get("oid")?.as_u64()?;
+                Some(match self.history.get(&oid) {
+                    Some((order, status, timestamp)) if Some(order.user) == user => {
+                        json!({
+                            "status": "order",
+                            "order": {
+                                "order": order_info(order),
+                                "status": status,
+                                "statusTimestamp": timestamp,
+                            }
+                        })
+                    }
+                    _ => json!({"status": "unknownOid"}),
+                })
+            }
+            "userFills" => {
+                let user = user?;
+                Some(json!(self
+                    .accounts
+                    .get(&user)
+                    .map(|account| account.fills.clone())
+                    .unwrap_or_default()))
+            }
+            "l2Book" => {
+                let coin = request.get("coin")?.as_str()?;
+                Some(self.l2_book(coin))
+            }
+            "vaultDetails" => {
+                let address = request
+                    .get("vaultAddress")
+                    .and_then(Value::as_str)?
+                    .parse::<Address>()
+                    .ok()?;
+                let vault = self.vaults.iter().find(|vault| vault.address == address)?;
+                Some(vault_details(vault, user))
+            }
+            "userVaultEquities" => {
+                let user = user?;
+                Some(json!(self
+                    .vaults
+                    .iter()
+                    .filter_map(|vault| {
+                        let equity = vault.equities.get(&user)?;
+                        Some(json!({
+                            "vaultAddress": vault.address,
+                            "equity": fmt(*equity),
+                        }))
+                    })
+                    .collect::<Vec<_>>()))
+            }
+            "userRateLimit" => {
+                let account = self.account(user?);
+                Some(json!({
+                    "cumVlm": "0.0",
+                    "nRequestsUsed": account.requests_used,
+                    "nRequestsCap": BASE_REQUEST_CAP + account.requests_reserved,
+                }))
+            }
+            "subAccounts" => {
+                let user = user?;
+                let sub_accounts: Vec<_> = self
+                    .sub_accounts
+                    .iter()
+                    .filter(|sub| sub.master == user)
+                    .map(|sub| {
+                        json!({
+                            "name": sub.name,
+                            "subAccountUser": sub.address,
+                            "master": sub.master,
+                            "clearinghouseState": self.clearinghouse_state(sub.address),
+                            "spotState": {"balances": []},
+                        })
+                    })
+                    .collect();
+                // The exchange answers `null` rather than an empty list
+                Some(if sub_accounts.is_empty() {
+                    Value::Null
+                } else {
+                    json!(sub_accounts)
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn clearinghouse_state(&self, user: Address) -> Value {
+        let account = self.accounts.get(&user);
+        let balance = account.map(|a| a.balance).unwrap_or_default();
+
+        let mut total_ntl = Decimal::ZERO;
+        let mut unrealized = Decimal::ZERO;
+        let mut positions = Vec::new();
+        for position in account.iter().flat_map(|a| a.positions.values()) {
+            let mark = self
+                .mids
+                .get(&position.coin)
+                .copied()
+                .unwrap_or(position.entry_px);
+            let value = (position.szi * mark).abs();
+            let pnl = position.szi * (mark - position.entry_px);
+            let max_leverage = self
+                .perps
+                .iter()
+                .find(|perp| perp.name == position.coin)
+                .map(|perp| perp.max_leverage)
+                .unwrap_or(1);
+            let leverage = account
+                .and_then(|a| a.leverage.get(&position.coin))
+                .copied()
+                .unwrap_or(max_leverage);
+            total_ntl += value;
+            unrealized += pnl;
+
+            positions.push(json!({
+                "type": "oneWay",
+                "position": {
+                    "coin": position.coin,
+                    "entryPx": fmt(position.entry_px),
+                    "leverage": {"type": "cross", "value": leverage, "rawUsd": null},
+                    "liquidationPx": null,
+                    "marginUsed": "0",
+                    "positionValue": fmt(value),
+                    "returnOnEquity": "0",
+                    "szi": fmt(position.szi),
+                    "unrealizedPnl": fmt(pnl),
+                    "maxLeverage": max_leverage,
+                    "cumFunding": {"allTime": "0", "sinceOpen": "0", "sinceChange": "0"},
+                }
+            }));
+        }
+
+        let account_value = fmt(balance + unrealized);
+        let summary = json!({
+            "accountValue": account_value,
+            "totalMarginUsed": "0",
+            "totalNtlPos": fmt(total_ntl),
+            "totalRawUsd": account_value,
+        });
+
+        json!({
+            "assetPositions": positions,
+            "crossMarginSummary": summary,
+            "marginSummary": summary,
+            "withdrawable": account_value,
+        })
+    }
+
+    fn l2_book(&self, coin: &str) -> Value {
+        let mut bids: Vec<(Decimal, Decimal, u64)> = Vec::new();
+        let mut asks: Vec<(Decimal, Decimal, u64)> = Vec::new();
+        for order in self.orders.iter().filter(|order| order.coin == coin) {
+            let levels = if order.is_buy { &mut bids } else { &mut asks };
+            match levels.iter_mut().find(|(px, _, _)| *px == order.limit_px) {
+                Some(level) => {
+                    level.1 += order.sz;
+                    level.2 += 1;
+                }
+                None => levels.push((order.limit_px, order.sz, 1)),
+            }
+        }
+        bids.sort_by_key(|level| std::cmp::Reverse(level.0));
+        asks.sort_by_key(|level| level.0);
+
+        let render = |levels: Vec<(Decimal, Decimal, u64)>| {
+            levels
+                .into_iter()
+                .map(|(px, sz, n)| json!({"px": fmt(px), "sz": fmt(sz), "n": n}))
+                .collect::<Vec<_>>()
+        };
+
+        json!({
+            "coin": coin,
+            "time": now_ms(),
+            "levels": [render(bids), render(asks)],
+        })
+    }
+
+    // ==================== /exchange ====================
+
+    /// Handle an `/exchange` payload, returning the response and the recovered signer
+    pub fn handle_exchange(&mut self, payload: &Value) -> (Value, Option<Address>) {
+        let Some(action) = payload.get("action") else {
+            return (err("Missing action"), None);
+        };
+        let nonce = payload
+            .get("nonce")
+            .and_then(Value::as_u64)
+            .unwrap_or_default();
+        let vault_address = payload
+            .get("vaultAddress")
+            .and_then(Value::as_str)
+            .and_then(|vault| vault.parse::<Address>().ok());
+        let Some(signature) = payload.get("signature") else {
+            return (err("Missing signature"), None);
+        };
+
+        let action = verify::unwrap_action(action);
+        let signer = match verify::recover_signer(
+            action,
+            nonce,
+            vault_address,
+            signature,
+            self.is_mainnet,
+        ) {
+            Ok(signer) => signer,
+            Err(e) => return (err(format!("Invalid signature: {e}")), None),
+        };
+
+        let user = if self.users.contains(&signer) {
+            signer
+        } else if let Some(user) = self.agents.get(&signer) {
+            *user
+        } else {
+            return (
+                err(format!("User or API Wallet {signer:#x} does not exist.")),
+                Some(signer),
+            );
+        };
+
+        if let Some(message) = self.rejections.pop_front() {
+            return (err(message), Some(signer));
+        }
+
+        let foreign_sub_account = self
+            .sub_accounts
+            .iter()
+            .any(|sub| Some(sub.address) == vault_address && sub.master != user);
+        if foreign_sub_account {
+            return (
+                err("User is not the master of this sub-account"),
+                Some(signer),
+            );
+        }
+
+        let account = vault_address.unwrap_or(user);
+        let action_type = action.get("type").and_then(Value::as_str).unwrap_or("");
+
+        // Batches count one request per item against the address budget
+        let items = ["orders", "cancels", "modifies"]
+            .iter()
+            .find_map(|key| action.get(*key).and_then(Value::as_array))
+            .map_or(1, |items| items.len() as u64);
+        self.account(account).requests_used += items;
+
+        let response = match action_type {
+            "order" => {
+                let orders = action
+                    .get("orders")
+                    .and_then(Value::as_array)
+                    .cloned()
+                    .unwrap_or_default();
+                let grouping = action.get("grouping").and_then(Value::as_str);
+                let statuses = match (grouping, orders.split_first()) {
+                    // Children wait until the entry fills
+                    (Some("normalTpsl"), Some((entry, children))) => {
+                        let entry = self.place(account, entry, None);
+                        let filled = entry.get("filled").is_some();
+                        let children = children.iter().map(|child| {
+                            if filled {
+                                self.place(account, child, None)
+                            } else {
+                                json!("waitingForFill")
+                            }
+                        });
+                        std::iter::once(entry)
+                            .chain(children.collect::<Vec<_>>())
+                            .collect()
+                    }
+                    _ => orders
+                        .iter()
+                        .map(|order| self.place(account, order, None))
+                        .collect(),
+                };
+                ok_statuses("order", statuses)
+            }
+            "cancel" | "cancelByCloid" => {
+                let statuses = action
+                    .get("cancels")
+                    .and_then(Value::as_array)
+                    .map(|cancels| {
+                        cancels
+                            .iter()
+                            .map(|cancel| self.cancel(account, cancel))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                ok_statuses("cancel", statuses)
+            }
+            "batchModify" => {
+                let statuses = action
+                    .get("modifies")
+                    .and_then(Value::as_array)
+                    .map(|modifies| {
+                        modifies
+                            .iter()
+                            .map(|modify| self.modify(account, modify))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                ok_statuses("order", statuses)
+            }
+            "updateLeverage" => {
+                let asset = action.get("asset").and_then(Value::as_u64).unwrap_or(0);
+                let leverage =
+                    action.get("leverage").and_then(Value::as_u64).unwrap_or(1);
+                match self.coin_for_asset(asset as u32) {
+                    Some(coin) => {
+                        self.account(account).leverage.insert(coin, leverage as u32);
+                        ok_default()
+                    }
+                    None => err(format!("Asset {asset} is out of range.")),
+                }
+            }
+            "twapOrder" => {
+                let asset = action
+                    .pointer("/twap/a")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(u64::MAX);
+                let status = if self.coin_for_asset(asset as u32).is_some() {
+                    // TWAP ids share the order id sequence
+                    let twap_id = self.next_oid;
+                    self.next_oid += 1;
+                    self.twaps.insert(twap_id, account);
+                    json!({"running": {"twapId": twap_id}})
+                } else {
+                    status_error(format!("Asset {asset} is out of range."))
+                };
+                ok_status("twapOrder", status)
+            }
+            "twapCancel" => {
+                let twap_id = action.get("t").and_then(Value::as_u64).unwrap_or(0);
+                let status = match self.twaps.get(&twap_id) {
+                    Some(owner) if *owner == account => {
+                        self.twaps.remove(&twap_id);
+                        json!("success")
+                    }
+                    _ => status_error(
+                        "TWAP was never placed, already canceled, or filled.",
+                    ),
+                };
+                ok_status("twapCancel", status)
+            }
+            "reserveRequestWeight" => {
+                let weight = action.get("weight").and_then(Value::as_u64).unwrap_or(0);
+                self.account(account).requests_reserved += weight;
+                ok_default()
+            }
+            "createSubAccount" => {
+                let name = action.get("name").and_then(Value::as_str).unwrap_or("");
+                if name.is_empty() {
+                    err("Sub-account name must not be empty")
+                } else if self
+                    .sub_accounts
+                    .iter()
+                    .any(|sub| sub.master == user && sub.name == name)
+                {
+                    err(format!("Sub-account name {name} already exists"))
+                } else {
+                    let address = self.new_account_address(user);
+                    self.sub_accounts.push(SubAccount {
+                        name: name.to_string(),
+                        address,
+                        master: user,
+                    });
+                    json!({
+                        "status": "ok",
+                        "response": {"type": "createSubAccount", "data": address},
+                    })
+                }
+            }
+            "createVault" => {
+                let usd = action
+                    .get("initialUsd")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(0);
+                let initial = Decimal::new(usd as i64, 6);
+                if initial < Decimal::ONE_HUNDRED {
+                    err("Initial vault deposit must be at least 100 USDC")
+                } else if self.account(user).balance < initial {
+                    err("Insufficient balance to create vault")
+                } else {
+                    self.account(user).balance -= initial;
+                    let address = self.new_account_address(user);
+                    let text =
+                        |key| action.get(key).and_then(Value::as_str).unwrap_or("");
+                    self.vaults.push(Vault {
+                        name: text("name").to_string(),
+                        description: text("description").to_string(),
+                        address,
+                        leader: user,
+                        allow_deposits: true,
+                        always_close_on_withdraw: false,
+                        equities: HashMap::from([(user, initial)]),
+                    });
+                    json!({
+                        "status": "ok",
+                        "response": {"type": "createVault", "data": address},
+                    })
+                }
+            }
+            "vaultTransfer" => self.vault_transfer(user, action),
+            "vaultModify" | "vaultDistribute" => {
+                let address = action
+                    .get("vaultAddress")
+                    .and_then(Value::as_str)
+                    .and_then(|vault| vault.parse::<Address>().ok());
+                match self
+                    .vaults
+                    .iter_mut()
+                    .find(|vault| Some(vault.address) == address)
+                {
+                    Some(vault) if vault.leader == user => {
+                        if let Some(allow) =
+                            action.get("allowDeposits").and_then(Value::as_bool)
+                        {
+                            vault.allow_deposits = allow;
+                        }
+                        if let Some(close) =
+                            action.get("alwaysCloseOnWithdraw").and_then(Value::as_bool)
+                        {
+                            vault.always_close_on_withdraw = close;
+                        }
+                        ok_default()
+                    }
+                    Some(_) => err("Only the vault leader can do this"),
+                    None => err("Vault not registered"),
+                }
+            }
+            "subAccountTransfer" | "subAccountSpotTransfer" => {
+                let sub_account = action
+                    .get("subAccountUser")
+                    .and_then(Value::as_str)
+                    .and_then(|sub| sub.parse::<Address>().ok());
+                let owned = self
+                    .sub_accounts
+                    .iter()
+                    .any(|sub| Some(sub.address) == sub_account && sub.master == user);
+                match sub_account.filter(|_| owned) {
+                    Some(sub_account) if action_type == "subAccountTransfer" => {
+                        self.sub_account_transfer(user, sub_account, action)
+                    }
+                    Some(_) => ok_default(),
+                    None => err("Invalid sub-account"),
+                }
+            }
+            "approveAgent" => {
+                match action
+                    .get("agentAddress")
+                    .and_then(Value::as_str)
+                    .and_then(|agent| agent.parse::<Address>().ok())
+                {
+                    Some(agent) => {
+                        self.agents.insert(agent, user);
+                        ok_default()
+                    }
+                    None => err("Invalid agent address"),
+                }
+            }
+            _ => ok_default(),
+        };
+
+        (response, Some(signer))
+    }
+
+    // Sub-accounts and vaults share one sequence so their addresses never collide
+    fn new_account_address(&self, user: Address) -> Address {
+        user.create((self.sub_accounts.len() + self.vaults.len()) as u64)
+    }
+
+    fn vault_transfer(&mut self, user: Address, action: &Value) -> Value {
+        let address = action
+            .get("vaultAddress")
+            .and_then(Value::as_str)
+            .and_then(|vault| vault.parse::<Address>().ok());
+        let is_deposit = action
+            .get("isDeposit")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let usd = action.get("usd").and_then(Value::as_u64).unwrap_or(0);
+        let amount = Decimal::new(usd as i64, 6);
+
+        let Some(index) = self
+            .vaults
+            .iter()
+            .position(|vault| Some(vault.address) == address)
+        else {
+            return err("Vault not registered");
+        };
+
+        if is_deposit {
+            let vault = &self.vaults[index];
+            if !vault.allow_deposits && vault.leader != user {
+                return err("Vault does not allow deposits");
+            }
+            if self.account(user).balance < amount {
+                return err("Insufficient balance for vault deposit");
+            }
+            self.account(user).balance -= amount;
+            *self.vaults[index].equities.entry(user).or_default() += amount;
+        } else {
+            let equity = self.vaults[index].equities.entry(user).or_default();
+            if *equity < amount {
+                return err("Insufficient vault equity for withdrawal");
+            }
+            *equity -= amount;
+            self.account(user).balance += amount;
+        }
+        ok_default()
+    }
+
+    fn sub_account_transfer(
+        &mut self,
+        master: Address,
+        sub_account: Address,
+        action: &Value,
+    ) -> Value {
+        let is_deposit = action
+            .get("isDeposit")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let usd = action.get("usd").and_then(Value::as_u64).unwrap_or(0);
+        let amount = Decimal::new(usd as i64, 6);
+
+        let (from, to) = if is_deposit {
+            (master, sub_account)
+        } else {
+            (sub_account, master)
+        };
+        if self.account(from).balance < amount {
+            return err("Insufficient balance for transfer");
+        }
+        self.account(from).balance -= amount;
+        self.account(to).balance += amount;
+        ok_default()
+    }
+
+    fn place(&mut self, user: Address, order: &Value, oid: Option<u64>) -> Value {
+        let asset = order.get("a").and_then(Value::as_u64).unwrap_or(u64::MAX) as u32;
+        let Some(coin) = self.coin_for_asset(asset) else {
+            return status_error(format!("Asset {asset} is out of range."));
+        };
+        let is_buy = order.get("b").and_then(Value::as_bool).unwrap_or(false);
+        let reduce_only = order.get("r").and_then(Value::as_bool).unwrap_or(false);
+        let cloid = order.get("c").and_then(Value::as_str).map(str::to_string);
+
+        let parse = |field: &str| {
+            order
+                .get(field)
+                .and_then(Value::as_str)
+                .and_then(|value| Decimal::from_str(value).ok())
+        };
+        let (Some(limit_px), Some(sz)) = (parse("p"), parse("s")) else {
+            return status_error(format!("Invalid price or size. asset={asset}"));
+        };
+        if sz <= Decimal::ZERO {
+            return status_error("Order has zero size.");
+        }
+
+        let order_type = order.get("t");
+        let is_trigger = order_type.and_then(|t| t.get("trigger")).is_some();
+        let tif = order_type
+            .and_then(|t| t.get("limit"))
+            .and_then(|limit| limit.get("tif"))
+            .and_then(Value::as_str)
+            .unwrap_or("Gtc")
+            .to_string();
+
+        if reduce_only {
+            let szi = self
+                .accounts
+                .get(&user)
+                .and_then(|account| account.positions.get(&coin))
+                .map(|position| position.szi)
+                .unwrap_or_default();
+            let reduces =
+                (is_buy && szi < Decimal::ZERO) || (!is_buy && szi > Decimal::ZERO);
+            if !reduces || sz > szi.abs() {
+                return status_error(format!(
+                    "Reduce only order would increase position. asset={asset}"
+                ));
+            }
+        }
+
+        let mid = self.mids.get(&coin).copied();
+        let crosses = !is_trigger
+            && mid.is_some_and(|mid| {
+                if is_buy {
+                    limit_px >= mid
+                } else {
+                    limit_px <= mid
+                }
+            });
+
+        if crosses && tif == "Alo" {
+            return status_error(format!(
+                "Post only order would have immediately matched, bbo was {}. asset={asset}",
+                fmt(mid.unwrap_or_default())
+            ));
+        }
+        if !crosses && tif == "Ioc" {
+            return status_error(format!(
+                "Order could not immediately match against any resting orders. asset={asset}"
+            ));
+        }
+
+        let oid = oid.unwrap_or_else(|| {
+            let oid = self.next_oid;
+            self.next_oid += 1;
+            oid
+        });
+        let order = MockOrder {
+            user,
+            oid,
+            asset,
+            coin,
+            is_buy,
+            limit_px,
+            sz,
+            orig_sz: sz,
+            reduce_only,
+            tif: if is_trigger {
+                "Trigger".to_string()
+            } else {
+                tif
+            },
+            cloid,
+            timestamp: now_ms(),
+        };
+
+        if crosses {
+            let px = mid.unwrap_or(limit_px);
+            let filled = match self.ioc_liquidity.get(&order.coin) {
+                Some(liquidity) if order.tif == "Ioc" => sz.min(*liquidity),
+                _ => sz,
+            };
+            if filled.is_zero() {
+                return status_error(format!(
+                    "Order could not immediately match against any resting orders. asset={asset}"
+                ));
+            }
+            self.fill(
+                &MockOrder {
+                    sz: filled,
+                    ..order
+                },
+                px,
+                true,
+            );
+            json!({"filled": {"totalSz": fmt(filled), "avgPx": fmt(px), "oid": oid}})
+        } else {
+            self.record_status(&order, "open");
+            let response = match &order.cloid {
+                Some(cloid) => json!({"resting": {"oid": oid, "cloid": cloid}}),
+                None => json!({"resting": {"oid": oid}}),
+            };
+            self.orders.push(order);
+            response
+        }
+    }
+
+    fn cancel(&mut self, user: Address, cancel: &Value) -> Value {
+        let asset = cancel
+            .get("a")
+            .or_else(|| cancel.get("asset"))
+            .and_then(Value::as_u64)
+            .unwrap_or(u64::MAX) as u32;
+        let oid = cancel.get("o").and_then(Value::as_u64);
+        let cloid = cancel.get("cloid").and_then(Value::as_str);
+
+        let position = self.orders.iter().position(|order| {
+            order.user == user
+                && order.asset == asset
+                && match (oid, cloid) {
+                    (Some(oid), _) => order.oid == oid,
+                    (None, Some(cloid)) => order.cloid.as_deref() == Some(cloid),
+                    (None, None) => false,
+                }
+        });
+
+        match position {
+            Some(index) => {
+                let order = self.orders.remove(index);
+                self.record_status(&order, "canceled");
+                json!("success")
+            }
+            None => status_error(format!(
+                "Order was never placed, already canceled, or filled. asset={asset}"
+            )),
+        }
+    }
+
+    fn modify(&mut self, user: Address, modify: &Value) -> Value {
+        let oid = modify.get("oid").and_then(Value::as_u64);
+        let cloid = modify.get("oid").and_then(Value::as_str);
+        let Some(order) = modify.get("order") else {
+            return status_error("Missing order");
+        };
+
+        let position = self.orders.iter().position(|resting| {
+            resting.user == user
+                && match (oid, cloid) {
+                    (Some(oid), _) => resting.oid == oid,
+                    (None, Some(cloid)) => resting.cloid.as_deref() == Some(cloid),
+                    (None, None) => false,
+                }
+        });
+        match position {
+            Some(index) => {
+                let existing = self.orders.remove(index);
+                self.record_status(&existing, "canceled");
+                self.place(user, order, None)
+            }
+            None => status_error("Cannot modify canceled or filled order"),
+        }
+    }
+
+    fn fill(&mut self, order: &MockOrder, px: Decimal, crossed: bool) {
+        let tid = self.next_tid;
+        self.next_tid += 1;
+        let time = now_ms();
+
+        let signed = if order.is_buy { order.sz } else { -order.sz };
+        let account = self.accounts.entry(order.user).or_default();
+        let position = account
+            .positions
+            .entry(order.coin.clone())
+            .or_insert_with(|| MockPosition {
+                coin: order.coin.clone(),
+                szi: Decimal::ZERO,
+                entry_px: px,
+            });
+
+        let start = position.szi;
+        let opening = start.is_zero() || start.is_sign_positive() == order.is_buy;
+        let closed_pnl = if opening {
+            Decimal::ZERO
+        } else {
+            let closed = order.sz.min(start.abs());
+            let direction = if start.is_sign_positive() {
+                Decimal::ONE
+            } else {
+                -Decimal::ONE
+            };
+            (px - position.entry_px) * closed * direction
+        };
+
+        let end = start + signed;
+        if opening {
+            position.entry_px =
+                (position.entry_px * start.abs() + px * order.sz) / end.abs();
+        } else if !end.is_zero() && end.is_sign_positive() != start.is_sign_positive() {
+            position.entry_px = px;
+        }
+        position.szi = end;
+        if end.is_zero() {
+            account.positions.remove(&order.coin);
+        }
+        account.balance += closed_pnl;
+
+        let long = if opening {
+            order.is_buy
+        } else {
+            start.is_sign_positive()
+        };
+        let dir = match (opening, long) {
+            (true, true) => "Open Long",
+            (true, false) => "Open Short",
+            (false, true) => "Close Long",
+            (false, false) => "Close Short",
+        };
+
+        let fill = json!({
+            "coin": order.coin,
+            "px": fmt(px),
+            "sz": fmt(order.sz),
+            "side": side(order.is_buy),
+            "time": time,
+            "startPosition": fmt(start),
+            "dir": dir,
+            "closedPnl": fmt(closed_pnl),
+            "hash": format!("0x{:064x}", tid),
+            "oid": order.oid,
+            "cloid": order.cloid,
+            "crossed": crossed,
+            "fee": "0",
+            "feeToken": "USDC",
+            "tid": tid,
+        });
+        account.fills.push(fill.clone());
+
+        self.record_status(order, "filled");
+        self.events.push(WsEvent {
+            channel: "userFills".to_string(),
+            user: Some(order.user),
+            data: json!({"user": format!("{:#x}", order.user), "fills": [fill]}),
+        });
+    }
+
+    // Remember the order's latest status and notify `orderUpdates` subscribers
+    fn record_status(&mut self, order: &MockOrder, status: &'static str) {
+        let timestamp = now_ms();
+        self.history
+            .insert(order.oid, (order.clone(), status, timestamp));
+
+        let sz = if status == "filled" {
+            Decimal::ZERO
+        } else {
+            order.sz
+        };
+        self.events.push(WsEvent {
+            channel: "orderUpdates".to_string(),
+            user: Some(order.user),
+            data: json!([{
+                "order": {
+                    "coin": order.coin,
+                    "side": side(order.is_buy),
+                    "limitPx": fmt(order.limit_px),
+                    "sz": fmt(sz),
+                    "oid": order.oid,
+                    "timestamp": order.timestamp,
+                    "origSz": fmt(order.orig_sz),
+                    "cloid": order.cloid,
+                },
+                "status": status,
+                "statusTimestamp": timestamp,
+            }]),
+        });
+    }
+}
+
+fn order_info(order: &MockOrder) -> Value {
+    json!({
+        "coin": order.coin,
+        "side": side(order.is_buy),
+        "limitPx": fmt(order.limit_px),
+        "sz": fmt(order.sz),
+        "oid": order.oid,
+        "timestamp": order.timestamp,
+        "triggerCondition": "N/A",
+        "isTrigger": order.tif == "Trigger",
+        "triggerPx": "0.0",
+        "isPositionTpsl": false,
+        "reduceOnly": order.reduce_only,
+        "orderType": if order.tif == "Trigger" { "Trigger" } else { "Limit" },
+        "origSz": fmt(order.orig_sz),
+        "tif": order.tif,
+        "cloid": order.cloid,
+    })
+}
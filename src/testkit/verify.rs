@@ -0,0 +1,270 @@
+//! Signature recovery for `/exchange` payloads
+
+use alloy::primitives::{Address, Signature, B256, U256};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{
+    providers::exchange::hash_l1_action,
+    types::{actions::*, eip712::HyperliquidAction},
+};
+
+/// Strip the `agent` envelope, if any, returning the action that was hashed
+pub(crate) fn unwrap_action(action: &Value) -> &Value {
+    if action.get("type").and_then(Value::as_str) == Some("agent") {
+        if let Some(inner) = action.get("agentAction") {
+            return inner;
+        }
+    }
+    action
+}
+
+/// Recover the address that signed an `/exchange` payload
+pub(crate) fn recover_signer(
+    action: &Value,
+    nonce: u64,
+    vault_address: Option<Address>,
+    signature: &Value,
+    is_mainnet: bool,
+) -> Result<Address, String> {
+    let action_type = action
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or("Missing action type")?;
+
+    let hash = match action_type {
+        "usdSend" | "withdraw3" | "spotSend" | "approveAgent" | "approveBuilderFee"
+        | "tokenDelegate" | "cDeposit" | "cWithdraw" => {
+            user_action_hash(action_type, action)?
+        }
+        _ => {
+            let connection_id = l1_action_hash(action, nonce, vault_address)?;
+            let agent = Agent {
+                source: if is_mainnet { "a" } else { "b" }.to_string(),
+                connection_id,
+            };
+            agent.eip712_signing_hash(&agent.domain())
+        }
+    };
+
+    let r = parse_u256(signature.get("r"))?;
+    let s = parse_u256(signature.get("s"))?;
+    let v = signature
+        .get("v")
+        .and_then(Value::as_u64)
+        .ok_or("Missing signature v")?;
+
+    let signature = Signature::from_rs_and_parity(r, s, v).map_err(|e| e.to_string())?;
+    signature
+        .recover_address_from_prehash(&hash)
+        .map_err(|e| e.to_string())
+}
+
+// Rebuild the typed action and hash it as the client does, so the key order of
+// the received JSON doesn't matter
+fn l1_action_hash(
+    action: &Value,
+    nonce: u64,
+    vault_address: Option<Address>,
+) -> Result<B256, String> {
+    let action_type = action
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or("Missing action type")?;
+
+    match action_type {
+        "order" => typed_hash::<BulkOrder>(action_type, action, nonce, vault_address),
+        "cancel" => typed_hash::<BulkCancel>(action_type, action, nonce, vault_address),
+        "cancelByCloid" => {
+            typed_hash::<BulkCancelCloid>(action_type, action, nonce, vault_address)
+        }
+        "batchModify" => {
+            typed_hash::<BulkModify>(action_type, action, nonce, vault_address)
+        }
+        "updateLeverage" => {
+            typed_hash::<UpdateLeverage>(action_type, action, nonce, vault_address)
+        }
+        "updateIsolatedMargin" => {
+            typed_hash::<UpdateIsolatedMargin>(action_type, action, nonce, vault_address)
+        }
+        "spotUser" => typed_hash::<SpotUser>(action_type, action, nonce, vault_address),
+        "vaultTransfer" => {
+            typed_hash::<VaultTransfer>(action_type, action, nonce, vault_address)
+        }
+        "createVault" => {
+            typed_hash::<CreateVault>(action_type, action, nonce, vault_address)
+        }
+        "vaultModify" => {
+            typed_hash::<VaultModify>(action_type, action, nonce, vault_address)
+        }
+        "vaultDistribute" => {
+            typed_hash::<VaultDistribute>(action_type, action, nonce, vault_address)
+        }
+        "setReferrer" => {
+            typed_hash::<SetReferrer>(action_type, action, nonce, vault_address)
+        }
+        "createSubAccount" => {
+            typed_hash::<CreateSubAccount>(action_type, action, nonce, vault_address)
+        }
+        "subAccountTransfer" => {
+            typed_hash::<SubAccountTransfer>(action_type, action, nonce, vault_address)
+        }
+        "subAccountSpotTransfer" => typed_hash::<SubAccountSpotTransfer>(
+            action_type,
+            action,
+            nonce,
+            vault_address,
+        ),
+        "scheduleCancel" => {
+            typed_hash::<ScheduleCancel>(action_type, action, nonce, vault_address)
+        }
+        "reserveRequestWeight" => {
+            typed_hash::<ReserveRequestWeight>(action_type, action, nonce, vault_address)
+        }
+        "twapOrder" => typed_hash::<TwapOrder>(action_type, action, nonce, vault_address),
+        "twapCancel" => {
+            typed_hash::<TwapCancel>(action_type, action, nonce, vault_address)
+        }
+        other => Err(format!("Unknown L1 action: {other}")),
+    }
+}
+
+fn typed_hash<T: DeserializeOwned + Serialize>(
+    action_type: &str,
+    action: &Value,
+    nonce: u64,
+    vault_address: Option<Address>,
+) -> Result<B256, String> {
+    let typed: T = serde_json::from_value(action.clone())
+        .map_err(|e| format!("Invalid {action_type} action: {e}"))?;
+    hash_l1_action(action_type, &typed, nonce, vault_address).map_err(|e| e.to_string())
+}
+
+fn user_action_hash(action_type: &str, action: &Value) -> Result<B256, String> {
+    let signature_chain_id = {
+        let raw = string_field(action, "signatureChainId")?;
+        u64::from_str_radix(raw.trim_start_matches("0x"), 16)
+            .map_err(|e| format!("Invalid signatureChainId: {e}"))?
+    };
+    let hyperliquid_chain = string_field(action, "hyperliquidChain")?;
+
+    let hash = match action_type {
+        "usdSend" => {
+            let action = UsdSend {
+                signature_chain_id,
+                hyperliquid_chain,
+                destination: string_field(action, "destination")?,
+                amount: string_field(action, "amount")?,
+                time: u64_field(action, "time")?,
+            };
+            action.eip712_signing_hash(&action.domain())
+        }
+        "withdraw3" => {
+            let action = Withdraw {
+                signature_chain_id,
+                hyperliquid_chain,
+                destination: string_field(action, "destination")?,
+                amount: string_field(action, "amount")?,
+                time: u64_field(action, "time")?,
+            };
+            action.eip712_signing_hash(&action.domain())
+        }
+        "spotSend" => {
+            let action = SpotSend {
+                signature_chain_id,
+                hyperliquid_chain,
+                destination: string_field(action, "destination")?,
+                token: string_field(action, "token")?,
+                amount: string_field(action, "amount")?,
+                time: u64_field(action, "time")?,
+            };
+            action.eip712_signing_hash(&action.domain())
+        }
+        "approveAgent" => {
+            let action = ApproveAgent {
+                signature_chain_id,
+                hyperliquid_chain,
+                agent_address: address_field(action, "agentAddress")?,
+                agent_name: action
+                    .get("agentName")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                nonce: u64_field(action, "nonce")?,
+            };
+            action.eip712_signing_hash(&action.domain())
+        }
+        "approveBuilderFee" => {
+            let action = ApproveBuilderFee {
+                signature_chain_id,
+                hyperliquid_chain,
+                max_fee_rate: string_field(action, "maxFeeRate")?,
+                builder: string_field(action, "builder")?,
+                nonce: u64_field(action, "nonce")?,
+            };
+            action.eip712_signing_hash(&action.domain())
+        }
+        "tokenDelegate" => {
+            let action = TokenDelegate {
+                signature_chain_id,
+                hyperliquid_chain,
+                validator: address_field(action, "validator")?,
+                wei: u64_field(action, "wei")?,
+                is_undelegate: action
+                    .get("isUndelegate")
+                    .and_then(Value::as_bool)
+                    .ok_or("Missing field: isUndelegate")?,
+                nonce: u64_field(action, "nonce")?,
+            };
+            action.eip712_signing_hash(&action.domain())
+        }
+        "cDeposit" => {
+            let action = CDeposit {
+                signature_chain_id,
+                hyperliquid_chain,
+                wei: u64_field(action, "wei")?,
+                nonce: u64_field(action, "nonce")?,
+            };
+            action.eip712_signing_hash(&action.domain())
+        }
+        "cWithdraw" => {
+            let action = CWithdraw {
+                signature_chain_id,
+                hyperliquid_chain,
+                wei: u64_field(action, "wei")?,
+                nonce: u64_field(action, "nonce")?,
+            };
+            action.eip712_signing_hash(&action.domain())
+        }
+        other => return Err(format!("Unknown user action: {other}")),
+    };
+
+    Ok(hash)
+}
+
+fn string_field(action: &Value, field: &str) -> Result<String, String> {
+    action
+        .get(field)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| format!("Missing field: {field}"))
+}
+
+fn u64_field(action: &Value, field: &str) -> Result<u64, String> {
+    action
+        .get(field)
+        .and_then(Value::as_u64)
+        .ok_or_else(|| format!("Missing field: {field}"))
+}
+
+fn address_field(action: &Value, field: &str) -> Result<Address, String> {
+    string_field(action, field)?
+        .parse()
+        .map_err(|e| format!("Invalid {field}: {e}"))
+}
+
+fn parse_u256(value: Option<&Value>) -> Result<U256, String> {
+    let raw = value
+        .and_then(Value::as_str)
+        .ok_or("Missing signature component")?;
+    U256::from_str_radix(raw.trim_start_matches("0x"), 16).map_err(|e| e.to_string())
+}
@@ -1,4 +1,5 @@
 use alloy::primitives::Address;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 // ==================== Request Types ====================
@@ -14,7 +15,7 @@ pub struct CandleSnapshotRequest {
 
 // ==================== Common Response Types ====================
 
-// Note: AllMids returns HashMap<String, String> directly, not wrapped
+// Note: AllMids returns HashMap<String, Decimal> directly, not wrapped
 
 // ==================== Position & Margin Types ====================
 
@@ -30,17 +31,17 @@ pub struct AssetPosition {
 pub struct BasicOrderInfo {
     pub coin: String,
     pub side: String,
-    pub limit_px: String,
-    pub sz: String,
+    pub limit_px: Decimal,
+    pub sz: Decimal,
     pub oid: u64,
     pub timestamp: u64,
     pub trigger_condition: String,
     pub is_trigger: bool,
-    pub trigger_px: String,
+    pub trigger_px: Decimal,
     pub is_position_tpsl: bool,
     pub reduce_only: bool,
     pub order_type: String,
-    pub orig_sz: String,
+    pub orig_sz: Decimal,
     pub tif: String,
     pub cloid: Option<String>,
 }
@@ -56,15 +57,15 @@ pub struct CandlesSnapshotResponse {
     #[serde(rename = "i")]
     pub candle_interval: String,
     #[serde(rename = "o")]
-    pub open: String,
+    pub open: Decimal,
     #[serde(rename = "c")]
-    pub close: String,
+    pub close: Decimal,
     #[serde(rename = "h")]
-    pub high: String,
+    pub high: Decimal,
     #[serde(rename = "l")]
-    pub low: String,
+    pub low: Decimal,
     #[serde(rename = "v")]
-    pub vlm: String,
+    pub vlm: Decimal,
     #[serde(rename = "n")]
     pub num_trades: u64,
 }
@@ -72,18 +73,18 @@ pub struct CandlesSnapshotResponse {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CumulativeFunding {
-    pub all_time: String,
-    pub since_open: String,
-    pub since_change: String,
+    pub all_time: Decimal,
+    pub since_open: Decimal,
+    pub since_change: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct DailyUserVlm {
     pub date: String,
-    pub exchange: String,
-    pub user_add: String,
-    pub user_cross: String,
+    pub exchange: Decimal,
+    pub user_add: Decimal,
+    pub user_cross: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -92,17 +93,17 @@ pub struct Delta {
     #[serde(rename = "type")]
     pub type_string: String,
     pub coin: String,
-    pub usdc: String,
-    pub szi: String,
-    pub funding_rate: String,
+    pub usdc: Decimal,
+    pub szi: Decimal,
+    pub funding_rate: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct FeeSchedule {
-    pub add: String,
-    pub cross: String,
-    pub referral_discount: String,
+    pub add: Decimal,
+    pub cross: Decimal,
+    pub referral_discount: Decimal,
     pub tiers: Tiers,
 }
 
@@ -110,8 +111,8 @@ pub struct FeeSchedule {
 #[serde(rename_all = "camelCase")]
 pub struct FundingHistoryResponse {
     pub coin: String,
-    pub funding_rate: String,
-    pub premium: String,
+    pub funding_rate: Decimal,
+    pub premium: Decimal,
     pub time: u64,
 }
 
@@ -127,8 +128,8 @@ pub struct L2SnapshotResponse {
 #[serde(rename_all = "camelCase")]
 pub struct Level {
     pub n: u64,
-    pub px: String,
-    pub sz: String,
+    pub px: Decimal,
+    pub sz: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -137,33 +138,33 @@ pub struct Leverage {
     #[serde(rename = "type")]
     pub type_string: String,
     pub value: u32,
-    pub raw_usd: Option<String>,
+    pub raw_usd: Option<Decimal>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct MarginSummary {
-    pub account_value: String,
-    pub total_margin_used: String,
-    pub total_ntl_pos: String,
-    pub total_raw_usd: String,
+    pub account_value: Decimal,
+    pub total_margin_used: Decimal,
+    pub total_ntl_pos: Decimal,
+    pub total_raw_usd: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Mm {
-    pub add: String,
-    pub maker_fraction_cutoff: String,
+    pub add: Decimal,
+    pub maker_fraction_cutoff: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenOrdersResponse {
     pub coin: String,
-    pub limit_px: String,
+    pub limit_px: Decimal,
     pub oid: u64,
     pub side: String,
-    pub sz: String,
+    pub sz: Decimal,
     pub timestamp: u64,
 }
 
@@ -187,14 +188,14 @@ pub struct OrderStatusResponse {
 #[serde(rename_all = "camelCase")]
 pub struct PositionData {
     pub coin: String,
-    pub entry_px: Option<String>,
+    pub entry_px: Option<Decimal>,
     pub leverage: Leverage,
-    pub liquidation_px: Option<String>,
-    pub margin_used: String,
-    pub position_value: String,
-    pub return_on_equity: String,
-    pub szi: String,
-    pub unrealized_pnl: String,
+    pub liquidation_px: Option<Decimal>,
+    pub margin_used: Decimal,
+    pub position_value: Decimal,
+    pub return_on_equity: Decimal,
+    pub szi: Decimal,
+    pub unrealized_pnl: Decimal,
     pub max_leverage: u32,
     pub cum_funding: CumulativeFunding,
 }
@@ -204,8 +205,8 @@ pub struct PositionData {
 pub struct RecentTradesResponse {
     pub coin: String,
     pub side: String,
-    pub px: String,
-    pub sz: String,
+    pub px: Decimal,
+    pub sz: Decimal,
     pub time: u64,
     pub hash: String,
 }
@@ -221,9 +222,9 @@ pub struct Referrer {
 #[serde(rename_all = "camelCase")]
 pub struct ReferralResponse {
     pub referred_by: Option<Referrer>,
-    pub cum_vlm: String,
-    pub unclaimed_rewards: String,
-    pub claimed_rewards: String,
+    pub cum_vlm: Decimal,
+    pub unclaimed_rewards: Decimal,
+    pub claimed_rewards: Decimal,
     pub referrer_state: ReferrerState,
 }
 
@@ -249,28 +250,28 @@ pub struct Tiers {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserFeesResponse {
-    pub active_referral_discount: String,
+    pub active_referral_discount: Decimal,
     pub daily_user_vlm: Vec<DailyUserVlm>,
     pub fee_schedule: FeeSchedule,
-    pub user_add_rate: String,
-    pub user_cross_rate: String,
+    pub user_add_rate: Decimal,
+    pub user_cross_rate: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserFillsResponse {
-    pub closed_pnl: String,
+    pub closed_pnl: Decimal,
     pub coin: String,
     pub crossed: bool,
     pub dir: String,
     pub hash: String,
     pub oid: u64,
-    pub px: String,
+    pub px: Decimal,
     pub side: String,
-    pub start_position: String,
-    pub sz: String,
+    pub start_position: Decimal,
+    pub sz: Decimal,
     pub time: u64,
-    pub fee: String,
+    pub fee: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -286,16 +287,16 @@ pub struct UserStateResponse {
     pub asset_positions: Vec<AssetPosition>,
     pub cross_margin_summary: MarginSummary,
     pub margin_summary: MarginSummary,
-    pub withdrawable: String,
+    pub withdrawable: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserTokenBalance {
     pub coin: String,
-    pub hold: String,
-    pub total: String,
-    pub entry_ntl: String,
+    pub hold: Decimal,
+    pub total: Decimal,
+    pub entry_ntl: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -303,23 +304,161 @@ pub struct UserTokenBalanceResponse {
     pub balances: Vec<UserTokenBalance>,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccount {
+    pub name: String,
+    pub sub_account_user: Address,
+    pub master: Address,
+    pub clearinghouse_state: UserStateResponse,
+    pub spot_state: UserTokenBalanceResponse,
+}
+
+/// Address-based request budget, which grows with traded volume
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserRateLimit {
+    pub cum_vlm: Decimal,
+    pub n_requests_used: u64,
+    pub n_requests_cap: u64,
+}
+
+impl UserRateLimit {
+    /// Requests left before the address is rate limited
+    pub fn remaining(&self) -> u64 {
+        self.n_requests_cap.saturating_sub(self.n_requests_used)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Vip {
-    pub add: String,
-    pub cross: String,
-    pub ntl_cutoff: String,
+    pub add: Decimal,
+    pub cross: Decimal,
+    pub ntl_cutoff: Decimal,
 }
 
-// ==================== Metadata Types ====================
+// ==================== Vault Types ====================
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDetails {
+    pub name: String,
+    pub vault_address: Address,
+    pub leader: Address,
+    pub description: String,
+    /// Performance by period: `day`, `week`, `month`, `allTime` and their
+    /// `perp` variants
+    pub portfolio: Vec<(String, VaultPortfolio)>,
+    pub apr: Decimal,
+    /// The queried user's stake, if a user was given and follows the vault
+    pub follower_state: Option<VaultFollower>,
+    pub leader_fraction: Decimal,
+    pub leader_commission: Decimal,
+    pub followers: Vec<VaultFollower>,
+    pub max_distributable: Decimal,
+    pub max_withdrawable: Decimal,
+    pub is_closed: bool,
+    pub allow_deposits: bool,
+    #[serde(default)]
+    pub always_close_on_withdraw: bool,
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
+pub struct VaultPortfolio {
+    /// `(timestamp, value)` samples
+    pub account_value_history: Vec<(u64, Decimal)>,
+    pub pnl_history: Vec<(u64, Decimal)>,
+    pub vlm: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultFollower {
+    pub user: Address,
+    pub vault_equity: Decimal,
+    pub pnl: Decimal,
+    pub all_time_pnl: Decimal,
+    pub days_following: u64,
+    pub vault_entry_time: u64,
+    /// Withdrawals are locked until this time (milliseconds since epoch)
+    pub lockup_until: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserVaultEquity {
+    pub vault_address: Address,
+    pub equity: Decimal,
+    #[serde(default)]
+    pub locked_until_timestamp: Option<u64>,
+}
+
+// ==================== Staking Types ====================
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Delegation {
+    pub validator: Address,
+    pub amount: Decimal,
+    /// Undelegating is locked until this time (milliseconds since epoch)
+    pub locked_until_timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegatorSummary {
+    pub delegated: Decimal,
+    pub undelegated: Decimal,
+    pub total_pending_withdrawal: Decimal,
+    pub n_pending_withdrawals: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegatorHistoryEntry {
+    pub time: u64,
+    pub hash: String,
+    pub delta: DelegatorDelta,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum DelegatorDelta {
+    Delegate {
+        validator: Address,
+        amount: Decimal,
+        is_undelegate: bool,
+    },
+    CDeposit {
+        amount: Decimal,
+    },
+    Withdrawal {
+        amount: Decimal,
+        /// `initiated` or `finalized`
+        phase: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegatorReward {
+    pub time: u64,
+    /// `delegation` or `commission`
+    pub source: String,
+    pub total_amount: Decimal,
+}
+
+// ==================== Metadata Types ====================
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct Meta {
     pub universe: Vec<AssetMeta>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AssetMeta {
     pub name: String,
@@ -337,14 +476,14 @@ pub struct AssetMeta {
     pub is_delisted: Option<bool>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SpotMeta {
     pub universe: Vec<SpotPairMeta>,
     pub tokens: Vec<TokenMeta>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SpotPairMeta {
     pub name: String,
@@ -353,7 +492,7 @@ pub struct SpotPairMeta {
     pub is_canonical: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum EvmContract {
     String(String),
@@ -363,7 +502,7 @@ pub enum EvmContract {
     },
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenMeta {
     pub name: String,
@@ -391,13 +530,13 @@ pub struct SpotMetaAndAssetCtxs {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct AssetContext {
-    pub day_ntl_vlm: String,
-    pub funding: String,
-    pub impact_pxs: Vec<String>,
-    pub mark_px: String,
-    pub mid_px: String,
-    pub open_interest: String,
-    pub oracle_px: String,
-    pub premium: String,
-    pub prev_day_px: String,
+    pub day_ntl_vlm: Decimal,
+    pub funding: Decimal,
+    pub impact_pxs: Vec<Decimal>,
+    pub mark_px: Decimal,
+    pub mid_px: Decimal,
+    pub open_interest: Decimal,
+    pub oracle_px: Decimal,
+    pub premium: Decimal,
+    pub prev_day_px: Decimal,
 }
@@ -1,6 +1,7 @@
 pub mod actions;
 pub mod eip712;
 pub mod info_types;
+pub mod precision;
 pub mod requests;
 pub mod responses;
 pub mod symbol;
@@ -11,6 +12,7 @@ pub mod ws;
 pub use actions::*;
 pub use eip712::{encode_value, EncodeEip712, HyperliquidAction};
 pub use info_types::*;
+pub use precision::{AssetPrecision, RoundingMode};
 pub use requests::*;
 pub use responses::*;
 pub use symbol::Symbol;
@@ -0,0 +1,165 @@
+//! Price and size normalization following the exchange's tick and lot rules
+//!
+//! Sizes are rounded to the asset's `szDecimals`. Prices may have at most 5
+//! significant figures and at most `6 - szDecimals` decimals for perps
+//! (`8 - szDecimals` for spot). Integer prices are always accepted.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Maximum significant figures in a price
+pub const MAX_SIG_FIGS: u32 = 5;
+/// Maximum price decimals for perpetuals, before subtracting `szDecimals`
+pub const MAX_PERP_DECIMALS: u32 = 6;
+/// Maximum price decimals for spot, before subtracting `szDecimals`
+pub const MAX_SPOT_DECIMALS: u32 = 8;
+
+/// How values are rounded onto the allowed grid
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Round towards positive infinity
+    Up,
+    /// Round towards zero
+    Down,
+    /// Round half away from zero
+    #[default]
+    Nearest,
+}
+
+impl RoundingMode {
+    fn strategy(self) -> RoundingStrategy {
+        match self {
+            RoundingMode::Up => RoundingStrategy::ToPositiveInfinity,
+            RoundingMode::Down => RoundingStrategy::ToZero,
+            RoundingMode::Nearest => RoundingStrategy::MidpointAwayFromZero,
+        }
+    }
+}
+
+/// Precision rules for a single asset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssetPrecision {
+    pub sz_decimals: u32,
+    pub is_spot: bool,
+}
+
+impl AssetPrecision {
+    pub fn perp(sz_decimals: u32) -> Self {
+        Self {
+            sz_decimals,
+            is_spot: false,
+        }
+    }
+
+    pub fn spot(sz_decimals: u32) -> Self {
+        Self {
+            sz_decimals,
+            is_spot: true,
+        }
+    }
+
+    /// Maximum number of decimals allowed in a price
+    pub fn max_price_decimals(&self) -> u32 {
+        let max = if self.is_spot {
+            MAX_SPOT_DECIMALS
+        } else {
+            MAX_PERP_DECIMALS
+        };
+        max.saturating_sub(self.sz_decimals)
+    }
+
+    /// Snap a price to 5 significant figures and the allowed decimal count
+    pub fn round_price(&self, px: Decimal, mode: RoundingMode) -> Decimal {
+        if px.is_zero() {
+            return px;
+        }
+        // Digits left of the decimal point, negative for leading fractional zeros
+        let int_digits = magnitude(px) + 1;
+        let sig_fig_decimals = (MAX_SIG_FIGS as i64 - int_digits).max(0) as u32;
+        let decimals = sig_fig_decimals.min(self.max_price_decimals());
+
+        px.round_dp_with_strategy(decimals, mode.strategy())
+            .normalize()
+    }
+
+    /// Round a size to `szDecimals`
+    pub fn round_size(&self, sz: Decimal, mode: RoundingMode) -> Decimal {
+        sz.round_dp_with_strategy(self.sz_decimals, mode.strategy())
+            .normalize()
+    }
+}
+
+// floor(log10(|value|)) for a non-zero decimal
+fn magnitude(value: Decimal) -> i64 {
+    let digits = value.mantissa().unsigned_abs().to_string().len() as i64;
+    digits - 1 - value.scale() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn test_price_significant_figures() {
+        let btc = AssetPrecision::perp(5);
+        assert_eq!(
+            btc.round_price(dec!(50123.7), RoundingMode::Nearest),
+            dec!(50124)
+        );
+        assert_eq!(
+            btc.round_price(dec!(50123.7), RoundingMode::Down),
+            dec!(50123)
+        );
+        assert_eq!(
+            btc.round_price(dec!(123456.7), RoundingMode::Nearest),
+            dec!(123457)
+        );
+        assert_eq!(
+            btc.round_price(dec!(1234.56), RoundingMode::Up),
+            dec!(1234.6)
+        );
+        // Only one decimal is allowed for szDecimals = 5
+        assert_eq!(
+            btc.round_price(dec!(1.23456), RoundingMode::Nearest),
+            dec!(1.2)
+        );
+
+        let small = AssetPrecision::perp(0);
+        let px = small.round_price(dec!(0.000123456), RoundingMode::Nearest);
+        assert_eq!(px.to_string(), "0.000123");
+        assert_eq!(
+            small.round_price(dec!(0.0123456), RoundingMode::Down),
+            dec!(0.012345)
+        );
+        assert_eq!(
+            small.round_price(dec!(0.0123456), RoundingMode::Up),
+            dec!(0.012346)
+        );
+    }
+
+    #[test]
+    fn test_spot_allows_more_decimals() {
+        let purr = AssetPrecision::spot(0);
+        assert_eq!(purr.max_price_decimals(), 8);
+        let px = purr.round_price(dec!(0.0000123456), RoundingMode::Nearest);
+        assert_eq!(px.to_string(), "0.00001235");
+        assert_eq!(AssetPrecision::spot(10).max_price_decimals(), 0);
+    }
+
+    #[test]
+    fn test_size_rounding() {
+        let eth = AssetPrecision::perp(4);
+        assert_eq!(
+            eth.round_size(dec!(0.123456), RoundingMode::Nearest),
+            dec!(0.1235)
+        );
+        assert_eq!(
+            eth.round_size(dec!(0.123456), RoundingMode::Down),
+            dec!(0.1234)
+        );
+        // Trailing zeros are dropped
+        let sz = eth.round_size(dec!(2.50000), RoundingMode::Up);
+        assert_eq!(sz.to_string(), "2.5");
+    }
+}
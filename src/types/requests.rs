@@ -1,6 +1,20 @@
-use serde::{Deserialize, Serialize};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize, Serializer};
 use uuid::Uuid;
 
+use crate::types::precision::{AssetPrecision, RoundingMode};
+
+/// Serialize a decimal without trailing zeros, as the exchange hashes it
+///
+/// The L1 signature covers the msgpack encoding of the action, so `"1.0"` and
+/// `"1"` produce different hashes and only the latter matches the server.
+fn serialize_canonical<S: Serializer>(
+    value: &Decimal,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.normalize().to_string())
+}
+
 // ==================== Order Types ====================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,10 +24,10 @@ pub struct OrderRequest {
     pub asset: u32,
     #[serde(rename = "b")]
     pub is_buy: bool,
-    #[serde(rename = "p")]
-    pub limit_px: String,
-    #[serde(rename = "s")]
-    pub sz: String,
+    #[serde(rename = "p", serialize_with = "serialize_canonical")]
+    pub limit_px: Decimal,
+    #[serde(rename = "s", serialize_with = "serialize_canonical")]
+    pub sz: Decimal,
     #[serde(rename = "r", default)]
     pub reduce_only: bool,
     #[serde(rename = "t")]
@@ -37,13 +51,26 @@ pub struct Limit {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Trigger {
-    #[serde(rename = "triggerPx")]
-    pub trigger_px: String,
+    #[serde(rename = "triggerPx", serialize_with = "serialize_canonical")]
+    pub trigger_px: Decimal,
     #[serde(rename = "isMarket")]
     pub is_market: bool,
     pub tpsl: String, // "tp" or "sl"
 }
 
+/// How the orders of one `order` action relate to each other
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderGrouping {
+    /// Independent orders
+    #[default]
+    Na,
+    /// An entry order followed by TP/SL triggers that activate once it fills
+    NormalTpsl,
+    /// TP/SL triggers attached to the open position, resized as it changes
+    PositionTpsl,
+}
+
 // ==================== Cancel Types ====================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,12 +89,93 @@ pub struct CancelRequestCloid {
 
 // ==================== Modify Types ====================
 
+/// An order referenced by its exchange ID or client order ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "RawOrderId", try_from = "RawOrderId")]
+pub enum OrderId {
+    Oid(u64),
+    Cloid(Uuid),
+}
+
+// Wire form: a number for an oid, a hex string for a cloid
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawOrderId {
+    Oid(u64),
+    Cloid(String),
+}
+
+impl From<OrderId> for RawOrderId {
+    fn from(id: OrderId) -> Self {
+        match id {
+            OrderId::Oid(oid) => RawOrderId::Oid(oid),
+            OrderId::Cloid(cloid) => {
+                RawOrderId::Cloid(format!("{:032x}", cloid.as_u128()))
+            }
+        }
+    }
+}
+
+impl TryFrom<RawOrderId> for OrderId {
+    type Error = uuid::Error;
+
+    fn try_from(id: RawOrderId) -> Result<Self, Self::Error> {
+        match id {
+            RawOrderId::Oid(oid) => Ok(OrderId::Oid(oid)),
+            RawOrderId::Cloid(cloid) => {
+                Uuid::parse_str(cloid.trim_start_matches("0x")).map(OrderId::Cloid)
+            }
+        }
+    }
+}
+
+impl From<u64> for OrderId {
+    fn from(oid: u64) -> Self {
+        OrderId::Oid(oid)
+    }
+}
+
+impl From<Uuid> for OrderId {
+    fn from(cloid: Uuid) -> Self {
+        OrderId::Cloid(cloid)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModifyRequest {
-    pub oid: u64,
+    pub oid: OrderId,
     pub order: OrderRequest,
 }
 
+impl ModifyRequest {
+    pub fn new(oid: impl Into<OrderId>, order: OrderRequest) -> Self {
+        Self {
+            oid: oid.into(),
+            order,
+        }
+    }
+}
+
+// ==================== TWAP Types ====================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwapRequest {
+    #[serde(rename = "a")]
+    pub asset: u32,
+    #[serde(rename = "b")]
+    pub is_buy: bool,
+    #[serde(rename = "s", serialize_with = "serialize_canonical")]
+    pub sz: Decimal,
+    #[serde(rename = "r")]
+    pub reduce_only: bool,
+    /// Duration in minutes
+    #[serde(rename = "m")]
+    pub minutes: u32,
+    /// Randomize slice timing
+    #[serde(rename = "t")]
+    pub randomize: bool,
+}
+
 // ==================== Builder Types ====================
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -86,15 +194,15 @@ impl OrderRequest {
     pub fn limit(
         asset: u32,
         is_buy: bool,
-        limit_px: impl Into<String>,
-        sz: impl Into<String>,
+        limit_px: Decimal,
+        sz: Decimal,
         tif: impl Into<String>,
     ) -> Self {
         Self {
             asset,
             is_buy,
-            limit_px: limit_px.into(),
-            sz: sz.into(),
+            limit_px,
+            sz,
             reduce_only: false,
             order_type: OrderType::Limit(Limit { tif: tif.into() }),
             cloid: None,
@@ -105,19 +213,19 @@ impl OrderRequest {
     pub fn trigger(
         asset: u32,
         is_buy: bool,
-        trigger_px: impl Into<String>,
-        sz: impl Into<String>,
+        trigger_px: Decimal,
+        sz: Decimal,
         tpsl: impl Into<String>,
         is_market: bool,
     ) -> Self {
         Self {
             asset,
             is_buy,
-            limit_px: "0".to_string(), // Triggers don't use limit_px
-            sz: sz.into(),
+            limit_px: Decimal::ZERO, // Triggers don't use limit_px
+            sz,
             reduce_only: false,
             order_type: OrderType::Trigger(Trigger {
-                trigger_px: trigger_px.into(),
+                trigger_px,
                 is_market,
                 tpsl: tpsl.into(),
             }),
@@ -125,6 +233,50 @@ impl OrderRequest {
         }
     }
 
+    /// Create a limit order with price and size snapped to the asset's precision
+    pub fn limit_normalized(
+        asset: u32,
+        is_buy: bool,
+        limit_px: Decimal,
+        sz: Decimal,
+        tif: impl Into<String>,
+        precision: AssetPrecision,
+        rounding: RoundingMode,
+    ) -> Self {
+        Self::limit(asset, is_buy, limit_px, sz, tif).normalize(precision, rounding)
+    }
+
+    /// Create a trigger order with trigger price and size snapped to the
+    /// asset's precision
+    #[allow(clippy::too_many_arguments)]
+    pub fn trigger_normalized(
+        asset: u32,
+        is_buy: bool,
+        trigger_px: Decimal,
+        sz: Decimal,
+        tpsl: impl Into<String>,
+        is_market: bool,
+        precision: AssetPrecision,
+        rounding: RoundingMode,
+    ) -> Self {
+        Self::trigger(asset, is_buy, trigger_px, sz, tpsl, is_market)
+            .normalize(precision, rounding)
+    }
+
+    /// Snap the limit price, trigger price and size to the asset's precision
+    pub fn normalize(
+        mut self,
+        precision: AssetPrecision,
+        rounding: RoundingMode,
+    ) -> Self {
+        self.limit_px = precision.round_price(self.limit_px, rounding);
+        self.sz = precision.round_size(self.sz, rounding);
+        if let OrderType::Trigger(trigger) = &mut self.order_type {
+            trigger.trigger_px = precision.round_price(trigger.trigger_px, rounding);
+        }
+        self
+    }
+
     /// Set client order ID
     pub fn with_cloid(mut self, cloid: Option<Uuid>) -> Self {
         self.cloid = cloid.map(|id| format!("{:032x}", id.as_u128()));
@@ -153,3 +305,39 @@ impl CancelRequestCloid {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn test_decimals_serialize_without_trailing_zeros() {
+        let order =
+            OrderRequest::trigger(0, false, dec!(45000.0), dec!(0.0100), "sl", true);
+        let json = serde_json::to_value(&order).unwrap();
+
+        assert_eq!(json["p"], "0");
+        assert_eq!(json["s"], "0.01");
+        assert_eq!(json["t"]["trigger"]["triggerPx"], "45000");
+    }
+
+    #[test]
+    fn test_modify_by_oid_or_cloid() {
+        let order = OrderRequest::limit(0, true, dec!(50000), dec!(0.01), "Gtc");
+        let by_oid =
+            serde_json::to_value(ModifyRequest::new(7u64, order.clone())).unwrap();
+        assert_eq!(by_oid["oid"], 7);
+
+        let cloid = Uuid::from_u128(0xabc);
+        let by_cloid = serde_json::to_value(ModifyRequest::new(cloid, order)).unwrap();
+        assert_eq!(by_cloid["oid"], "00000000000000000000000000000abc");
+
+        let parsed: OrderId =
+            serde_json::from_str("\"0x00000000000000000000000000000abc\"").unwrap();
+        assert_eq!(parsed, OrderId::Cloid(cloid));
+        let parsed: OrderId = serde_json::from_str("7").unwrap();
+        assert_eq!(parsed, OrderId::Oid(7));
+    }
+}
@@ -1,3 +1,5 @@
+use alloy::primitives::Address;
+use rust_decimal::Decimal;
 use serde::Deserialize;
 
 // ==================== Order Status Types ====================
@@ -10,8 +12,8 @@ pub struct RestingOrder {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FilledOrder {
-    pub total_sz: String,
-    pub avg_px: String,
+    pub total_sz: Decimal,
+    pub avg_px: Decimal,
     pub oid: u64,
 }
 
@@ -26,11 +28,159 @@ pub enum ExchangeDataStatus {
     Filled(FilledOrder),
 }
 
+// ==================== Rejection Reasons ====================
+
+/// Known reasons the exchange rejects an order or action
+///
+/// Parsed from the error text of [`ExchangeDataStatus::Error`] and
+/// [`ExchangeResponseStatus::Err`]. Messages that don't match a known
+/// rejection are kept verbatim in [`ExchangeRejectReason::Other`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExchangeRejectReason {
+    /// Not enough margin to place the order
+    InsufficientMargin,
+    /// Not enough spot balance to place the order or transfer
+    InsufficientSpotBalance,
+    /// Price is not a multiple of the asset's tick size
+    TickSize,
+    /// Size is zero or has too many decimals
+    InvalidSize,
+    /// Order value is below the minimum notional
+    MinNotional,
+    /// Price is too far from the oracle or reference price
+    PriceTooFar,
+    /// Reduce-only order would increase the position
+    ReduceOnlyViolation,
+    /// Post-only (ALO) order would have crossed the book
+    PostOnlyWouldCross,
+    /// IOC order found nothing to match against
+    IocNoMatch,
+    /// Order would exceed the asset's open interest cap
+    OpenInterestCap,
+    /// Too many open orders for the account
+    TooManyOpenOrders,
+    /// Request or order rate limit exceeded
+    RateLimited,
+    /// Nonce was already used or fell outside the allowed window; the action
+    /// must be signed again with a fresh nonce
+    NonceTooLow,
+    /// Asset index or coin does not exist
+    UnknownAsset,
+    /// Signer is neither a user nor an approved agent
+    UnknownSigner,
+    /// Order to cancel or modify was never placed, already canceled or filled
+    OrderNotFound,
+    /// Any other rejection, with the exchange's message
+    Other(String),
+}
+
+impl ExchangeRejectReason {
+    /// Classify an error message returned by the exchange
+    pub fn parse(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let has = |pattern: &str| lower.contains(pattern);
+
+        if has("insufficient margin") {
+            Self::InsufficientMargin
+        } else if has("insufficient spot balance") || has("insufficient balance") {
+            Self::InsufficientSpotBalance
+        } else if has("tick size") {
+            Self::TickSize
+        } else if has("minimum value") {
+            Self::MinNotional
+        } else if has("invalid size") {
+            Self::InvalidSize
+        } else if has("away from the reference price") || has("away from the oracle") {
+            Self::PriceTooFar
+        } else if has("reduce only order would increase position") {
+            Self::ReduceOnlyViolation
+        } else if has("post only order would have immediately matched") {
+            Self::PostOnlyWouldCross
+        } else if has("could not immediately match") {
+            Self::IocNoMatch
+        } else if has("open interest") {
+            Self::OpenInterestCap
+        } else if has("too many open orders") {
+            Self::TooManyOpenOrders
+        } else if has("too many") || has("rate limit") {
+            Self::RateLimited
+        } else if has("invalid nonce") || has("duplicate nonce") || has("nonce too low") {
+            Self::NonceTooLow
+        } else if has("user or api wallet") {
+            Self::UnknownSigner
+        } else if has("asset") && (has("does not exist") || has("unknown")) {
+            Self::UnknownAsset
+        } else if has("never placed") || has("cannot modify canceled or filled") {
+            Self::OrderNotFound
+        } else {
+            Self::Other(message.to_string())
+        }
+    }
+
+    /// Whether resending the same request later may succeed
+    ///
+    /// [`Self::NonceTooLow`] is not retryable: the signed request will always
+    /// be rejected again, so it has to be rebuilt with a fresh nonce instead.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::RateLimited)
+    }
+}
+
 // ==================== Exchange Response Types ====================
 
 #[derive(Debug, Clone, Deserialize)]
+#[serde(from = "ExchangeData")]
 pub struct ExchangeDataStatuses {
     pub statuses: Vec<ExchangeDataStatus>,
+    /// Set instead of `statuses` by `twapOrder` and `twapCancel`
+    pub status: Option<TwapStatus>,
+    /// Set instead of `statuses` by `createSubAccount` and `createVault`, the
+    /// new account's address
+    pub address: Option<Address>,
+}
+
+// `data` is an object for most actions but a bare address for account creation
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExchangeData {
+    Address(Address),
+    Statuses {
+        #[serde(default)]
+        statuses: Vec<ExchangeDataStatus>,
+        #[serde(default)]
+        status: Option<TwapStatus>,
+    },
+}
+
+impl From<ExchangeData> for ExchangeDataStatuses {
+    fn from(data: ExchangeData) -> Self {
+        match data {
+            ExchangeData::Address(address) => Self {
+                statuses: Vec::new(),
+                status: None,
+                address: Some(address),
+            },
+            ExchangeData::Statuses { statuses, status } => Self {
+                statuses,
+                status,
+                address: None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TwapStatus {
+    Success,
+    Running(RunningTwap),
+    Error(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningTwap {
+    pub twap_id: u64,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -64,6 +214,11 @@ impl ExchangeResponseStatus {
         }
     }
 
+    /// Classify the error message, if this was an error response
+    pub fn reject_reason(&self) -> Option<ExchangeRejectReason> {
+        self.error().map(ExchangeRejectReason::parse)
+    }
+
     /// Get the inner response if successful
     pub fn into_result(self) -> Result<ExchangeResponse, String> {
         match self {
@@ -73,12 +228,30 @@ impl ExchangeResponseStatus {
     }
 }
 
+impl TwapStatus {
+    /// Get the TWAP ID of a started TWAP
+    pub fn twap_id(&self) -> Option<u64> {
+        match self {
+            Self::Running(twap) => Some(twap.twap_id),
+            _ => None,
+        }
+    }
+}
+
 impl ExchangeDataStatus {
     /// Check if this status represents a successful order
     pub fn is_success(&self) -> bool {
         matches!(self, Self::Success | Self::Resting(_) | Self::Filled(_))
     }
 
+    /// Classify the rejection, if this status is an error
+    pub fn reject_reason(&self) -> Option<ExchangeRejectReason> {
+        match self {
+            Self::Error(message) => Some(ExchangeRejectReason::parse(message)),
+            _ => None,
+        }
+    }
+
     /// Get order ID if available
     pub fn order_id(&self) -> Option<u64> {
         match self {
@@ -88,3 +261,93 @@ impl ExchangeDataStatus {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reject_reasons() {
+        let cases = [
+            (
+                "Insufficient margin to place order. asset=0",
+                ExchangeRejectReason::InsufficientMargin,
+            ),
+            (
+                "Price must be divisible by tick size. asset=0",
+                ExchangeRejectReason::TickSize,
+            ),
+            (
+                "Order must have minimum value of $10. asset=0",
+                ExchangeRejectReason::MinNotional,
+            ),
+            (
+                "Reduce only order would increase position. asset=0",
+                ExchangeRejectReason::ReduceOnlyViolation,
+            ),
+            (
+                "Post only order would have immediately matched, bbo was 50000. asset=0",
+                ExchangeRejectReason::PostOnlyWouldCross,
+            ),
+            (
+                "Too many cumulative requests sent (10000) for cumulative volume traded",
+                ExchangeRejectReason::RateLimited,
+            ),
+            ("Invalid nonce: duplicate nonce", ExchangeRejectReason::NonceTooLow),
+            (
+                "User or API Wallet 0x0000000000000000000000000000000000000001 does not exist.",
+                ExchangeRejectReason::UnknownSigner,
+            ),
+            (
+                "Order was never placed, already canceled, or filled. asset=0",
+                ExchangeRejectReason::OrderNotFound,
+            ),
+        ];
+
+        for (message, reason) in cases {
+            assert_eq!(ExchangeRejectReason::parse(message), reason, "{message}");
+        }
+        for message in ["Vault not registered", "Nonce field missing from vault"] {
+            assert_eq!(
+                ExchangeRejectReason::parse(message),
+                ExchangeRejectReason::Other(message.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_reject_reason_from_status() {
+        let status: ExchangeDataStatus =
+            serde_json::from_str(r#"{"error":"Insufficient margin to place order."}"#)
+                .unwrap();
+        assert_eq!(
+            status.reject_reason(),
+            Some(ExchangeRejectReason::InsufficientMargin)
+        );
+        assert!(ExchangeDataStatus::Success.reject_reason().is_none());
+        assert!(ExchangeRejectReason::RateLimited.is_retryable());
+        assert!(!ExchangeRejectReason::NonceTooLow.is_retryable());
+    }
+
+    #[test]
+    fn test_parse_response_data() {
+        let created: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"createSubAccount","data":"0x0000000000000000000000000000000000000abc"}}"#,
+        )
+        .unwrap();
+        let data = created.into_result().unwrap().data.unwrap();
+        assert!(data.statuses.is_empty());
+        let address: Address = "0x0000000000000000000000000000000000000abc"
+            .parse()
+            .unwrap();
+        assert_eq!(data.address, Some(address));
+
+        let order: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[{"resting":{"oid":7}}]}}}"#,
+        )
+        .unwrap();
+        let data = order.into_result().unwrap().data.unwrap();
+        assert_eq!(data.statuses[0].order_id(), Some(7));
+        assert!(data.address.is_none());
+    }
+}
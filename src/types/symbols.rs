@@ -2,6 +2,9 @@
 //!
 //! For perpetuals, use the coin name directly (e.g., `BTC`, `ETH`)
 //! For spot pairs, use the Hyperliquid notation (e.g., `@0` for PURR/USDC)
+//!
+//! The indices in these docs are a snapshot and go stale as assets are listed;
+//! use `AssetRegistry` to resolve a symbol to its current asset index.
 
 use crate::types::symbol::Symbol;
 
@@ -3,6 +3,7 @@
 use std::collections::HashMap;
 
 use alloy::primitives::Address;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 // Subscription types
@@ -20,6 +21,55 @@ pub enum Subscription {
     UserFills { user: Address },
     UserFundings { user: Address },
     UserNonFundingLedgerUpdates { user: Address },
+    UserTwapSliceFills { user: Address },
+    UserTwapHistory { user: Address },
+}
+
+impl Subscription {
+    /// Check whether an incoming message was produced by this subscription
+    ///
+    /// Matches on the channel and, where the message carries them, on the
+    /// coin, candle interval or user. Channels whose payload does not identify
+    /// the subscriber (e.g. `orderUpdates`, `notification`) match on channel alone.
+    pub fn matches(&self, message: &Message) -> bool {
+        match (self, message) {
+            (Subscription::AllMids, Message::AllMids(_)) => true,
+            (Subscription::L2Book { coin }, Message::L2Book(book)) => {
+                book.data.coin == *coin
+            }
+            (Subscription::Trades { coin }, Message::Trades(trades)) => {
+                trades.data.first().is_some_and(|trade| trade.coin == *coin)
+            }
+            (Subscription::Candle { coin, interval }, Message::Candle(candle)) => {
+                candle.data.coin == *coin && candle.data.interval == *interval
+            }
+            (Subscription::OrderUpdates { .. }, Message::OrderUpdates(_)) => true,
+            (Subscription::UserEvents { .. }, Message::User(_)) => true,
+            (Subscription::UserFills { user }, Message::UserFills(fills)) => {
+                fills.data.user == *user
+            }
+            (Subscription::UserFundings { user }, Message::UserFundings(fundings)) => {
+                fundings.data.user == *user
+            }
+            (
+                Subscription::UserNonFundingLedgerUpdates { user },
+                Message::UserNonFundingLedgerUpdates(updates),
+            ) => updates.data.user == *user,
+            (
+                Subscription::UserTwapSliceFills { user },
+                Message::UserTwapSliceFills(fills),
+            ) => fills.data.user == *user,
+            (
+                Subscription::UserTwapHistory { user },
+                Message::UserTwapHistory(history),
+            ) => history.data.user == *user,
+            (Subscription::Notification { .. }, Message::Notification(_)) => true,
+            (Subscription::WebData2 { user }, Message::WebData2(data)) => {
+                data.data.user == *user
+            }
+            _ => false,
+        }
+    }
 }
 
 // Incoming message types
@@ -34,9 +84,12 @@ pub enum Message {
     UserFills(UserFills),
     UserFundings(UserFundings),
     UserNonFundingLedgerUpdates(UserNonFundingLedgerUpdates),
+    UserTwapSliceFills(UserTwapSliceFills),
+    UserTwapHistory(UserTwapHistory),
     Notification(Notification),
     WebData2(WebData2),
     User(User),
+    Post(Post),
     SubscriptionResponse,
     Pong,
 }
@@ -49,7 +102,7 @@ pub struct AllMids {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct AllMidsData {
-    pub mids: HashMap<String, String>,
+    pub mids: HashMap<String, Decimal>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -61,8 +114,8 @@ pub struct Trades {
 pub struct Trade {
     pub coin: String,
     pub side: String,
-    pub px: String,
-    pub sz: String,
+    pub px: Decimal,
+    pub sz: Decimal,
     pub time: u64,
     pub hash: String,
     pub tid: u64,
@@ -82,8 +135,8 @@ pub struct L2BookData {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct BookLevel {
-    pub px: String,
-    pub sz: String,
+    pub px: Decimal,
+    pub sz: Decimal,
     pub n: u64,
 }
 
@@ -97,23 +150,23 @@ pub struct CandleData {
     #[serde(rename = "T")]
     pub time_close: u64,
     #[serde(rename = "c")]
-    pub close: String,
+    pub close: Decimal,
     #[serde(rename = "h")]
-    pub high: String,
+    pub high: Decimal,
     #[serde(rename = "i")]
     pub interval: String,
     #[serde(rename = "l")]
-    pub low: String,
+    pub low: Decimal,
     #[serde(rename = "n")]
     pub num_trades: u64,
     #[serde(rename = "o")]
-    pub open: String,
+    pub open: Decimal,
     #[serde(rename = "s")]
     pub coin: String,
     #[serde(rename = "t")]
     pub time_open: u64,
     #[serde(rename = "v")]
-    pub volume: String,
+    pub volume: Decimal,
 }
 
 // User event structures
@@ -135,11 +188,11 @@ pub struct OrderUpdate {
 pub struct BasicOrder {
     pub coin: String,
     pub side: String,
-    pub limit_px: String,
-    pub sz: String,
+    pub limit_px: Decimal,
+    pub sz: Decimal,
     pub oid: u64,
     pub timestamp: u64,
-    pub orig_sz: String,
+    pub orig_sz: Decimal,
     pub cloid: Option<String>,
 }
 
@@ -161,17 +214,17 @@ pub struct UserFillsData {
 pub struct TradeInfo {
     pub coin: String,
     pub side: String,
-    pub px: String,
-    pub sz: String,
+    pub px: Decimal,
+    pub sz: Decimal,
     pub time: u64,
     pub hash: String,
-    pub start_position: String,
+    pub start_position: Decimal,
     pub dir: String,
-    pub closed_pnl: String,
+    pub closed_pnl: Decimal,
     pub oid: u64,
     pub cloid: Option<String>,
     pub crossed: bool,
-    pub fee: String,
+    pub fee: Decimal,
     pub fee_token: String,
     pub tid: u64,
 }
@@ -194,9 +247,9 @@ pub struct UserFundingsData {
 pub struct UserFunding {
     pub time: u64,
     pub coin: String,
-    pub usdc: String,
-    pub szi: String,
-    pub funding_rate: String,
+    pub usdc: Decimal,
+    pub szi: Decimal,
+    pub funding_rate: Decimal,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -224,33 +277,97 @@ pub struct LedgerUpdateData {
 #[serde(tag = "type")]
 pub enum LedgerUpdate {
     Deposit {
-        usdc: String,
+        usdc: Decimal,
     },
     Withdraw {
-        usdc: String,
+        usdc: Decimal,
         nonce: u64,
-        fee: String,
+        fee: Decimal,
     },
     InternalTransfer {
-        usdc: String,
+        usdc: Decimal,
         user: Address,
         destination: Address,
-        fee: String,
+        fee: Decimal,
     },
     SubAccountTransfer {
-        usdc: String,
+        usdc: Decimal,
         user: Address,
         destination: Address,
     },
     SpotTransfer {
         token: String,
-        amount: String,
+        amount: Decimal,
         user: Address,
         destination: Address,
-        fee: String,
+        fee: Decimal,
     },
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserTwapSliceFills {
+    pub data: UserTwapSliceFillsData,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTwapSliceFillsData {
+    pub is_snapshot: Option<bool>,
+    pub user: Address,
+    pub twap_slice_fills: Vec<TwapSliceFill>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapSliceFill {
+    pub fill: TradeInfo,
+    pub twap_id: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserTwapHistory {
+    pub data: UserTwapHistoryData,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTwapHistoryData {
+    pub is_snapshot: Option<bool>,
+    pub user: Address,
+    pub history: Vec<TwapHistory>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapHistory {
+    pub time: u64,
+    pub state: TwapState,
+    pub status: TwapHistoryStatus,
+    pub twap_id: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapState {
+    pub coin: String,
+    pub user: Address,
+    pub side: String,
+    pub sz: Decimal,
+    pub executed_sz: Decimal,
+    pub executed_ntl: Decimal,
+    pub minutes: u32,
+    pub reduce_only: bool,
+    pub randomize: bool,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TwapHistoryStatus {
+    /// "activated", "terminated", "finished" or "error"
+    pub status: String,
+    pub description: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Notification {
     pub data: NotificationData,
@@ -285,12 +402,45 @@ pub enum UserData {
     Funding(UserFunding),
 }
 
+// Responses to post requests
+#[derive(Debug, Clone, Deserialize)]
+pub struct Post {
+    pub data: PostData,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PostData {
+    pub id: u64,
+    pub response: PostResponse,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", content = "payload", rename_all = "camelCase")]
+pub enum PostResponse {
+    /// `{"type": <info request type>, "data": <info response>}`
+    Info(serde_json::Value),
+    Action(serde_json::Value),
+    Error(String),
+}
+
+/// Request sent over the WebSocket in place of an HTTP `/info` or `/exchange` call
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "payload", rename_all = "camelCase")]
+pub enum PostRequest {
+    Info(serde_json::Value),
+    Action(serde_json::Value),
+}
+
 // WebSocket protocol messages
 #[derive(Debug, Serialize)]
 pub struct WsRequest {
     pub method: &'static str,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subscription: Option<Subscription>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<PostRequest>,
 }
 
 impl WsRequest {
@@ -298,6 +448,8 @@ impl WsRequest {
         Self {
             method: "subscribe",
             subscription: Some(subscription),
+            id: None,
+            request: None,
         }
     }
 
@@ -305,6 +457,8 @@ impl WsRequest {
         Self {
             method: "unsubscribe",
             subscription: Some(subscription),
+            id: None,
+            request: None,
         }
     }
 
@@ -312,6 +466,156 @@ impl WsRequest {
         Self {
             method: "ping",
             subscription: None,
+            id: None,
+            request: None,
+        }
+    }
+
+    pub fn post(id: u64, request: PostRequest) -> Self {
+        Self {
+            method: "post",
+            subscription: None,
+            id: Some(id),
+            request: Some(request),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Message {
+        let mut bytes = json.as_bytes().to_vec();
+        simd_json::from_slice(&mut bytes).unwrap()
+    }
+
+    #[test]
+    fn test_l2_book_routing_by_coin() {
+        let message = parse(
+            r#"{"channel":"l2Book","data":{"coin":"ETH","time":1,"levels":[[],[]]}}"#,
+        );
+
+        let btc = Subscription::L2Book {
+            coin: "BTC".to_string(),
+        };
+        let eth = Subscription::L2Book {
+            coin: "ETH".to_string(),
+        };
+
+        assert!(!btc.matches(&message));
+        assert!(eth.matches(&message));
+        assert!(!Subscription::AllMids.matches(&message));
+    }
+
+    #[test]
+    fn test_trades_and_candle_routing() {
+        let trades = parse(
+            r#"{"channel":"trades","data":[{"coin":"BTC","side":"B","px":"1","sz":"1","time":1,"hash":"0x","tid":1}]}"#,
+        );
+        assert!(Subscription::Trades {
+            coin: "BTC".to_string()
+        }
+        .matches(&trades));
+        assert!(!Subscription::Trades {
+            coin: "ETH".to_string()
+        }
+        .matches(&trades));
+
+        let candle = parse(
+            r#"{"channel":"candle","data":{"T":2,"c":"1","h":"1","i":"1m","l":"1","n":1,"o":"1","s":"BTC","t":1,"v":"1"}}"#,
+        );
+        assert!(Subscription::Candle {
+            coin: "BTC".to_string(),
+            interval: "1m".to_string()
+        }
+        .matches(&candle));
+        assert!(!Subscription::Candle {
+            coin: "BTC".to_string(),
+            interval: "1h".to_string()
+        }
+        .matches(&candle));
+    }
+
+    #[test]
+    fn test_user_routing_by_address() {
+        let user = Address::new([1u8; 20]);
+        let other = Address::new([2u8; 20]);
+        let message = parse(&format!(
+            r#"{{"channel":"userFills","data":{{"user":"{user:#x}","fills":[]}}}}"#
+        ));
+
+        assert!(Subscription::UserFills { user }.matches(&message));
+        assert!(!Subscription::UserFills { user: other }.matches(&message));
+        assert!(!Subscription::UserFundings { user }.matches(&message));
+    }
+
+    #[test]
+    fn test_twap_messages() {
+        let user = Address::new([1u8; 20]);
+        let fills = parse(&format!(
+            r#"{{"channel":"userTwapSliceFills","data":{{"user":"{user:#x}","twapSliceFills":[
+                {{"fill":{{"coin":"BTC","side":"B","px":"50000","sz":"0.01","time":1,"hash":"0x","startPosition":"0","dir":"Open Long","closedPnl":"0","oid":5,"crossed":true,"fee":"0.1","feeToken":"USDC","tid":9}},"twapId":3}}
+            ]}}}}"#
+        ));
+        assert!(Subscription::UserTwapSliceFills { user }.matches(&fills));
+        assert!(!Subscription::UserFills { user }.matches(&fills));
+        let Message::UserTwapSliceFills(fills) = fills else {
+            panic!("expected TWAP slice fills");
+        };
+        assert_eq!(fills.data.twap_slice_fills[0].twap_id, 3);
+
+        let history = parse(&format!(
+            r#"{{"channel":"userTwapHistory","data":{{"user":"{user:#x}","isSnapshot":true,"history":[
+                {{"time":1,"twapId":3,"state":{{"coin":"BTC","user":"{user:#x}","side":"B","sz":"0.1","executedSz":"0.01","executedNtl":"500","minutes":10,"reduceOnly":false,"randomize":true,"timestamp":1}},
+                 "status":{{"status":"activated"}}}}
+            ]}}}}"#
+        ));
+        assert!(Subscription::UserTwapHistory { user }.matches(&history));
+        let Message::UserTwapHistory(history) = history else {
+            panic!("expected TWAP history");
+        };
+        assert_eq!(history.data.history[0].state.minutes, 10);
+        assert_eq!(history.data.history[0].status.status, "activated");
+    }
+
+    #[test]
+    fn test_control_messages_not_routed() {
+        let pong = parse(r#"{"channel":"pong"}"#);
+        assert!(!Subscription::AllMids.matches(&pong));
+    }
+
+    #[test]
+    fn test_post_messages() {
+        let request = serde_json::to_value(WsRequest::post(
+            7,
+            PostRequest::Info(serde_json::json!({"type": "allMids"})),
+        ))
+        .unwrap();
+        assert_eq!(
+            request,
+            serde_json::json!({
+                "method": "post",
+                "id": 7,
+                "request": {"type": "info", "payload": {"type": "allMids"}}
+            })
+        );
+
+        let response = parse(
+            r#"{"channel":"post","data":{"id":7,"response":{"type":"info","payload":{"type":"allMids","data":{"mids":{}}}}}}"#,
+        );
+        let Message::Post(post) = &response else {
+            panic!("expected a post response");
+        };
+        assert_eq!(post.data.id, 7);
+        assert!(matches!(post.data.response, PostResponse::Info(_)));
+        assert!(!Subscription::AllMids.matches(&response));
+
+        let error = parse(
+            r#"{"channel":"post","data":{"id":8,"response":{"type":"error","payload":"bad request"}}}"#,
+        );
+        assert!(
+            matches!(error, Message::Post(post) if matches!(post.data.response, PostResponse::Error(ref e) if e == "bad request"))
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Sub-account, vault and staking actions
+
+mod common;
+
+use alloy::signers::local::PrivateKeySigner;
+use ferrofluid::{
+    constants::TIF_GTC,
+    types::{
+        info_types::DelegatorDelta, requests::OrderRequest,
+        responses::ExchangeResponseStatus,
+    },
+    ExchangeProvider, InfoProvider,
+};
+use rust_decimal_macros::dec;
+
+use common::setup;
+
+#[tokio::test]
+async fn test_sub_account_lifecycle() {
+    let (server, signer) = setup().await;
+    server.set_balance(signer.address(), "1000");
+    let info = InfoProvider::new(server.network());
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network());
+
+    assert!(info
+        .sub_accounts(signer.address())
+        .await
+        .unwrap()
+        .is_empty());
+
+    let created = exchange.create_sub_account("momentum").await.unwrap();
+    let sub_account = created
+        .into_result()
+        .unwrap()
+        .data
+        .unwrap()
+        .address
+        .unwrap();
+    let duplicate = exchange.create_sub_account("momentum").await.unwrap();
+    assert!(matches!(duplicate, ExchangeResponseStatus::Err(_)));
+
+    let transfer = exchange
+        .sub_account_transfer(sub_account, true, 250_000_000)
+        .await
+        .unwrap();
+    assert!(transfer.into_result().is_ok());
+    let body = &server.exchange_requests()[2].body["action"];
+    assert_eq!(body["subAccountUser"], format!("{sub_account:#x}"));
+    assert_eq!(body["usd"], 250_000_000);
+
+    let sub_accounts = info.sub_accounts(signer.address()).await.unwrap();
+    assert_eq!(sub_accounts.len(), 1);
+    assert_eq!(sub_accounts[0].name, "momentum");
+    assert_eq!(sub_accounts[0].sub_account_user, sub_account);
+    assert_eq!(sub_accounts[0].master, signer.address());
+    assert_eq!(sub_accounts[0].clearinghouse_state.withdrawable, dec!(250));
+
+    // Orders from the derived provider land on the sub-account
+    let strategy = exchange.for_sub_account(sub_account);
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    strategy.place_order(&order).await.unwrap();
+    assert_eq!(server.open_orders(sub_account).len(), 1);
+    assert!(server.open_orders(signer.address()).is_empty());
+
+    // Only the master may act for its sub-accounts
+    let other = PrivateKeySigner::random();
+    server.expect_signer(other.address());
+    let intruder = ExchangeProvider::for_network(other, server.network())
+        .for_sub_account(sub_account);
+    let rejected = intruder.place_order(&order).await.unwrap();
+    assert!(matches!(rejected, ExchangeResponseStatus::Err(_)));
+}
+
+#[tokio::test]
+async fn test_vault_lifecycle() {
+    let (server, leader) = setup().await;
+    server.set_balance(leader.address(), "1000");
+    let follower = PrivateKeySigner::random();
+    server.expect_signer(follower.address());
+    server.set_balance(follower.address(), "500");
+    let info = InfoProvider::new(server.network());
+    let exchange = ExchangeProvider::for_network(leader.clone(), server.network());
+    let following = ExchangeProvider::for_network(follower.clone(), server.network());
+
+    let created = exchange
+        .create_vault("Basis", "Funding capture", 200_000_000)
+        .await
+        .unwrap();
+    let vault = created
+        .into_result()
+        .unwrap()
+        .data
+        .unwrap()
+        .address
+        .unwrap();
+    let action = &server.exchange_requests()[0].body["action"];
+    assert_eq!(action["initialUsd"], 200_000_000);
+    assert_eq!(action["nonce"], server.exchange_requests()[0].body["nonce"]);
+
+    let deposit = following
+        .vault_transfer(vault, true, 300_000_000)
+        .await
+        .unwrap();
+    assert!(deposit.into_result().is_ok());
+
+    let details = info
+        .vault_details(vault, Some(follower.address()))
+        .await
+        .unwrap();
+    assert_eq!(details.name, "Basis");
+    assert_eq!(details.leader, leader.address());
+    assert_eq!(details.followers.len(), 2);
+    assert_eq!(details.leader_fraction, dec!(0.4));
+    assert_eq!(details.follower_state.unwrap().vault_equity, dec!(300));
+    assert!(details.allow_deposits);
+
+    let equities = info.user_vault_equities(follower.address()).await.unwrap();
+    assert_eq!(equities.len(), 1);
+    assert_eq!(equities[0].vault_address, vault);
+    assert_eq!(equities[0].equity, dec!(300));
+
+    // Settings left as None are sent as null
+    exchange
+        .vault_modify(vault, Some(false), None)
+        .await
+        .unwrap();
+    let action = &server.exchange_requests()[2].body["action"];
+    assert!(action["alwaysCloseOnWithdraw"].is_null());
+    let details = info.vault_details(vault, None).await.unwrap();
+    assert!(!details.allow_deposits);
+    assert!(details.follower_state.is_none());
+
+    let refused = following
+        .vault_transfer(vault, true, 1_000_000)
+        .await
+        .unwrap();
+    assert!(matches!(refused, ExchangeResponseStatus::Err(_)));
+    let refused = following.vault_distribute(vault, 1_000_000).await.unwrap();
+    assert!(matches!(refused, ExchangeResponseStatus::Err(_)));
+    let distributed = exchange.vault_distribute(vault, 1_000_000).await.unwrap();
+    assert!(distributed.into_result().is_ok());
+}
+
+#[tokio::test]
+async fn test_staking_actions_and_queries() {
+    let (server, signer) = setup().await;
+    let info = InfoProvider::new(server.network());
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network());
+    let validator: alloy::primitives::Address =
+        "0x5ac99df645f3414876c816caa18b2d234024b487"
+            .parse()
+            .unwrap();
+
+    // The mock verifies each user-signed action's EIP-712 signature
+    let deposited = exchange.staking_deposit(100_000_000).await.unwrap();
+    assert!(deposited.into_result().is_ok());
+    let delegated = exchange
+        .token_delegate(validator, 100_000_000, false)
+        .await
+        .unwrap();
+    assert!(delegated.into_result().is_ok());
+    let withdrawn = exchange.staking_withdraw(50_000_000).await.unwrap();
+    assert!(withdrawn.into_result().is_ok());
+
+    let requests = server.exchange_requests();
+    let action = &requests[1].body["action"];
+    assert_eq!(action["type"], "tokenDelegate");
+    assert_eq!(
+        action["validator"],
+        "0x5ac99df645f3414876c816caa18b2d234024b487"
+    );
+    assert_eq!(action["wei"], 100_000_000);
+    assert_eq!(action["isUndelegate"], false);
+    assert_eq!(requests[0].body["action"]["type"], "cDeposit");
+    assert_eq!(requests[2].body["action"]["type"], "cWithdraw");
+
+    server.set_info_response(
+        "delegations",
+        serde_json::json!([{
+            "validator": "0x5ac99df645f3414876c816caa18b2d234024b487",
+            "amount": "1.0",
+            "lockedUntilTimestamp": 1735466781353u64
+        }]),
+    );
+    server.set_info_response(
+        "delegatorSummary",
+        serde_json::json!({
+            "delegated": "1.0",
+            "undelegated": "0.0",
+            "totalPendingWithdrawal": "0.5",
+            "nPendingWithdrawals": 1
+        }),
+    );
+    server.set_info_response(
+        "delegatorHistory",
+        serde_json::json!([
+            {
+                "time": 1735380381353u64,
+                "hash": "0x55e1d5ae4d1d8bbd5d1b04183b42b6010b2a00e4f4d2c8fb6bd4a8ab4d6cc7e8",
+                "delta": {"delegate": {
+                    "validator": "0x5ac99df645f3414876c816caa18b2d234024b487",
+                    "amount": "1.0",
+                    "isUndelegate": false
+                }}
+            },
+            {
+                "time": 1735380381000u64,
+                "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "delta": {"withdrawal": {"amount": "0.5", "phase": "initiated"}}
+            }
+        ]),
+    );
+    server.set_info_response(
+        "delegatorRewards",
+        serde_json::json!([{
+            "time": 1736726400073u64,
+            "source": "delegation",
+            "totalAmount": "0.00012"
+        }]),
+    );
+
+    let user = signer.address();
+    let delegations = info.delegations(user).await.unwrap();
+    assert_eq!(delegations[0].validator, validator);
+    assert_eq!(delegations[0].amount, dec!(1));
+
+    let summary = info.delegator_summary(user).await.unwrap();
+    assert_eq!(summary.total_pending_withdrawal, dec!(0.5));
+    assert_eq!(summary.n_pending_withdrawals, 1);
+
+    let history = info.delegator_history(user).await.unwrap();
+    assert!(matches!(
+        history[0].delta,
+        DelegatorDelta::Delegate {
+            is_undelegate: false,
+            ..
+        }
+    ));
+    assert!(
+        matches!(&history[1].delta, DelegatorDelta::Withdrawal { phase, .. } if phase == "initiated")
+    );
+
+    let rewards = info.delegator_rewards(user).await.unwrap();
+    assert_eq!(rewards[0].total_amount, dec!(0.00012));
+}
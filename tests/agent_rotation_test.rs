@@ -0,0 +1,126 @@
+//! Agent approval, rotation and persistence
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use ferrofluid::{
+    constants::TIF_GTC,
+    providers::{
+        agent::{AgentConfig, AgentManager},
+        AgentStore, FileAgentStore, ManagedExchangeProvider,
+    },
+    types::{requests::OrderRequest, responses::ExchangeDataStatus},
+    HyperliquidError,
+};
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use common::{first_status, setup};
+
+#[tokio::test]
+async fn test_managed_orders_signed_by_agent() {
+    let (server, signer) = setup().await;
+    let exchange = ManagedExchangeProvider::builder(signer.clone())
+        .with_network(server.network())
+        .build()
+        .await
+        .unwrap();
+
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    for _ in 0..2 {
+        let status = first_status(exchange.place_order_immediate(&order).await.unwrap());
+        assert!(matches!(status, ExchangeDataStatus::Resting(_)));
+    }
+
+    let agents = server.approved_agents();
+    assert_eq!(agents.len(), 1);
+    let (agent, user) = agents[0];
+    assert_eq!(user, signer.address());
+
+    // Only the approval is signed by the master key
+    let requests = server.exchange_requests();
+    assert_eq!(requests[0].body["action"]["type"], "approveAgent");
+    assert_eq!(requests[0].signer, Some(signer.address()));
+    for request in &requests[1..] {
+        assert_eq!(request.signer, Some(agent));
+    }
+    assert!(requests[2].body["nonce"].as_u64() > requests[1].body["nonce"].as_u64());
+    assert_eq!(server.open_orders(signer.address()).len(), 2);
+}
+
+#[tokio::test]
+async fn test_rotation_replaces_stored_agent() {
+    let (server, signer) = setup().await;
+    let dir = std::env::temp_dir().join(format!("ferrofluid-agents-{}", Uuid::new_v4()));
+    let store = Arc::new(FileAgentStore::new(&dir, "passphrase").unwrap());
+    // Every call rotates
+    let config = AgentConfig {
+        ttl: Duration::ZERO,
+        ..AgentConfig::default()
+    };
+    let manager =
+        AgentManager::with_store(signer, config, server.network(), store.clone())
+            .await
+            .unwrap();
+
+    let first = manager.get_or_rotate_agent("default").await.unwrap();
+    let second = manager.get_or_rotate_agent("default").await.unwrap();
+    assert_ne!(first.address, second.address);
+
+    let stored = store.load().unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].1.address, second.address);
+    // The index and the new agent's key are all that is left
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_rejected_approval_installs_no_agent() {
+    let (server, signer) = setup().await;
+    let manager = AgentManager::new(signer, AgentConfig::default(), server.network());
+
+    server.reject_next_exchange("Too many agents");
+    assert!(matches!(
+        manager.get_or_rotate_agent("default").await,
+        Err(HyperliquidError::Unauthorized(_))
+    ));
+    assert!(manager.get_active_agents().await.is_empty());
+
+    // The next approval goes through
+    let agent = manager.get_or_rotate_agent("default").await.unwrap();
+    let active = manager.get_active_agents().await;
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].1.address, agent.address);
+}
+
+#[tokio::test]
+async fn test_rejected_approval_is_not_stored() {
+    let (server, signer) = setup().await;
+    let dir = std::env::temp_dir().join(format!("ferrofluid-agents-{}", Uuid::new_v4()));
+    let store = Arc::new(FileAgentStore::new(&dir, "passphrase").unwrap());
+    let manager = AgentManager::with_store(
+        signer.clone(),
+        AgentConfig::default(),
+        server.network(),
+        store.clone(),
+    )
+    .await
+    .unwrap();
+
+    server.reject_next_exchange("Too many agents");
+    assert!(manager.get_or_rotate_agent("default").await.is_err());
+    assert!(store.load().unwrap().is_empty());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    // A restart has nothing to reload
+    let manager =
+        AgentManager::with_store(signer, AgentConfig::default(), server.network(), store)
+            .await
+            .unwrap();
+    assert!(manager.get_active_agents().await.is_empty());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,168 @@
+//! Order batching through the managed exchange provider
+
+mod common;
+
+use std::time::Duration;
+
+use ferrofluid::{
+    constants::TIF_GTC,
+    providers::{BatchConfig, ManagedExchangeProvider, OrderHandle},
+    types::{
+        requests::OrderRequest, responses::ExchangeDataStatus,
+        responses::ExchangeRejectReason, responses::ExchangeResponseStatus,
+    },
+};
+use rust_decimal_macros::dec;
+
+use common::{first_status, requests_of_type, setup};
+
+#[tokio::test]
+async fn test_managed_provider_batches_orders() {
+    let (server, signer) = setup().await;
+    let exchange = ManagedExchangeProvider::builder(signer.clone())
+        .with_network(server.network())
+        .with_auto_batching(Duration::from_millis(50))
+        .build()
+        .await
+        .unwrap();
+
+    let mut handles = Vec::new();
+    for px in [dec!(49000), dec!(48000)] {
+        let order = OrderRequest::limit(0, true, px, dec!(0.01), TIF_GTC);
+        handles.push(exchange.place_order(&order).await.unwrap());
+    }
+
+    for handle in handles {
+        match handle {
+            OrderHandle::Pending { mut rx, .. } => {
+                let response = rx.recv().await.unwrap().unwrap();
+                assert!(response.is_ok());
+            }
+            OrderHandle::Immediate(_) => panic!("expected batched order"),
+        }
+    }
+
+    assert_eq!(server.open_orders(signer.address()).len(), 2);
+    // The managed provider approved an agent before trading
+    assert_eq!(server.approved_agents().len(), 1);
+
+    exchange.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_managed_batch_reports_per_order_status() {
+    let (server, signer) = setup().await;
+    let exchange = ManagedExchangeProvider::builder(signer.clone())
+        .with_network(server.network())
+        .with_auto_batching(Duration::from_millis(50))
+        .build()
+        .await
+        .unwrap();
+
+    let resting = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    let rejected =
+        OrderRequest::limit(0, false, dec!(51000), dec!(0.01), TIF_GTC).reduce_only(true);
+
+    // Queue both before the batch interval elapses so they share a request
+    let mut handles = Vec::new();
+    for order in [resting, rejected] {
+        handles.push(exchange.place_order(&order).await.unwrap());
+    }
+
+    let mut statuses = Vec::new();
+    for handle in handles {
+        match handle {
+            OrderHandle::Pending { mut rx, .. } => {
+                let response = rx.recv().await.unwrap().unwrap();
+                let mut data = response.into_result().unwrap().data.unwrap();
+                assert_eq!(data.statuses.len(), 1);
+                statuses.push(data.statuses.remove(0));
+            }
+            OrderHandle::Immediate(_) => panic!("expected batched order"),
+        }
+    }
+
+    assert!(matches!(statuses[0], ExchangeDataStatus::Resting(_)));
+    assert_eq!(
+        statuses[1].reject_reason(),
+        Some(ExchangeRejectReason::ReduceOnlyViolation)
+    );
+    let orders: Vec<_> = server
+        .exchange_requests()
+        .into_iter()
+        .filter(|request| request.body["action"]["type"] == "order")
+        .collect();
+    assert_eq!(orders.len(), 1);
+
+    exchange.shutdown().await;
+}
+
+async fn batched(handle: OrderHandle) -> ExchangeResponseStatus {
+    match handle {
+        OrderHandle::Pending { mut rx, .. } => rx.recv().await.unwrap().unwrap(),
+        OrderHandle::Immediate(_) => panic!("expected batched request"),
+    }
+}
+
+#[tokio::test]
+async fn test_managed_batches_cancels() {
+    let (server, signer) = setup().await;
+    let exchange = ManagedExchangeProvider::builder(signer.clone())
+        .with_network(server.network())
+        .with_auto_batching(Duration::from_millis(50))
+        .build()
+        .await
+        .unwrap();
+
+    let mut oids = Vec::new();
+    for px in [dec!(49000), dec!(48000)] {
+        let order = OrderRequest::limit(0, true, px, dec!(0.01), TIF_GTC);
+        let status = first_status(exchange.place_order_immediate(&order).await.unwrap());
+        oids.push(status.order_id().unwrap());
+    }
+
+    let mut handles = Vec::new();
+    for oid in oids {
+        handles.push(exchange.cancel_order(0, oid).await.unwrap());
+    }
+    for handle in handles {
+        let status = first_status(batched(handle).await);
+        assert!(matches!(status, ExchangeDataStatus::Success));
+    }
+
+    assert!(server.open_orders(signer.address()).is_empty());
+    assert_eq!(requests_of_type(&server, "cancel"), 1);
+
+    exchange.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_managed_batch_size_and_shutdown_flush() {
+    let (server, signer) = setup().await;
+    let exchange = ManagedExchangeProvider::builder(signer.clone())
+        .with_network(server.network())
+        .with_batch_config(BatchConfig {
+            interval: Duration::from_secs(3600),
+            max_batch_size: 2,
+            max_wait_time: Duration::from_secs(3600),
+            ..Default::default()
+        })
+        .build()
+        .await
+        .unwrap();
+
+    let mut handles = Vec::new();
+    for px in [dec!(49000), dec!(48000), dec!(47000)] {
+        let order = OrderRequest::limit(0, true, px, dec!(0.01), TIF_GTC);
+        handles.push(exchange.place_order(&order).await.unwrap());
+    }
+
+    // Shutting down sends what is still queued instead of dropping it
+    exchange.clone().shutdown().await;
+    for handle in handles {
+        assert!(batched(handle).await.is_ok());
+    }
+
+    assert_eq!(server.open_orders(signer.address()).len(), 3);
+    assert_eq!(requests_of_type(&server, "order"), 2);
+}
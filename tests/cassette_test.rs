@@ -0,0 +1,102 @@
+//! Recording and replaying transport cassettes
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use ferrofluid::{
+    constants::TIF_GTC,
+    providers::{
+        Cassette, CassetteRecorder, HyperTransport, RecordingTransport, ReplayTransport,
+        Transport,
+    },
+    types::requests::OrderRequest,
+    ExchangeProvider, InfoProvider, ManagedWsProvider, WsConfig,
+};
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use common::{eventually, first_status, setup};
+
+#[tokio::test]
+async fn test_cassette_record_and_replay() {
+    let (server, signer) = setup().await;
+    let network = server.network();
+    let path = std::env::temp_dir()
+        .join(format!("ferrofluid-cassette-{}.jsonl", Uuid::new_v4()));
+
+    // Record a session against the server
+    let recorder = Arc::new(CassetteRecorder::create(&path).unwrap());
+    let transport: Arc<dyn Transport> = Arc::new(RecordingTransport::new(
+        Arc::new(HyperTransport::new()),
+        recorder.clone(),
+    ));
+    let info = InfoProvider::new(network.clone()).with_transport(transport.clone());
+    let exchange = ExchangeProvider::for_network(signer.clone(), network.clone())
+        .with_transport(transport);
+    let ws_config = WsConfig {
+        ping_interval: Duration::ZERO,
+        auto_reconnect: false,
+        ..WsConfig::default()
+    };
+    let ws = ManagedWsProvider::connect(
+        network.clone(),
+        WsConfig {
+            recorder: Some(recorder),
+            ..ws_config.clone()
+        },
+    )
+    .await
+    .unwrap();
+    let (_, mut rx) = ws.subscribe_all_mids().await.unwrap();
+    ws.start_reading().await.unwrap();
+    eventually(|| server.ws_subscriptions().len() == 1).await;
+
+    let mids = info.all_mids().await.unwrap();
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    let resting = first_status(exchange.place_order(&order).await.unwrap());
+    server.reject_next_exchange("Too many pending requests");
+    let rejected = exchange.place_order(&order).await.unwrap();
+    server.push_ws(
+        serde_json::json!({"channel": "allMids", "data": {"mids": {"BTC": "1"}}}),
+    );
+    let pushed = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    drop(ws);
+    drop(server);
+
+    // Replay it with no server at all
+    let cassette = Arc::new(Cassette::load(&path).unwrap());
+    let replay = Arc::new(ReplayTransport::new(&cassette));
+    let info = InfoProvider::new(network.clone()).with_transport(replay.clone());
+    let exchange = ExchangeProvider::for_network(signer, network.clone())
+        .with_transport(replay.clone());
+
+    assert_eq!(info.all_mids().await.unwrap(), mids);
+    let replayed = first_status(exchange.place_order(&order).await.unwrap());
+    assert_eq!(format!("{replayed:?}"), format!("{resting:?}"));
+    let replayed = exchange.place_order(&order).await.unwrap();
+    assert_eq!(replayed.error(), rejected.error());
+    assert_eq!(replay.unplayed(), 0);
+
+    let ws = ManagedWsProvider::connect(
+        network,
+        WsConfig {
+            replay: Some(cassette),
+            ..ws_config
+        },
+    )
+    .await
+    .unwrap();
+    let (_, mut rx) = ws.subscribe_all_mids().await.unwrap();
+    ws.start_reading().await.unwrap();
+    let replayed = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(format!("{replayed:?}"), format!("{pushed:?}"));
+
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,55 @@
+//! Shared setup for the mock server integration tests
+
+#![allow(dead_code)]
+
+use std::{sync::Once, time::Duration};
+
+use alloy::signers::local::PrivateKeySigner;
+use ferrofluid::{
+    testkit::MockServer,
+    types::responses::{ExchangeDataStatus, ExchangeResponseStatus},
+};
+
+static INIT: Once = Once::new();
+
+pub fn init_crypto() {
+    INIT.call_once(|| {
+        rustls::crypto::CryptoProvider::install_default(
+            rustls::crypto::aws_lc_rs::default_provider(),
+        )
+        .expect("Failed to install rustls crypto provider");
+    });
+}
+
+pub async fn setup() -> (MockServer, PrivateKeySigner) {
+    init_crypto();
+    let server = MockServer::start().await.unwrap();
+    let signer = PrivateKeySigner::random();
+    server.expect_signer(signer.address());
+    server.add_perp("BTC", 5, 50);
+    server.set_mid("BTC", "50000");
+    (server, signer)
+}
+
+pub fn first_status(response: ExchangeResponseStatus) -> ExchangeDataStatus {
+    let response = response.into_result().expect("exchange rejected request");
+    response.data.expect("missing statuses").statuses[0].clone()
+}
+
+pub async fn eventually(mut condition: impl FnMut() -> bool) {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("condition not met in time");
+}
+
+pub fn requests_of_type(server: &MockServer, action: &str) -> usize {
+    server
+        .exchange_requests()
+        .into_iter()
+        .filter(|request| request.body["action"]["type"] == action)
+        .count()
+}
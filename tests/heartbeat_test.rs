@@ -0,0 +1,61 @@
+//! Scheduled cancels and the managed cancel heartbeat
+
+mod common;
+
+use std::time::Duration;
+
+use ferrofluid::{
+    providers::ManagedExchangeProvider, ExchangeProvider, HyperliquidError,
+};
+
+use common::{eventually, requests_of_type, setup};
+
+#[tokio::test]
+async fn test_managed_cancel_heartbeat() {
+    let (server, signer) = setup().await;
+    let exchange = ManagedExchangeProvider::builder(signer.clone())
+        .with_network(server.network())
+        .with_cancel_heartbeat(Duration::from_millis(50), Duration::from_secs(10))
+        .build()
+        .await
+        .unwrap();
+
+    eventually(|| requests_of_type(&server, "scheduleCancel") >= 2).await;
+    exchange.clone().shutdown().await;
+
+    let schedules: Vec<_> = server
+        .exchange_requests()
+        .into_iter()
+        .filter(|request| request.body["action"]["type"] == "scheduleCancel")
+        .collect();
+    let agent = server.approved_agents()[0].0;
+    for schedule in &schedules {
+        assert_eq!(schedule.signer, Some(agent));
+        let time = schedule.body["action"]["time"].as_u64().unwrap();
+        let nonce = schedule.body["nonce"].as_u64().unwrap();
+        assert!(time >= nonce + 9_000);
+    }
+
+    // The heartbeat stops on shutdown
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(requests_of_type(&server, "scheduleCancel"), schedules.len());
+
+    // Scheduling too close to now is refused up front
+    let result = ManagedExchangeProvider::builder(signer)
+        .with_network(server.network())
+        .with_cancel_heartbeat(Duration::from_secs(1), Duration::from_secs(2))
+        .build()
+        .await;
+    assert!(matches!(result, Err(HyperliquidError::InvalidRequest(_))));
+}
+
+#[tokio::test]
+async fn test_clear_schedule_cancel() {
+    let (server, signer) = setup().await;
+    let exchange = ExchangeProvider::for_network(signer, server.network());
+
+    assert!(exchange.schedule_cancel(None).await.unwrap().is_ok());
+    let action = &server.exchange_requests()[0].body["action"];
+    assert_eq!(action["type"], "scheduleCancel");
+    assert!(action.get("time").is_none());
+}
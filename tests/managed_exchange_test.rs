@@ -8,6 +8,7 @@ use ferrofluid::{
     providers::{ManagedExchangeProvider, OrderHandle},
     types::requests::{Limit, OrderRequest, OrderType},
 };
+use rust_decimal_macros::dec;
 
 #[tokio::test]
 async fn test_managed_provider_creation() {
@@ -52,8 +53,8 @@ async fn test_managed_provider_with_batching() {
     let order = OrderRequest {
         asset: 0,
         is_buy: true,
-        limit_px: "50000".to_string(),
-        sz: "0.01".to_string(),
+        limit_px: dec!(50000),
+        sz: dec!(0.01),
         reduce_only: false,
         order_type: OrderType::Limit(Limit {
             tif: TIF_GTC.to_string(),
@@ -79,8 +80,8 @@ async fn test_alo_order_detection() {
     let order = OrderRequest {
         asset: 0,
         is_buy: true,
-        limit_px: "50000".to_string(),
-        sz: "0.01".to_string(),
+        limit_px: dec!(50000),
+        sz: dec!(0.01),
         reduce_only: false,
         order_type: OrderType::Limit(Limit {
             tif: "Alo".to_string(),
@@ -93,8 +94,8 @@ async fn test_alo_order_detection() {
     let regular_order = OrderRequest {
         asset: 0,
         is_buy: true,
-        limit_px: "50000".to_string(),
-        sz: "0.01".to_string(),
+        limit_px: dec!(50000),
+        sz: dec!(0.01),
         reduce_only: false,
         order_type: OrderType::Limit(Limit {
             tif: "Gtc".to_string(),
@@ -0,0 +1,165 @@
+//! Order matching and signature checks of the in-process mock server
+
+mod common;
+
+use alloy::signers::local::PrivateKeySigner;
+use ferrofluid::{
+    constants::{TIF_ALO, TIF_GTC, TIF_IOC},
+    types::{
+        requests::OrderRequest, responses::ExchangeDataStatus,
+        responses::ExchangeRejectReason,
+    },
+    ExchangeProvider,
+};
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use common::{first_status, setup};
+
+#[tokio::test]
+async fn test_order_rests_then_fills_when_mid_crosses() {
+    let (server, signer) = setup().await;
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network());
+
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    let status = first_status(exchange.place_order(&order).await.unwrap());
+    let oid = match status {
+        ExchangeDataStatus::Resting(resting) => resting.oid,
+        other => panic!("expected resting order, got {other:?}"),
+    };
+
+    let open = server.open_orders(signer.address());
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].oid, oid);
+
+    server.set_mid("BTC", "48000");
+    assert!(server.open_orders(signer.address()).is_empty());
+    let position = server.position(signer.address(), "BTC").unwrap();
+    assert_eq!(position.szi.to_string(), "0.01");
+
+    let requests = server.exchange_requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].signer, Some(signer.address()));
+}
+
+#[tokio::test]
+async fn test_crossing_orders_follow_time_in_force() {
+    let (server, signer) = setup().await;
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network());
+
+    let alo = OrderRequest::limit(0, true, dec!(51000), dec!(0.01), TIF_ALO);
+    match first_status(exchange.place_order(&alo).await.unwrap()) {
+        ExchangeDataStatus::Error(message) => {
+            assert!(message.starts_with("Post only order would have immediately matched"))
+        }
+        other => panic!("expected post-only rejection, got {other:?}"),
+    }
+
+    let ioc = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_IOC);
+    assert_eq!(
+        first_status(exchange.place_order(&ioc).await.unwrap()).reject_reason(),
+        Some(ExchangeRejectReason::IocNoMatch)
+    );
+
+    let taker = OrderRequest::limit(0, false, dec!(49000), dec!(0.02), TIF_IOC);
+    match first_status(exchange.place_order(&taker).await.unwrap()) {
+        ExchangeDataStatus::Filled(filled) => {
+            assert_eq!(filled.avg_px, dec!(50000));
+            assert_eq!(filled.total_sz, dec!(0.02));
+        }
+        other => panic!("expected fill, got {other:?}"),
+    }
+
+    let reduce =
+        OrderRequest::limit(0, false, dec!(49000), dec!(0.01), TIF_IOC).reduce_only(true);
+    assert_eq!(
+        first_status(exchange.place_order(&reduce).await.unwrap()).reject_reason(),
+        Some(ExchangeRejectReason::ReduceOnlyViolation)
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_signer_is_rejected() {
+    let (server, _) = setup().await;
+    let stranger = PrivateKeySigner::random();
+    let exchange = ExchangeProvider::for_network(stranger.clone(), server.network());
+
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    let response = exchange.place_order(&order).await.unwrap();
+
+    assert_eq!(
+        response.error(),
+        Some(
+            format!(
+                "User or API Wallet {:#x} does not exist.",
+                stranger.address()
+            )
+            .as_str()
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_cancel_by_oid_and_cloid() {
+    let (server, signer) = setup().await;
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network());
+
+    let first = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    let oid = first_status(exchange.place_order(&first).await.unwrap())
+        .order_id()
+        .unwrap();
+
+    let cloid = Uuid::new_v4();
+    let second = OrderRequest::limit(0, true, dec!(48000), dec!(0.01), TIF_GTC);
+    exchange
+        .place_order_with_cloid(second, cloid)
+        .await
+        .unwrap();
+    assert_eq!(server.open_orders(signer.address()).len(), 2);
+
+    let status = first_status(exchange.cancel_order(0, oid).await.unwrap());
+    assert!(status.is_success());
+    let status = first_status(exchange.cancel_order_by_cloid(0, cloid).await.unwrap());
+    assert!(status.is_success());
+    assert!(server.open_orders(signer.address()).is_empty());
+
+    let status = first_status(exchange.cancel_order(0, oid).await.unwrap());
+    assert!(matches!(status, ExchangeDataStatus::Error(_)));
+}
+
+#[tokio::test]
+async fn test_approved_agent_trades_for_master() {
+    let (server, master) = setup().await;
+    let agent = PrivateKeySigner::random();
+
+    let exchange = ExchangeProvider::for_network(master.clone(), server.network());
+    let response = exchange
+        .approve_agent(agent.address(), Some("bot".to_string()))
+        .await
+        .unwrap();
+    assert!(response.is_ok());
+    assert_eq!(
+        server.approved_agents(),
+        vec![(agent.address(), master.address())]
+    );
+
+    let agent_exchange = ExchangeProvider::for_network(agent.clone(), server.network());
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    let status = first_status(agent_exchange.place_order(&order).await.unwrap());
+    assert!(status.is_success());
+    assert_eq!(server.open_orders(master.address()).len(), 1);
+}
+
+#[tokio::test]
+async fn test_user_action_signature_is_verified() {
+    let (server, signer) = setup().await;
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network());
+
+    let destination = PrivateKeySigner::random().address();
+    let response = exchange.usd_transfer(destination, "10").await.unwrap();
+    assert!(response.is_ok());
+
+    let request = server.exchange_requests().pop().unwrap();
+    assert_eq!(request.body["action"]["type"], "usdSend");
+    assert_eq!(request.signer, Some(signer.address()));
+}
@@ -0,0 +1,427 @@
+//! Symbol resolution, the order builder and market helpers
+
+mod common;
+
+use std::sync::Arc;
+
+use ferrofluid::{
+    constants::TIF_GTC,
+    providers::AssetRegistry,
+    testkit::MockServer,
+    types::{
+        requests::Limit, requests::OrderType, requests::Trigger,
+        responses::ExchangeDataStatus, responses::TwapStatus, symbols::BTC,
+        AssetPrecision, RoundingMode,
+    },
+    ExchangeProvider, HyperliquidError, InfoProvider,
+};
+use rust_decimal_macros::dec;
+
+use common::{first_status, setup};
+
+#[tokio::test]
+async fn test_asset_registry_resolves_symbols() {
+    let (server, signer) = setup().await;
+    let purr = server.add_spot_pair("PURR", 0);
+    let info = Arc::new(InfoProvider::new(server.network()));
+    let registry = Arc::new(AssetRegistry::load(&info).await.unwrap());
+    assert_eq!(registry.resolve("@0").unwrap(), purr);
+    assert_eq!(registry.resolve("PURR/USDC").unwrap(), purr);
+
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network())
+        .with_asset_registry(registry.clone());
+
+    let status = first_status(
+        exchange
+            .order(BTC)
+            .limit_buy("49000", "0.01")
+            .send()
+            .await
+            .unwrap(),
+    );
+    let oid = status.order_id().unwrap();
+    assert_eq!(server.open_orders(signer.address())[0].asset, 0);
+
+    let status = first_status(exchange.cancel_order(0, oid).await.unwrap());
+    assert!(status.is_success());
+
+    assert!(matches!(
+        exchange.cancel_order("ETH", oid).await,
+        Err(HyperliquidError::AssetNotFound(_))
+    ));
+    server.add_perp("ETH", 4, 50);
+    registry.refresh(&info).await.unwrap();
+    assert_eq!(exchange.resolve_asset("ETH").unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_order_builder_normalizes_price_and_size() {
+    let (server, signer) = setup().await;
+    let info = InfoProvider::new(server.network());
+    let registry = Arc::new(AssetRegistry::load(&info).await.unwrap());
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network())
+        .with_asset_registry(registry);
+
+    let order = exchange
+        .order(BTC)
+        .limit_buy("49012.345", "0.0123456")
+        .rounding(RoundingMode::Down)
+        .build()
+        .unwrap();
+    assert_eq!(order.limit_px, dec!(49012));
+    assert_eq!(order.sz, dec!(0.01234));
+
+    let status = first_status(exchange.place_order(&order).await.unwrap());
+    assert!(status.is_success());
+    assert_eq!(
+        server.open_orders(signer.address())[0].limit_px.to_string(),
+        "49012"
+    );
+
+    let spot = exchange
+        .order(10_000)
+        .limit_sell("0.0000123456", "12.7")
+        .precision(AssetPrecision::spot(0))
+        .build()
+        .unwrap();
+    assert_eq!(spot.limit_px, dec!(0.00001235));
+    assert_eq!(spot.sz, dec!(13));
+}
+
+#[tokio::test]
+async fn test_order_builder_last_order_type_wins() {
+    let (server, signer) = setup().await;
+    let exchange = ExchangeProvider::for_network(signer, server.network());
+    let gtc = || {
+        OrderType::Limit(Limit {
+            tif: TIF_GTC.to_string(),
+        })
+    };
+
+    let order = exchange
+        .order(0)
+        .trigger_sell("48000", "0.01", "sl")
+        .order_type(gtc())
+        .limit_px("49000")
+        .build()
+        .unwrap();
+    assert!(matches!(order.order_type, OrderType::Limit(_)));
+
+    let order = exchange
+        .order(0)
+        .order_type(gtc())
+        .trigger_sell("48000", "0.01", "sl")
+        .build()
+        .unwrap();
+    assert_eq!(order.limit_px, dec!(48000));
+    assert!(matches!(
+        order.order_type,
+        OrderType::Trigger(Trigger {
+            is_market: true,
+            ..
+        })
+    ));
+
+    // Limit triggers keep their own flag and need a limit price
+    let limit_trigger = || {
+        OrderType::Trigger(Trigger {
+            trigger_px: dec!(48000),
+            is_market: false,
+            tpsl: "sl".to_string(),
+        })
+    };
+    let builder = || {
+        exchange
+            .order(0)
+            .sell()
+            .size("0.01")
+            .order_type(limit_trigger())
+    };
+    assert!(matches!(
+        builder().build(),
+        Err(HyperliquidError::InvalidRequest(_))
+    ));
+    let order = builder().limit_px("47900").build().unwrap();
+    assert!(matches!(
+        order.order_type,
+        OrderType::Trigger(Trigger {
+            is_market: false,
+            ..
+        })
+    ));
+    assert_eq!(order.limit_px, dec!(47900));
+}
+
+#[tokio::test]
+async fn test_market_open_and_close() {
+    let (server, signer) = setup().await;
+    let info = InfoProvider::new(server.network());
+    let registry = Arc::new(AssetRegistry::load(&info).await.unwrap());
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network())
+        .with_asset_registry(registry);
+
+    assert!(matches!(
+        exchange.market_close(BTC, None, dec!(0.05)).await,
+        Err(HyperliquidError::InvalidRequest(_))
+    ));
+    for slippage in [dec!(-0.01), dec!(1), dec!(1.5)] {
+        assert!(matches!(
+            exchange.market_open(BTC, true, dec!(0.01), slippage).await,
+            Err(HyperliquidError::InvalidRequest(_))
+        ));
+    }
+    assert!(server.exchange_requests().is_empty());
+
+    let status = first_status(
+        exchange
+            .market_open(BTC, true, dec!(0.0123456), dec!(0.05))
+            .await
+            .unwrap(),
+    );
+    assert!(matches!(status, ExchangeDataStatus::Filled(_)));
+    let order = &server.exchange_requests()[0].body["action"]["orders"][0];
+    assert_eq!(order["p"], "52500");
+    assert_eq!(order["s"], "0.01235");
+    assert_eq!(order["t"]["limit"]["tif"], "Ioc");
+    let position = server.position(signer.address(), "BTC").unwrap();
+    assert_eq!(position.szi, dec!(0.01235));
+
+    server.set_mid("BTC", "51000");
+    let status =
+        first_status(exchange.market_close(BTC, None, dec!(0.01)).await.unwrap());
+    assert!(matches!(status, ExchangeDataStatus::Filled(_)));
+    let order = &server.exchange_requests()[1].body["action"]["orders"][0];
+    assert_eq!(order["b"], false);
+    assert_eq!(order["p"], "50490");
+    assert_eq!(order["r"], true);
+    assert!(server.position(signer.address(), "BTC").is_none());
+}
+
+#[tokio::test]
+async fn test_place_and_cancel_twap() {
+    let (server, signer) = setup().await;
+    let info = InfoProvider::new(server.network());
+    let registry = Arc::new(AssetRegistry::load(&info).await.unwrap());
+    let exchange = ExchangeProvider::for_network(signer, server.network())
+        .with_asset_registry(registry);
+
+    let response = exchange
+        .place_twap(BTC, true, dec!(0.0123456), 30, true, false)
+        .await
+        .unwrap();
+    let twap = &server.exchange_requests()[0].body["action"]["twap"];
+    assert_eq!(twap["a"], 0);
+    assert_eq!(twap["s"], "0.01235");
+    assert_eq!(twap["m"], 30);
+    assert_eq!(twap["t"], true);
+
+    let status = response
+        .into_result()
+        .unwrap()
+        .data
+        .unwrap()
+        .status
+        .unwrap();
+    let twap_id = status.twap_id().unwrap();
+
+    let canceled = exchange.cancel_twap(BTC, twap_id).await.unwrap();
+    let status = canceled
+        .into_result()
+        .unwrap()
+        .data
+        .unwrap()
+        .status
+        .unwrap();
+    assert!(matches!(status, TwapStatus::Success));
+
+    let again = exchange.cancel_twap(BTC, twap_id).await.unwrap();
+    let status = again.into_result().unwrap().data.unwrap().status.unwrap();
+    assert!(matches!(status, TwapStatus::Error(_)));
+}
+
+#[tokio::test]
+async fn test_bracket_orders_are_grouped() {
+    let (server, signer) = setup().await;
+    let info = InfoProvider::new(server.network());
+    let registry = Arc::new(AssetRegistry::load(&info).await.unwrap());
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network())
+        .with_asset_registry(registry);
+
+    assert!(matches!(
+        exchange
+            .order(BTC)
+            .limit_buy("49000", "0.01")
+            .bracket()
+            .build(),
+        Err(HyperliquidError::InvalidRequest(_))
+    ));
+
+    // A resting entry leaves its triggers waiting
+    let resting = exchange
+        .order(BTC)
+        .limit_buy("49000", "0.01")
+        .bracket()
+        .take_profit("55000.4")
+        .stop_loss("45000")
+        .send()
+        .await
+        .unwrap();
+    assert!(matches!(
+        resting.entry,
+        Some(ExchangeDataStatus::Resting(_))
+    ));
+    assert!(matches!(
+        resting.take_profit,
+        Some(ExchangeDataStatus::WaitingForFill)
+    ));
+    assert!(matches!(
+        resting.stop_loss,
+        Some(ExchangeDataStatus::WaitingForFill)
+    ));
+
+    let action = &server.exchange_requests()[0].body["action"];
+    assert_eq!(action["grouping"], "normalTpsl");
+    let take_profit = &action["orders"][1];
+    assert_eq!(take_profit["b"], false);
+    assert_eq!(take_profit["r"], true);
+    assert_eq!(take_profit["s"], "0.01");
+    assert_eq!(take_profit["t"]["trigger"]["tpsl"], "tp");
+    assert_eq!(take_profit["t"]["trigger"]["triggerPx"], "55000");
+    assert_eq!(action["orders"][2]["t"]["trigger"]["tpsl"], "sl");
+
+    // A filled entry places them
+    let filled = exchange
+        .order(BTC)
+        .limit_buy("51000", "0.01")
+        .bracket()
+        .stop_loss("45000")
+        .send()
+        .await
+        .unwrap();
+    assert!(matches!(filled.entry, Some(ExchangeDataStatus::Filled(_))));
+    assert!(filled.take_profit.is_none());
+    assert!(matches!(
+        filled.stop_loss,
+        Some(ExchangeDataStatus::Resting(_))
+    ));
+
+    let protect = exchange
+        .position_tpsl(BTC, true, "0.01")
+        .take_profit("60000")
+        .stop_loss("40000")
+        .send()
+        .await
+        .unwrap();
+    assert!(protect.entry.is_none());
+    assert!(matches!(
+        protect.take_profit,
+        Some(ExchangeDataStatus::Resting(_))
+    ));
+    let action = &server.exchange_requests()[2].body["action"];
+    assert_eq!(action["grouping"], "positionTpsl");
+    assert_eq!(action["orders"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_cancel_all_and_flatten_all() {
+    let (server, signer) = setup().await;
+    server.add_perp("ETH", 4, 50);
+    server.set_mid("ETH", "3000");
+    let info = InfoProvider::new(server.network());
+    let registry = Arc::new(AssetRegistry::load(&info).await.unwrap());
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network())
+        .with_asset_registry(registry);
+
+    for px in ["49000", "48000"] {
+        exchange
+            .order(BTC)
+            .limit_buy(px, "0.01")
+            .send()
+            .await
+            .unwrap();
+    }
+    exchange
+        .order("ETH")
+        .limit_sell("3100", "0.1")
+        .send()
+        .await
+        .unwrap();
+
+    let report = exchange.cancel_all(Some(BTC)).await.unwrap();
+    assert!(report.is_complete());
+    assert_eq!(report.succeeded.len(), 2);
+    let open = server.open_orders(signer.address());
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].coin, "ETH");
+
+    server.reject_next_exchange("Too many pending requests");
+    let report = exchange.cancel_all(None).await.unwrap();
+    assert!(report.succeeded.is_empty());
+    assert_eq!(report.failed[0].0.coin, "ETH");
+    assert_eq!(report.failed[0].1, "Too many pending requests");
+    let report = exchange.cancel_all(None).await.unwrap();
+    assert_eq!(report.succeeded.len(), 1);
+    assert!(server.open_orders(signer.address()).is_empty());
+
+    exchange
+        .market_open(BTC, true, dec!(0.01), dec!(0.05))
+        .await
+        .unwrap();
+    exchange
+        .market_open("ETH", false, dec!(0.5), dec!(0.05))
+        .await
+        .unwrap();
+
+    let report = exchange.flatten_all(dec!(0.05)).await.unwrap();
+    assert!(report.is_complete());
+    assert_eq!(report.succeeded.len(), 2);
+    let requests = server.exchange_requests();
+    let orders = &requests.last().unwrap().body["action"]["orders"];
+    assert_eq!(orders.as_array().unwrap().len(), 2);
+    assert!(server.position(signer.address(), "BTC").is_none());
+    assert!(server.position(signer.address(), "ETH").is_none());
+
+    let report = exchange.flatten_all(dec!(0.05)).await.unwrap();
+    assert!(report.succeeded.is_empty() && report.is_complete());
+}
+
+#[tokio::test]
+async fn test_flatten_all_reports_partial_fills() {
+    let (server, signer) = setup().await;
+    server.add_perp("ETH", 4, 50);
+    server.set_mid("ETH", "3000");
+    let info = InfoProvider::new(server.network());
+    let registry = Arc::new(AssetRegistry::load(&info).await.unwrap());
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network())
+        .with_asset_registry(registry);
+
+    exchange
+        .market_open(BTC, true, dec!(0.02), dec!(0.05))
+        .await
+        .unwrap();
+    exchange
+        .market_open("ETH", false, dec!(0.5), dec!(0.05))
+        .await
+        .unwrap();
+    server.set_ioc_liquidity("BTC", "0.015");
+
+    let all_mids = |server: &MockServer| {
+        server
+            .requests()
+            .iter()
+            .filter(|request| request.body["type"] == "allMids")
+            .count()
+    };
+    let before = all_mids(&server);
+    let report = exchange.flatten_all(dec!(0.05)).await.unwrap();
+    assert_eq!(all_mids(&server) - before, 1);
+
+    assert!(!report.is_complete());
+    assert_eq!(report.succeeded.len(), 1);
+    assert_eq!(report.succeeded[0].coin, "ETH");
+    assert_eq!(report.failed[0].0.coin, "BTC");
+    assert_eq!(report.failed[0].1, "Partially filled: 0.015 of 0.02");
+    let btc = server.position(signer.address(), "BTC").unwrap();
+    assert_eq!(btc.szi, dec!(0.005));
+    assert!(server.position(signer.address(), "ETH").is_none());
+}
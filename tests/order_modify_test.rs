@@ -0,0 +1,147 @@
+//! Order tracking across exchange responses and modifies
+
+mod common;
+
+use ferrofluid::{
+    constants::TIF_GTC,
+    providers::order_tracker::OrderStatus,
+    types::{requests::OrderRequest, responses::ExchangeDataStatus},
+    ExchangeProvider,
+};
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use common::{first_status, setup};
+
+#[tokio::test]
+async fn test_order_tracking_records_exchange_response() {
+    let (server, signer) = setup().await;
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network())
+        .with_order_tracking();
+
+    let cloid = Uuid::new_v4();
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    exchange.place_order_with_cloid(order, cloid).await.unwrap();
+
+    let tracked = exchange.get_tracked_order(&cloid).unwrap();
+    assert_eq!(exchange.get_submitted_orders().len(), 1);
+    let status = first_status(tracked.response.unwrap());
+    assert!(matches!(status, ExchangeDataStatus::Resting(_)));
+
+    server.reject_next_exchange("Too many pending requests");
+    let failed = Uuid::new_v4();
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    let response = exchange
+        .place_order_with_cloid(order, failed)
+        .await
+        .unwrap();
+    assert_eq!(response.error(), Some("Too many pending requests"));
+    assert_eq!(server.open_orders(signer.address()).len(), 1);
+}
+
+#[tokio::test]
+async fn test_modify_by_cloid_follows_tracked_order() {
+    let (server, signer) = setup().await;
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network())
+        .with_order_tracking();
+
+    let cloid = Uuid::new_v4();
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    exchange.place_order_with_cloid(order, cloid).await.unwrap();
+
+    let repriced = OrderRequest::limit(0, true, dec!(48500), dec!(0.02), TIF_GTC);
+    let status = first_status(
+        exchange
+            .modify_order_by_cloid(cloid, repriced)
+            .await
+            .unwrap(),
+    );
+    assert!(matches!(status, ExchangeDataStatus::Resting(_)));
+
+    let modify = &server.exchange_requests()[1].body["action"]["modifies"][0];
+    assert_eq!(modify["oid"], format!("{:032x}", cloid.as_u128()));
+    let open = server.open_orders(signer.address());
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].limit_px, dec!(48500));
+
+    // The tracked entry now describes the modified order
+    let tracked = exchange.get_tracked_order(&cloid).unwrap();
+    assert_eq!(tracked.order.limit_px, dec!(48500));
+    assert_eq!(tracked.order.sz, dec!(0.02));
+    assert!(matches!(
+        first_status(tracked.response.unwrap()),
+        ExchangeDataStatus::Resting(_)
+    ));
+
+    // The modified order kept its cloid
+    let status = first_status(exchange.cancel_order_by_cloid(0, cloid).await.unwrap());
+    assert!(status.is_success());
+}
+
+#[tokio::test]
+async fn test_rejected_modify_keeps_tracked_order() {
+    let (server, signer) = setup().await;
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network())
+        .with_order_tracking();
+
+    let cloid = Uuid::new_v4();
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    exchange.place_order_with_cloid(order, cloid).await.unwrap();
+    let repriced = OrderRequest::limit(0, true, dec!(48500), dec!(0.02), TIF_GTC);
+
+    // A rejected request leaves the original order live and tracked as is
+    server.reject_next_exchange("Too many pending requests");
+    let response = exchange
+        .modify_order_by_cloid(cloid, repriced.clone())
+        .await
+        .unwrap();
+    assert!(response.error().is_some());
+    let tracked = exchange.get_tracked_order(&cloid).unwrap();
+    assert_eq!(tracked.status, OrderStatus::Submitted);
+    assert_eq!(tracked.order.limit_px, dec!(49000));
+    assert_eq!(server.open_orders(signer.address()).len(), 1);
+
+    // So does a modify whose own status is an error
+    let oid = server.open_orders(signer.address())[0].oid;
+    exchange.cancel_order(0, oid).await.unwrap();
+    let status = first_status(
+        exchange
+            .modify_order_by_cloid(cloid, repriced)
+            .await
+            .unwrap(),
+    );
+    assert!(matches!(status, ExchangeDataStatus::Error(_)));
+    let tracked = exchange.get_tracked_order(&cloid).unwrap();
+    assert_eq!(tracked.status, OrderStatus::Submitted);
+    assert_eq!(tracked.order.limit_px, dec!(49000));
+    assert_eq!(tracked.order.sz, dec!(0.01));
+}
+
+#[tokio::test]
+async fn test_modify_with_new_cloid_rekeys_tracked_order() {
+    let (server, signer) = setup().await;
+    let exchange =
+        ExchangeProvider::for_network(signer, server.network()).with_order_tracking();
+
+    let cloid = Uuid::new_v4();
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    exchange.place_order_with_cloid(order, cloid).await.unwrap();
+
+    let new_cloid = Uuid::new_v4();
+    let repriced = OrderRequest::limit(0, true, dec!(48500), dec!(0.01), TIF_GTC)
+        .with_cloid(Some(new_cloid));
+    let status = first_status(
+        exchange
+            .modify_order_by_cloid(cloid, repriced)
+            .await
+            .unwrap(),
+    );
+    assert!(status.is_success());
+
+    assert!(exchange.get_tracked_order(&cloid).is_none());
+    let tracked = exchange.get_tracked_order(&new_cloid).unwrap();
+    assert_eq!(tracked.cloid, new_cloid);
+    assert_eq!(tracked.status, OrderStatus::Submitted);
+    assert_eq!(tracked.order.limit_px, dec!(48500));
+    assert_eq!(exchange.tracked_order_count(), 1);
+}
@@ -7,6 +7,7 @@ mod tests {
         constants::TIF_GTC, signers::AlloySigner, types::requests::OrderRequest,
         ExchangeProvider,
     };
+    use rust_decimal_macros::dec;
     use uuid::Uuid;
 
     static INIT: Once = Once::new();
@@ -102,7 +103,7 @@ mod tests {
         let exchange = create_test_exchange();
 
         // Create a test order
-        let _order = OrderRequest::limit(0, true, "45000.0", "0.01", TIF_GTC);
+        let _order = OrderRequest::limit(0, true, dec!(45000.0), dec!(0.01), TIF_GTC);
 
         // Before placing, no orders tracked
         assert_eq!(exchange.tracked_order_count(), 0);
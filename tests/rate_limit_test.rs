@@ -0,0 +1,218 @@
+//! Shared rate limits, address budgets and retries
+
+mod common;
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use ferrofluid::{
+    constants::{
+        TIF_GTC, WEIGHT_ALL_MIDS, WEIGHT_BULK_ORDER, WEIGHT_EXCHANGE_DEFAULT,
+        WEIGHT_INFO_DEFAULT, WEIGHT_PLACE_ORDER,
+    },
+    providers::{
+        AddressBudgetConfig, LowBudgetPolicy, ManagedExchangeProvider, OrderHandle,
+        RateLimiter, RetryPolicy,
+    },
+    types::{requests::OrderRequest, responses::ExchangeDataStatus},
+    ExchangeProvider, HyperliquidError, InfoProvider, ManagedWsProvider, WsConfig,
+};
+use rust_decimal_macros::dec;
+
+use common::{first_status, setup};
+
+#[tokio::test]
+async fn test_shared_rate_limiter_and_ws_post() {
+    let (server, signer) = setup().await;
+    // No refill, so the remaining budget is exact
+    let limiter = Arc::new(RateLimiter::new(100, 0));
+
+    let info = InfoProvider::new(server.network()).with_rate_limiter(limiter.clone());
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network())
+        .with_rate_limiter(limiter.clone());
+    let config = WsConfig {
+        ping_interval: Duration::ZERO,
+        rate_limiter: Some(limiter.clone()),
+        ..WsConfig::default()
+    };
+    let ws = ManagedWsProvider::connect(server.network(), config)
+        .await
+        .unwrap();
+    ws.start_reading().await.unwrap();
+
+    info.all_mids().await.unwrap();
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    exchange.place_order(&order).await.unwrap();
+    exchange
+        .bulk_orders(vec![order.clone(), order])
+        .await
+        .unwrap();
+    exchange.update_leverage(0, true, 5).await.unwrap();
+    let mids: HashMap<String, rust_decimal::Decimal> = ws
+        .post_info(serde_json::json!({"type": "allMids"}))
+        .await
+        .unwrap();
+    assert_eq!(mids["BTC"], dec!(50000));
+
+    assert_eq!(
+        limiter.available(),
+        100 - 2 * WEIGHT_ALL_MIDS
+            - WEIGHT_PLACE_ORDER
+            - WEIGHT_BULK_ORDER
+            - WEIGHT_EXCHANGE_DEFAULT
+    );
+    let info_requests = server
+        .requests()
+        .into_iter()
+        .filter(|request| request.path == "/info")
+        .count();
+    assert_eq!(info_requests, 2);
+
+    // Without refill the budget runs out instead of waiting forever
+    let exhausted = Arc::new(RateLimiter::new(1, 0));
+    let info = InfoProvider::new(server.network()).with_rate_limiter(exhausted);
+    assert!(matches!(
+        info.all_mids().await,
+        Err(HyperliquidError::RateLimited {
+            available: 1,
+            required: WEIGHT_ALL_MIDS
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_address_budget_blocks_or_reserves() {
+    let (server, signer) = setup().await;
+    let user = signer.address();
+    server.set_requests_used(user, 9_950);
+
+    let limit = InfoProvider::new(server.network())
+        .user_rate_limit(user)
+        .await
+        .unwrap();
+    assert_eq!(limit.n_requests_used, 9_950);
+    assert_eq!(limit.remaining(), 50);
+
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    let blocking = ManagedExchangeProvider::builder(signer.clone())
+        .with_network(server.network())
+        .without_agent_rotation()
+        .with_address_budget(AddressBudgetConfig {
+            threshold: 100,
+            policy: LowBudgetPolicy::Block,
+            ..AddressBudgetConfig::default()
+        })
+        .build()
+        .await
+        .unwrap();
+    assert!(matches!(
+        blocking.place_order(&order).await,
+        Err(HyperliquidError::RateLimited {
+            available: 50,
+            required: 1
+        })
+    ));
+    assert!(server.exchange_requests().is_empty());
+
+    // Buying weight lifts the budget above the threshold again
+    let reserving = ManagedExchangeProvider::builder(signer)
+        .with_network(server.network())
+        .without_agent_rotation()
+        .with_address_budget(AddressBudgetConfig {
+            threshold: 100,
+            policy: LowBudgetPolicy::Block,
+            reserve_weight: Some(1_000),
+            ..AddressBudgetConfig::default()
+        })
+        .build()
+        .await
+        .unwrap();
+    let handle = reserving.place_order(&order).await.unwrap();
+    assert!(matches!(handle, OrderHandle::Immediate(Ok(_))));
+
+    let requests = server.exchange_requests();
+    assert_eq!(requests[0].body["action"]["type"], "reserveRequestWeight");
+    assert_eq!(requests[0].body["action"]["weight"], 1_000);
+    assert_eq!(requests[1].body["action"]["type"], "order");
+
+    let budget = reserving.address_budget().unwrap();
+    assert_eq!(budget.user(), user);
+    assert_eq!(budget.cap(), 11_000);
+    assert_eq!(budget.used(), 9_951);
+}
+
+#[tokio::test]
+async fn test_agent_approval_shares_managed_limits() {
+    let (server, signer) = setup().await;
+    let limiter = Arc::new(RateLimiter::new(100, 0));
+    let policy = RetryPolicy {
+        initial_backoff: Duration::from_millis(10),
+        ..RetryPolicy::default()
+    };
+
+    // The first approval attempt is throttled and retried
+    server.fail_next_exchange(429);
+    let exchange = ManagedExchangeProvider::builder(signer.clone())
+        .with_network(server.network())
+        .with_agent(Some("default".to_string()))
+        .with_rate_limiter(limiter.clone())
+        .with_retry_policy(policy)
+        .with_address_budget(AddressBudgetConfig::default())
+        .build()
+        .await
+        .unwrap();
+
+    let approved = server.approved_agents();
+    assert_eq!(approved.len(), 1);
+    assert_eq!(approved[0].1, signer.address());
+    assert_eq!(
+        limiter.available(),
+        100 - WEIGHT_INFO_DEFAULT - WEIGHT_EXCHANGE_DEFAULT
+    );
+    assert_eq!(exchange.address_budget().unwrap().used(), 1);
+}
+
+#[tokio::test]
+async fn test_retry_policy() {
+    let (server, signer) = setup().await;
+    let policy = RetryPolicy {
+        initial_backoff: Duration::from_millis(10),
+        ..RetryPolicy::default()
+    };
+    let info = InfoProvider::new(server.network()).with_retry_policy(policy.clone());
+
+    server.fail_next_info(503);
+    server.fail_next_info(502);
+    assert!(info.all_mids().await.unwrap().contains_key("BTC"));
+
+    // Client errors are not retried, so the request after it succeeds
+    server.fail_next_info(400);
+    assert!(matches!(
+        info.all_mids().await,
+        Err(HyperliquidError::Http { status: 400, .. })
+    ));
+    assert!(info.all_mids().await.is_ok());
+
+    let no_retry =
+        InfoProvider::new(server.network()).with_retry_policy(RetryPolicy::none());
+    server.fail_next_info(503);
+    assert!(no_retry.all_mids().await.is_err());
+
+    // A 429 means the action was not processed, so the same payload is resent
+    let exchange =
+        ExchangeProvider::for_network(signer, server.network()).with_retry_policy(policy);
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    server.fail_next_exchange(429);
+    assert!(matches!(
+        first_status(exchange.place_order(&order).await.unwrap()),
+        ExchangeDataStatus::Resting(_)
+    ));
+    assert_eq!(server.exchange_requests().len(), 1);
+
+    // A 5xx may have been processed, so it is never resent
+    server.fail_next_exchange(503);
+    assert!(matches!(
+        exchange.place_order(&order).await,
+        Err(HyperliquidError::Http { status: 503, .. })
+    ));
+    assert_eq!(server.exchange_requests().len(), 1);
+}
@@ -0,0 +1,138 @@
+//! Providers over an in-memory transport, without sockets
+
+use std::{collections::VecDeque, sync::Arc};
+
+use alloy::{
+    primitives::{address, Signature, U256},
+    signers::local::PrivateKeySigner,
+};
+use async_trait::async_trait;
+use ferrofluid::{
+    providers::{RetryPolicy, Transport, TransportError},
+    types::{actions::UsdSend, eip712::HyperliquidAction},
+    ExchangeProvider, HyperliquidError, InfoProvider,
+};
+use hyper::body::Bytes;
+use parking_lot::Mutex;
+use rust_decimal_macros::dec;
+use serde_json::{json, Value};
+
+/// Answers each request with the next queued response, recording what was sent
+#[derive(Default)]
+struct FakeTransport {
+    responses: Mutex<VecDeque<(u16, Value)>>,
+    sent: Mutex<Vec<(String, Value)>>,
+}
+
+impl FakeTransport {
+    fn respond(&self, status: u16, body: Value) {
+        self.responses.lock().push_back((status, body));
+    }
+
+    fn sent(&self) -> Vec<(String, Value)> {
+        self.sent.lock().clone()
+    }
+}
+
+#[async_trait]
+impl Transport for FakeTransport {
+    async fn post(&self, url: &str, body: Bytes) -> Result<(u16, Bytes), TransportError> {
+        self.sent
+            .lock()
+            .push((url.to_string(), serde_json::from_slice(&body).unwrap()));
+        let (status, body) = self
+            .responses
+            .lock()
+            .pop_front()
+            .ok_or_else(|| TransportError::NotSent("no response queued".into()))?;
+        Ok((status, Bytes::from(body.to_string())))
+    }
+}
+
+fn parse_u256(value: &Value) -> U256 {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_exchange_signing_over_fake_transport() {
+    let signer = PrivateKeySigner::random();
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond(
+        200,
+        json!({"status": "ok", "response": {"type": "default"}}),
+    );
+
+    let exchange =
+        ExchangeProvider::testnet(signer.clone()).with_transport(transport.clone());
+    let destination = address!("1234567890123456789012345678901234567890");
+    let result = exchange.usd_transfer(destination, "12.5").await.unwrap();
+    assert!(result.is_ok());
+
+    let sent = transport.sent();
+    assert_eq!(sent.len(), 1);
+    let (url, payload) = &sent[0];
+    assert!(url.ends_with("/exchange"));
+
+    // The signature must recover to the signer over the action that was sent
+    let action = &payload["action"];
+    assert_eq!(action["type"], "usdSend");
+    let usd_send = UsdSend {
+        signature_chain_id: u64::from_str_radix(
+            action["signatureChainId"]
+                .as_str()
+                .unwrap()
+                .trim_start_matches("0x"),
+            16,
+        )
+        .unwrap(),
+        hyperliquid_chain: action["hyperliquidChain"].as_str().unwrap().to_string(),
+        destination: action["destination"].as_str().unwrap().to_string(),
+        amount: action["amount"].as_str().unwrap().to_string(),
+        time: action["time"].as_u64().unwrap(),
+    };
+    assert_eq!(usd_send.hyperliquid_chain, "Testnet");
+    assert_eq!(usd_send.amount, "12.5");
+    assert_eq!(payload["nonce"].as_u64().unwrap(), usd_send.time);
+
+    let signature = &payload["signature"];
+    let signature = Signature::from_rs_and_parity(
+        parse_u256(&signature["r"]),
+        parse_u256(&signature["s"]),
+        signature["v"].as_u64().unwrap(),
+    )
+    .unwrap();
+    let hash = usd_send.eip712_signing_hash(&usd_send.domain());
+    assert_eq!(
+        signature.recover_address_from_prehash(&hash).unwrap(),
+        signer.address()
+    );
+}
+
+#[tokio::test]
+async fn test_info_over_fake_transport() {
+    let transport = Arc::new(FakeTransport::default());
+    let info = InfoProvider::testnet()
+        .with_transport(transport.clone())
+        .with_retry_policy(RetryPolicy::none());
+
+    transport.respond(200, json!({"BTC": "50000.5"}));
+    let mids = info.all_mids().await.unwrap();
+    assert_eq!(mids["BTC"], dec!(50000.5));
+    assert_eq!(transport.sent()[0].1, json!({"type": "allMids"}));
+
+    // Error statuses surface with their body
+    transport.respond(500, json!("boom"));
+    match info.all_mids().await {
+        Err(HyperliquidError::Http { status, body }) => {
+            assert_eq!(status, 500);
+            assert!(body.contains("boom"));
+        }
+        other => panic!("expected HTTP error, got {other:?}"),
+    }
+
+    // Transport failures surface as network errors
+    assert!(matches!(
+        info.all_mids().await,
+        Err(HyperliquidError::Network(_))
+    ));
+}
@@ -0,0 +1,92 @@
+//! WebSocket subscriptions against the mock server
+
+mod common;
+
+use std::time::Duration;
+
+use ferrofluid::{
+    constants::TIF_GTC,
+    testkit::MockServer,
+    types::{requests::OrderRequest, ws::Message, ws::Subscription},
+    ExchangeProvider, ManagedWsProvider, RawWsProvider, WsConfig,
+};
+use rust_decimal_macros::dec;
+
+use common::{eventually, init_crypto, setup};
+
+#[tokio::test]
+async fn test_raw_ws_receives_order_updates() {
+    let (server, signer) = setup().await;
+    let exchange = ExchangeProvider::for_network(signer.clone(), server.network());
+
+    let mut ws = RawWsProvider::connect(server.network()).await.unwrap();
+    ws.start_reading().await.unwrap();
+    let (_, mut rx) = ws
+        .subscribe(Subscription::OrderUpdates {
+            user: signer.address(),
+        })
+        .await
+        .unwrap();
+    eventually(|| server.ws_subscriptions().len() == 1).await;
+
+    let order = OrderRequest::limit(0, true, dec!(49000), dec!(0.01), TIF_GTC);
+    exchange.place_order(&order).await.unwrap();
+
+    let message = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    match message {
+        Message::OrderUpdates(updates) => {
+            assert_eq!(updates.data[0].status, "open");
+            assert_eq!(updates.data[0].order.coin, "BTC");
+        }
+        other => panic!("expected order update, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_managed_ws_replays_subscriptions_after_disconnect() {
+    init_crypto();
+    let server = MockServer::start().await.unwrap();
+    let config = WsConfig {
+        ping_interval: Duration::ZERO,
+        reconnect_delay: Duration::from_millis(50),
+        ..WsConfig::default()
+    };
+
+    let ws = ManagedWsProvider::connect(server.network(), config)
+        .await
+        .unwrap();
+    let (_, mut rx) = ws.subscribe_all_mids().await.unwrap();
+    ws.start_reading().await.unwrap();
+    eventually(|| server.ws_subscriptions().len() == 1).await;
+
+    let mids = serde_json::json!({"channel": "allMids", "data": {"mids": {"BTC": "1"}}});
+    server.push_ws(mids.clone());
+    let first = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(first, Message::AllMids(_)));
+
+    server.disconnect_ws_clients();
+    eventually(|| server.ws_client_count() == 0).await;
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !ws.is_connected().await || server.ws_subscriptions().len() != 1 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("managed provider did not reconnect");
+
+    server.push_ws(mids);
+    let replayed = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    match replayed {
+        Message::AllMids(all_mids) => assert_eq!(all_mids.data.mids["BTC"], dec!(1)),
+        other => panic!("expected mids, got {other:?}"),
+    }
+}